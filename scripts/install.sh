@@ -330,11 +330,12 @@ Description=Beeper Automations Service
 After=network.target
 
 [Service]
-Type=simple
+Type=notify
 User=$SERVICE_USER
 ExecStart=$INSTALL_DIR/$SERVICE_NAME
 Restart=on-failure
 RestartSec=10
+WatchdogSec=60
 StandardOutput=journal
 StandardError=journal
 
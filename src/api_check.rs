@@ -1,25 +1,146 @@
 use crate::app_state::SharedAppState;
 use beeper_desktop_api::BeeperClient;
 
+/// Outcome of checking the API connection, specific enough to tell the user what to fix
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiStatus {
+    Ok,
+    /// The token was rejected
+    Unauthorized,
+    /// Nothing answered at the URL, usually because Beeper Desktop isn't running
+    Unreachable,
+    /// The URL couldn't be parsed as an http(s) address
+    BadUrl,
+    Other(String),
+}
+
+impl ApiStatus {
+    pub fn is_valid(&self) -> bool {
+        *self == ApiStatus::Ok
+    }
+}
+
+impl std::fmt::Display for ApiStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiStatus::Ok => write!(f, "API connection OK"),
+            ApiStatus::Unauthorized => {
+                write!(f, "The API token was rejected. Check the token in Beeper Desktop's settings.")
+            }
+            ApiStatus::Unreachable => write!(
+                f,
+                "Could not reach the API. Is Beeper Desktop running with the API enabled?"
+            ),
+            ApiStatus::BadUrl => write!(
+                f,
+                "The API URL is not valid. It should look like http://localhost:23373"
+            ),
+            ApiStatus::Other(e) => write!(f, "API check failed: {}", e),
+        }
+    }
+}
+
+/// Whether a Beeper Desktop process is running. Only checked on Windows;
+/// `None` elsewhere or if the process list couldn't be read.
+pub fn is_beeper_running() -> Option<bool> {
+    #[cfg(windows)]
+    {
+        let output = std::process::Command::new("tasklist")
+            .args(["/FI", "IMAGENAME eq Beeper.exe", "/NH"])
+            .output()
+            .ok()?;
+        Some(
+            String::from_utf8_lossy(&output.stdout)
+                .to_lowercase()
+                .contains("beeper.exe"),
+        )
+    }
+
+    #[cfg(not(windows))]
+    {
+        None
+    }
+}
+
 /// Validate API credentials using the shared AppState
-pub async fn validate_api_with_state(state: &SharedAppState) -> bool {
+pub async fn validate_api_with_state(state: &SharedAppState) -> ApiStatus {
     let config = match state.get_config() {
         Ok(cfg) => cfg,
-        Err(_) => return false,
+        Err(e) => return ApiStatus::Other(e.to_string()),
     };
 
-    let client = BeeperClient::new(&config.api.token, &config.api.url);
-    match client.get_accounts().await {
-        Ok(_) => true,
-        Err(_) => false,
-    }
+    validate_api(&config.api.url, config.api.token.reveal()).await
 }
 
 /// Validate API credentials directly with url and token
-pub async fn validate_api(url: &str, token: &str) -> bool {
+pub async fn validate_api(url: &str, token: &str) -> ApiStatus {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+        _ => return ApiStatus::BadUrl,
+    }
+
     let client = BeeperClient::new(token, url);
     match client.get_accounts().await {
-        Ok(_) => true,
-        Err(_) => false,
+        Ok(_) => ApiStatus::Ok,
+        Err(e) => classify_error(&e.to_string()),
+    }
+}
+
+/// Whether a client error means the API token was rejected, as opposed to
+/// Beeper not answering
+pub fn is_unauthorized(message: &str) -> bool {
+    classify_error(message) == ApiStatus::Unauthorized
+}
+
+/// Sort a client error into a status by its message, since the client
+/// doesn't expose the HTTP status or connection failure separately
+fn classify_error(message: &str) -> ApiStatus {
+    let lower = message.to_lowercase();
+
+    if ["401", "403", "unauthorized", "forbidden"]
+        .iter()
+        .any(|needle| lower.contains(needle))
+    {
+        ApiStatus::Unauthorized
+    } else if [
+        "connection refused",
+        "error sending request",
+        "error trying to connect",
+        "dns error",
+        "timed out",
+    ]
+    .iter()
+    .any(|needle| lower.contains(needle))
+    {
+        ApiStatus::Unreachable
+    } else {
+        ApiStatus::Other(message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_error() {
+        assert_eq!(
+            classify_error("HTTP status client error (401 Unauthorized) for url"),
+            ApiStatus::Unauthorized
+        );
+        assert_eq!(
+            classify_error("error sending request for url (http://localhost:23373/v0/get-accounts)"),
+            ApiStatus::Unreachable
+        );
+        assert_eq!(
+            classify_error("unexpected response"),
+            ApiStatus::Other("unexpected response".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bad_url_is_rejected_before_connecting() {
+        assert_eq!(validate_api("localhost:23373", "token").await, ApiStatus::BadUrl);
+        assert_eq!(validate_api("not a url", "token").await, ApiStatus::BadUrl);
     }
 }
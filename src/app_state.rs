@@ -1,20 +1,104 @@
-use crate::config::Config;
-use beeper_desktop_api::BeeperClient;
-use std::sync::{Arc, RwLock};
+use crate::beeper_api::BeeperApi;
+use crate::config::{Config, ConfigError, ConfigFormat};
+use beeper_desktop_api::{BeeperClient, Chat};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Why a [`SharedAppState`] call failed
+#[derive(Error, Debug)]
+pub enum AppStateError {
+    #[error("Configuration error: {0}")]
+    Config(#[from] ConfigError),
+    /// The Beeper API call itself failed
+    #[error("{0}")]
+    Client(String),
+}
+
+/// The first page of `list_chats`, as cached by [`SharedAppState::get_chats_cached`]
+#[derive(Debug, Clone)]
+pub struct ChatList {
+    pub chats: Vec<Chat>,
+    pub oldest_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+/// A value that goes stale once it is older than the TTL it's read with
+struct TtlCache<T> {
+    entry: Option<(Instant, Arc<T>)>,
+}
+
+impl<T> TtlCache<T> {
+    fn new() -> Self {
+        Self { entry: None }
+    }
+
+    fn get(&self, ttl: Duration, now: Instant) -> Option<Arc<T>> {
+        self.entry
+            .as_ref()
+            .filter(|(stored_at, _)| now.saturating_duration_since(*stored_at) < ttl)
+            .map(|(_, value)| Arc::clone(value))
+    }
+
+    fn store(&mut self, value: T, now: Instant) -> Arc<T> {
+        let value = Arc::new(value);
+        self.entry = Some((now, Arc::clone(&value)));
+        value
+    }
+
+    fn clear(&mut self) {
+        self.entry = None;
+    }
+}
+
+// The locks below carry on past poisoning. A task that panics while holding
+// one leaves whatever it was writing in place, which at worst is a config
+// edit half applied. That beats every later call failing until a restart,
+// which is what honouring the poison would mean for a long-running service.
+
+fn read<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
 
 /// Application state shared across the entire app
 pub struct AppState {
     pub config: RwLock<Config>,
-    pub client: RwLock<BeeperClient>,
+    pub client: RwLock<Arc<dyn BeeperApi>>,
+    chat_list: Mutex<TtlCache<ChatList>>,
+    /// Automation last selected in the notification screen
+    last_automation_id: Mutex<Option<String>>,
+    /// Set while the last attempt to save the config failed
+    unsaved_changes: AtomicBool,
+    /// Why the config can't be written, while it can't
+    write_problem: Mutex<Option<String>>,
 }
 
 impl AppState {
     /// Create a new AppState with a configured client
     pub fn new(config: Config) -> Self {
-        let client = BeeperClient::new(&config.api.token, &config.api.url);
+        let client = BeeperClient::new(config.api.token.reveal(), &config.api.url);
+        Self::with_api(config, Arc::new(client))
+    }
+
+    /// Create an AppState that talks to `api` instead of a real client
+    pub fn with_api(config: Config, api: Arc<dyn BeeperApi>) -> Self {
         Self {
             config: RwLock::new(config),
-            client: RwLock::new(client),
+            client: RwLock::new(api),
+            chat_list: Mutex::new(TtlCache::new()),
+            last_automation_id: Mutex::new(None),
+            unsaved_changes: AtomicBool::new(false),
+            write_problem: Mutex::new(None),
         }
     }
 }
@@ -28,107 +112,165 @@ impl SharedAppState {
         SharedAppState(Arc::new(RwLock::new(AppState::new(config))))
     }
 
+    /// Create a SharedAppState backed by `api`, e.g. a mock in tests
+    pub fn with_api(config: Config, api: Arc<dyn BeeperApi>) -> Self {
+        SharedAppState(Arc::new(RwLock::new(AppState::with_api(config, api))))
+    }
+
     /// Clone the Arc for sharing across threads/tasks
     pub fn clone_arc(&self) -> Arc<RwLock<AppState>> {
         Arc::clone(&self.0)
     }
 
+    /// Read access to the state itself
+    fn state(&self) -> RwLockReadGuard<'_, AppState> {
+        read(&self.0)
+    }
+
     /// Update the API configuration and recreate the client
-    pub fn update_api(&self, url: String, token: String) -> Result<(), String> {
-        let state = self
-            .0
-            .write()
-            .map_err(|e| format!("Failed to acquire write lock: {}", e))?;
-        let mut config = state
-            .config
-            .write()
-            .map_err(|e| format!("Failed to acquire config write lock: {}", e))?;
+    pub fn update_api(&self, url: String, token: String) -> Result<(), AppStateError> {
+        let state = write(&self.0);
+        let mut config = write(&state.config);
         config.api.url = url.clone();
-        config.api.token = token.clone();
+        config.api.token = token.clone().into();
         drop(config); // Release the config lock before acquiring client lock
 
-        let mut client = state
-            .client
-            .write()
-            .map_err(|e| format!("Failed to acquire client write lock: {}", e))?;
-        *client = BeeperClient::new(&token, &url);
+        let mut client = write(&state.client);
+        *client = Arc::new(BeeperClient::new(&token, &url));
+        drop(client);
+
+        // Chats from the old connection don't apply anymore
+        lock(&state.chat_list).clear();
         Ok(())
     }
 
     /// Get a cloned config
-    pub fn get_config(&self) -> Result<Config, String> {
-        let state = self
-            .0
-            .read()
-            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
-        let config = state
-            .config
-            .read()
-            .map_err(|e| format!("Failed to acquire config read lock: {}", e))?;
+    pub fn get_config(&self) -> Result<Config, AppStateError> {
+        let state = self.state();
+        let config = read(&state.config);
         Ok(config.clone())
     }
 
     /// Execute a function with read-only access to the client
-    pub fn with_client<F, T>(&self, f: F) -> Result<T, String>
+    pub fn with_client<F, T>(&self, f: F) -> Result<T, AppStateError>
     where
-        F: FnOnce(&BeeperClient) -> T,
+        F: FnOnce(&dyn BeeperApi) -> T,
     {
-        let state = self
-            .0
-            .read()
-            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
-        let client = state
-            .client
-            .read()
-            .map_err(|e| format!("Failed to acquire client read lock: {}", e))?;
-        Ok(f(&client))
+        let state = self.state();
+        let client = read(&state.client);
+        Ok(f(client.as_ref()))
+    }
+
+    /// The current client, shared so it can be used across `.await` points
+    /// without holding any lock
+    pub fn client(&self) -> Result<Arc<dyn BeeperApi>, AppStateError> {
+        let state = self.state();
+        let client = read(&state.client);
+        Ok(Arc::clone(&client))
+    }
+
+    /// Run an async call against the client. Unlike [`Self::with_client`],
+    /// the call can be awaited directly from async code.
+    pub async fn with_client_async<F, Fut, T>(&self, f: F) -> Result<T, AppStateError>
+    where
+        F: FnOnce(Arc<dyn BeeperApi>) -> Fut,
+        Fut: std::future::Future<Output = T>,
+    {
+        let client = self.client()?;
+        Ok(f(client).await)
     }
 
     /// Execute a function with mutable access to the config
-    pub fn with_config_mut<F>(&self, f: F) -> Result<(), String>
+    pub fn with_config_mut<F>(&self, f: F) -> Result<(), AppStateError>
     where
         F: FnOnce(&mut Config) -> (),
     {
-        let state = self
-            .0
-            .read()
-            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
-        let mut config = state
-            .config
-            .write()
-            .map_err(|e| format!("Failed to acquire config write lock: {}", e))?;
+        let state = self.state();
+        let mut config = write(&state.config);
         f(&mut config);
         Ok(())
     }
 
     /// Execute a function with read-only access to the config
-    pub fn with_config<F, T>(&self, f: F) -> Result<T, String>
+    pub fn with_config<F, T>(&self, f: F) -> Result<T, AppStateError>
     where
         F: FnOnce(&Config) -> T,
     {
-        let state = self
-            .0
-            .read()
-            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
-        let config = state
-            .config
-            .read()
-            .map_err(|e| format!("Failed to acquire config read lock: {}", e))?;
+        let state = self.state();
+        let config = read(&state.config);
         Ok(f(&config))
     }
 
+    /// Write the config to disk. Until a save succeeds, the in-memory config
+    /// counts as ahead of the file for [`Self::has_unsaved_changes`].
+    pub fn save_config(&self) -> Result<(), AppStateError> {
+        let result = self
+            .get_config()
+            .and_then(|config| config.save().map_err(AppStateError::from));
+        let state = self.state();
+        state
+            .unsaved_changes
+            .store(result.is_err(), Ordering::Relaxed);
+        match &result {
+            Ok(()) => *lock(&state.write_problem) = None,
+            Err(AppStateError::Config(e @ ConfigError::NotWritable { .. })) => {
+                *lock(&state.write_problem) = Some(e.to_string());
+            }
+            Err(_) => {}
+        }
+        drop(state);
+        result
+    }
+
+    /// Check up front whether the config directory can be written to,
+    /// remembering the answer for [`Self::config_write_problem`]
+    pub fn check_config_writable(&self) {
+        let problem = Config::check_writable().err().map(|e| e.to_string());
+        *lock(&self.state().write_problem) = problem;
+    }
+
+    /// Why changes to the config won't be saved, if the last check or save
+    /// found it can't be written
+    pub fn config_write_problem(&self) -> Option<String> {
+        lock(&self.state().write_problem).clone()
+    }
+
+    /// Whether the in-memory config has changes that aren't on disk, so
+    /// exiting now would lose them: the last save failed, or the automations
+    /// differ from the config file's
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.state().unsaved_changes.load(Ordering::Relaxed)
+            || Config::config_file_path().is_ok_and(|path| self.automations_differ_from(&path))
+    }
+
+    /// Whether the automations in memory differ from those in the config
+    /// file at `path`. A file that can't be read counts as no difference;
+    /// a missing one as having no automations.
+    fn automations_differ_from(&self, path: &Path) -> bool {
+        let on_disk = match std::fs::read_to_string(path) {
+            Ok(content) => match ConfigFormat::from_path(path).parse(&content) {
+                Ok(config) => config,
+                Err(_) => return false,
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Config::default(),
+            Err(_) => return false,
+        };
+        let in_memory = self.get_config().unwrap_or_else(|_| on_disk.clone());
+
+        // Compared as JSON, with snoozes that ran out dropped as loading does
+        let automations = |mut config: Config| {
+            config.clear_expired_snoozes();
+            serde_json::to_value(&config.notifications.automations).ok()
+        };
+        automations(in_memory) != automations(on_disk)
+    }
+
     /// Update the entire config and recreate the client if API config changed
-    pub fn update_config(&self, new_config: Config) -> Result<(), String> {
-        let state = self
-            .0
-            .read()
-            .map_err(|e| format!("Failed to acquire read lock: {}", e))?;
+    pub fn update_config(&self, new_config: Config) -> Result<(), AppStateError> {
+        let state = self.state();
 
         // Update config
-        let mut config = state
-            .config
-            .write()
-            .map_err(|e| format!("Failed to acquire config write lock: {}", e))?;
+        let mut config = write(&state.config);
         let api_changed =
             config.api.url != new_config.api.url || config.api.token != new_config.api.token;
         *config = new_config.clone();
@@ -136,15 +278,79 @@ impl SharedAppState {
 
         // Recreate client if API config changed
         if api_changed {
-            let mut client = state
-                .client
-                .write()
-                .map_err(|e| format!("Failed to acquire client write lock: {}", e))?;
-            *client = BeeperClient::new(&new_config.api.token, &new_config.api.url);
+            let mut client = write(&state.client);
+            *client = Arc::new(BeeperClient::new(
+                new_config.api.token.reveal(),
+                &new_config.api.url,
+            ));
+            drop(client);
+
+            lock(&state.chat_list).clear();
         }
 
         Ok(())
     }
+
+    /// The cached first page of chats, if it was fetched less than `ttl` ago
+    pub fn cached_chats(&self, ttl: Duration) -> Option<Arc<ChatList>> {
+        let state = self.state();
+        let cache = lock(&state.chat_list);
+        cache.get(ttl, Instant::now())
+    }
+
+    /// Blocking version of [`Self::get_chats_cached_async`] for sync code.
+    ///
+    /// Bridges the async call with `block_in_place`, so it must be called
+    /// from within the multi-threaded Tokio runtime.
+    pub fn get_chats_cached(&self, ttl: Duration) -> Result<Arc<ChatList>, AppStateError> {
+        tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(self.get_chats_cached_async(ttl))
+        })
+    }
+
+    /// The first page of chats, reusing the last fetch while it is younger
+    /// than `ttl` so callers polling the chat list share one request
+    pub async fn get_chats_cached_async(
+        &self,
+        ttl: Duration,
+    ) -> Result<Arc<ChatList>, AppStateError> {
+        if let Some(chats) = self.cached_chats(ttl) {
+            return Ok(chats);
+        }
+
+        let response = self
+            .with_client_async(|client| async move { client.list_chats(None).await })
+            .await?
+            .map_err(AppStateError::Client)?;
+
+        let chat_list = ChatList {
+            chats: response.items,
+            oldest_cursor: response.oldest_cursor,
+            has_more: response.has_more,
+        };
+
+        let state = self.state();
+        let mut cache = lock(&state.chat_list);
+        Ok(cache.store(chat_list, Instant::now()))
+    }
+
+    /// The automation last selected in the notification screen, so reopening
+    /// it picks up where the user left off
+    pub fn last_automation_id(&self) -> Option<String> {
+        let state = self.state();
+        let id = lock(&state.last_automation_id);
+        id.clone()
+    }
+
+    /// Remember the selected automation for [`Self::last_automation_id`]
+    pub fn set_last_automation_id(&self, id: Option<String>) {
+        *lock(&self.state().last_automation_id) = id;
+    }
+
+    /// Drop the cached chat list so the next lookup fetches it again
+    pub fn invalidate_chats(&self) {
+        lock(&self.state().chat_list).clear();
+    }
 }
 
 impl Clone for SharedAppState {
@@ -157,3 +363,88 @@ impl Clone for SharedAppState {
 pub fn create_shared_app_state(config: Config) -> SharedAppState {
     SharedAppState::new(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beeper_api::mock::MockApi;
+
+    #[test]
+    fn test_state_usable_after_panic_mid_write() {
+        let app_state = SharedAppState::with_api(Config::default(), Arc::new(MockApi::default()));
+
+        let writer = app_state.clone();
+        let panicked = std::thread::spawn(move || {
+            writer.with_config_mut(|config| {
+                config.notifications.paused = true;
+                panic!("panic while holding the config lock");
+            })
+        })
+        .join();
+        assert!(panicked.is_err());
+
+        // The write made before the panic stays, and the locks still work
+        let paused = app_state.with_config(|config| config.notifications.paused);
+        assert!(paused.unwrap());
+        app_state
+            .with_config_mut(|config| config.notifications.paused = false)
+            .unwrap();
+        assert!(!app_state.get_config().unwrap().notifications.paused);
+        assert!(app_state.client().is_ok());
+
+        // So do the rest after a panic holding the outer lock
+        let arc = app_state.clone_arc();
+        let panicked = std::thread::spawn(move || {
+            let _state = arc.write().unwrap();
+            panic!("panic while holding the state lock");
+        })
+        .join();
+        assert!(panicked.is_err());
+        app_state.set_last_automation_id(Some("work".to_string()));
+        assert_eq!(app_state.last_automation_id().as_deref(), Some("work"));
+        app_state.invalidate_chats();
+        assert!(app_state.cached_chats(Duration::from_secs(60)).is_none());
+    }
+
+    #[test]
+    fn test_automations_differ_from_config_file() {
+        use crate::notifications::NotificationAutomation;
+
+        let dir = std::env::temp_dir().join(format!("beeper-app-state-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("config.toml");
+        let app_state = SharedAppState::with_api(Config::default(), Arc::new(MockApi::default()));
+        assert!(!app_state.automations_differ_from(&path));
+
+        let automation = NotificationAutomation::new("a".to_string(), "A".to_string(), vec![]);
+        app_state
+            .with_config_mut(|config| config.notifications.automations.push(automation))
+            .unwrap();
+        assert!(app_state.automations_differ_from(&path));
+
+        app_state.get_config().unwrap().save_to(&path).unwrap();
+        assert!(!app_state.automations_differ_from(&path));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_ttl_cache_expiry() {
+        let ttl = Duration::from_secs(2);
+        let t0 = Instant::now();
+        let mut cache = TtlCache::new();
+        assert!(cache.get(ttl, t0).is_none());
+
+        cache.store(vec!["chat"], t0);
+        assert_eq!(*cache.get(ttl, t0 + Duration::from_secs(1)).unwrap(), vec!["chat"]);
+        assert!(cache.get(ttl, t0 + ttl).is_none());
+
+        // A shorter TTL treats the same entry as stale sooner
+        assert!(cache.get(Duration::ZERO, t0).is_none());
+
+        cache.store(vec!["newer"], t0 + ttl);
+        assert_eq!(*cache.get(ttl, t0 + ttl).unwrap(), vec!["newer"]);
+
+        cache.clear();
+        assert!(cache.get(ttl, t0 + ttl).is_none());
+    }
+}
@@ -0,0 +1,210 @@
+// The Beeper Desktop API calls the app makes, behind a trait so the
+// notification service can run against an in-memory client in tests
+
+use beeper_desktop_api::{
+    BeeperClient, FocusAppInput, FocusAppOutput, ListChatsOutput, ListMessagesOutput,
+    MarkReadInput, MarkReadOutput, SendMessageInput, SendMessageOutput,
+};
+use std::future::Future;
+use std::pin::Pin;
+
+/// A pending API call. Errors are already formatted for display.
+pub type ApiFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, String>> + Send + 'a>>;
+
+/// The parts of the Beeper Desktop API used by the service and configurator
+pub trait BeeperApi: Send + Sync {
+    /// One page of chats, most recently active first. `cursor` continues
+    /// from the `oldest_cursor` of an earlier page.
+    fn list_chats<'a>(&'a self, cursor: Option<&'a str>) -> ApiFuture<'a, ListChatsOutput>;
+
+    /// One page of a chat's messages, newest first
+    fn list_messages<'a>(
+        &'a self,
+        chat_id: &'a str,
+        cursor: Option<&'a str>,
+    ) -> ApiFuture<'a, ListMessagesOutput>;
+
+    /// Bring Beeper Desktop to the front, optionally on a chat
+    fn focus_app(&self, input: Option<FocusAppInput>) -> ApiFuture<'_, FocusAppOutput>;
+
+    fn send_message(&self, input: SendMessageInput) -> ApiFuture<'_, SendMessageOutput>;
+
+    fn mark_read(&self, input: MarkReadInput) -> ApiFuture<'_, MarkReadOutput>;
+}
+
+impl BeeperApi for BeeperClient {
+    fn list_chats<'a>(&'a self, cursor: Option<&'a str>) -> ApiFuture<'a, ListChatsOutput> {
+        Box::pin(async move {
+            BeeperClient::list_chats(self, cursor, None)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn list_messages<'a>(
+        &'a self,
+        chat_id: &'a str,
+        cursor: Option<&'a str>,
+    ) -> ApiFuture<'a, ListMessagesOutput> {
+        Box::pin(async move {
+            BeeperClient::list_messages(self, chat_id, cursor, None)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn focus_app(&self, input: Option<FocusAppInput>) -> ApiFuture<'_, FocusAppOutput> {
+        Box::pin(async move {
+            BeeperClient::focus_app(self, input)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn send_message(&self, input: SendMessageInput) -> ApiFuture<'_, SendMessageOutput> {
+        Box::pin(async move {
+            BeeperClient::send_message(self, input)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+
+    fn mark_read(&self, input: MarkReadInput) -> ApiFuture<'_, MarkReadOutput> {
+        Box::pin(async move {
+            BeeperClient::mark_read(self, input)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::*;
+    use beeper_desktop_api::{Chat, Message};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// In-memory stand-in for Beeper Desktop. Tests add chats and messages
+    /// between polls and check what the service did with them.
+    #[derive(Default)]
+    pub(crate) struct MockApi {
+        chats: Mutex<Vec<Chat>>,
+        /// Messages per chat, newest first
+        messages: Mutex<HashMap<String, Vec<Message>>>,
+        /// Chat ids passed to `focus_app`, in call order
+        pub(crate) focused: Mutex<Vec<String>>,
+        /// Chat id and text of each `send_message`, in call order
+        pub(crate) sent: Mutex<Vec<(String, String)>>,
+        /// Error `send_message` fails with while set
+        pub(crate) send_error: Mutex<Option<String>>,
+        /// Chats per `list_chats` page; all of them on one page when unset
+        pub(crate) page_size: Mutex<Option<usize>>,
+    }
+
+    impl MockApi {
+        pub(crate) fn add_chat(&self, chat_id: &str, title: &str) {
+            self.chats.lock().unwrap().push(Chat {
+                id: chat_id.to_string(),
+                title: title.to_string(),
+                unread_count: 0,
+                last_activity: None,
+            });
+        }
+
+        /// Add a message as the chat's newest. Messages from others count
+        /// as unread until the chat is marked read.
+        pub(crate) fn push_message(&self, chat_id: &str, message_id: &str, from_me: bool) {
+            let mut messages = self.messages.lock().unwrap();
+            let chat_messages = messages.entry(chat_id.to_string()).or_default();
+            chat_messages.insert(
+                0,
+                Message {
+                    id: message_id.to_string(),
+                    sort_key: format!("{:08}", chat_messages.len() + 1),
+                    sender_name: Some(if from_me { "Me" } else { "Friend" }.to_string()),
+                    text: Some(format!("message {}", message_id)),
+                    is_sender: Some(from_me),
+                    timestamp: chrono::Utc::now().to_rfc3339(),
+                },
+            );
+
+            let mut chats = self.chats.lock().unwrap();
+            if let Some(chat) = chats.iter_mut().find(|chat| chat.id == chat_id) {
+                chat.unread_count = if from_me { 0 } else { chat.unread_count + 1 };
+            }
+        }
+
+        pub(crate) fn mark_chat_read(&self, chat_id: &str) {
+            let mut chats = self.chats.lock().unwrap();
+            if let Some(chat) = chats.iter_mut().find(|chat| chat.id == chat_id) {
+                chat.unread_count = 0;
+            }
+        }
+    }
+
+    impl BeeperApi for MockApi {
+        fn list_chats<'a>(&'a self, cursor: Option<&'a str>) -> ApiFuture<'a, ListChatsOutput> {
+            // The cursor is the index of the page's first chat
+            let chats = self.chats.lock().unwrap();
+            let start = cursor
+                .and_then(|c| c.parse().ok())
+                .unwrap_or(0)
+                .min(chats.len());
+            let end = match *self.page_size.lock().unwrap() {
+                Some(size) => (start + size).min(chats.len()),
+                None => chats.len(),
+            };
+            let has_more = end < chats.len();
+            Box::pin(std::future::ready(Ok(ListChatsOutput {
+                items: chats[start..end].to_vec(),
+                oldest_cursor: has_more.then(|| end.to_string()),
+                newest_cursor: None,
+                has_more,
+            })))
+        }
+
+        fn list_messages<'a>(
+            &'a self,
+            chat_id: &'a str,
+            _cursor: Option<&'a str>,
+        ) -> ApiFuture<'a, ListMessagesOutput> {
+            let items = self
+                .messages
+                .lock()
+                .unwrap()
+                .get(chat_id)
+                .cloned()
+                .unwrap_or_default();
+            Box::pin(std::future::ready(Ok(ListMessagesOutput {
+                items,
+                has_more: false,
+            })))
+        }
+
+        fn focus_app(&self, input: Option<FocusAppInput>) -> ApiFuture<'_, FocusAppOutput> {
+            if let Some(chat_id) = input.and_then(|input| input.chat_id) {
+                self.focused.lock().unwrap().push(chat_id);
+            }
+            Box::pin(std::future::ready(Ok(FocusAppOutput { success: true })))
+        }
+
+        fn send_message(&self, input: SendMessageInput) -> ApiFuture<'_, SendMessageOutput> {
+            if let Some(e) = self.send_error.lock().unwrap().clone() {
+                return Box::pin(std::future::ready(Err(e)));
+            }
+            self.sent
+                .lock()
+                .unwrap()
+                .push((input.chat_id, input.text.unwrap_or_default()));
+            Box::pin(std::future::ready(Ok(SendMessageOutput {
+                message_id: None,
+            })))
+        }
+
+        fn mark_read(&self, input: MarkReadInput) -> ApiFuture<'_, MarkReadOutput> {
+            self.mark_chat_read(&input.chat_id);
+            Box::pin(std::future::ready(Ok(MarkReadOutput { success: true })))
+        }
+    }
+}
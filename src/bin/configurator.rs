@@ -1,9 +1,16 @@
 use anyhow::Result;
-use beeper_automations::api_check::validate_api;
+use beeper_automations::api_check::{ApiStatus, is_beeper_running, validate_api};
 use beeper_automations::app_state::SharedAppState;
+use beeper_automations::cli::{Command, USAGE, take_config_dir};
 use beeper_automations::config::Config;
+use beeper_automations::notifications::control::{self, ControlCommand};
+use beeper_automations::notifications::quiet;
+use beeper_automations::notifications::state::RuntimeState;
 use beeper_automations::tui::{
-    MenuOption, show_config_screen, show_loading_screen, show_main_screen, show_notification_screen,
+    LoadOutcome, MenuOption, OfflineAction, TransferKind, show_config_screen,
+    show_config_screen_validated, show_config_screen_with_message, show_history_screen,
+    show_loading_screen, show_log_screen, show_main_screen, show_notification_screen,
+    show_offline_screen, show_scheduled_focus_screen, show_transfer_screen,
 };
 use std::path::PathBuf;
 
@@ -43,10 +50,56 @@ fn migrate_old_config() -> Result<()> {
     Ok(())
 }
 
+/// Whether the service stopped polling because Beeper rejected the token in
+/// config.toml. Asks the running service, or reads the state it left behind
+/// when it isn't running; a rejection older than the config file was for a
+/// token that has been replaced since.
+fn service_rejected_token(config: &Config) -> bool {
+    let rejected_ms = match control::status(&config.control) {
+        Ok(status) => status.token_rejected_ms,
+        Err(_) => RuntimeState::state_file_path()
+            .ok()
+            .and_then(|path| RuntimeState::load_from(&path, quiet::now_ms()).token_rejected_ms),
+    };
+    let Some(rejected_ms) = rejected_ms else {
+        return false;
+    };
+
+    let config_saved_ms = Config::config_file_path()
+        .ok()
+        .and_then(|path| std::fs::metadata(path).ok())
+        .and_then(|metadata| metadata.modified().ok())
+        .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_millis() as u64);
+    config_saved_ms.is_none_or(|saved_ms| rejected_ms > saved_ms)
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Attempt to migrate old config if it exists
-    migrate_old_config().ok();
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    match take_config_dir(&mut args) {
+        Ok(Some(dir)) => Config::set_config_dir(dir),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("✗ {}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    }
+
+    // Attempt to migrate old config if it exists; an explicit directory is used as is
+    if !Config::config_dir_overridden() {
+        migrate_old_config().ok();
+    }
+
+    // Headless subcommands skip the TUI entirely
+    match Command::parse(&args) {
+        Ok(Some(command)) => return command.run().await,
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("✗ {}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    }
 
     // Load configuration
     let config = Config::load()?;
@@ -55,6 +108,9 @@ async fn main() -> Result<()> {
     // Initialize shared app state
     let app_state = SharedAppState::new(config);
 
+    // Find out now, not on the first save, if changes can't be kept
+    app_state.check_config_writable();
+
     // Check if API is configured, if not show configuration screen first
     let current_config = app_state
         .get_config()
@@ -69,52 +125,80 @@ async fn main() -> Result<()> {
         }
     }
 
-    // Validate API credentials
-    {
+    // The service found the token rejected; go straight to replacing it
+    let mut credentials_edited = false;
+    let current_config = app_state
+        .get_config()
+        .unwrap_or_else(|_| default_config.clone());
+    if service_rejected_token(&current_config) {
+        let message =
+            "✗ The service stopped polling: Beeper rejected the API token. Enter a new one."
+                .to_string();
+        let updated_config = show_config_screen_with_message(current_config, message)?;
+        app_state.update_config(updated_config.clone()).ok();
+        credentials_edited = true;
+
+        if !updated_config.is_api_configured() {
+            return Ok(());
+        }
+    }
+
+    // Validate API credentials. If Beeper Desktop isn't answering, wait for
+    // the user to start it rather than asking for credentials again.
+    loop {
         let cfg = app_state
             .get_config()
             .unwrap_or_else(|_| default_config.clone());
         let url = cfg.api.url.clone();
-        let token = cfg.api.token.clone();
-        let is_valid = show_loading_screen("Validating API credentials...", async move {
+        let token = cfg.api.token.reveal().to_string();
+        let outcome = show_loading_screen("Validating API credentials...", async move {
             validate_api(&url, &token).await
         })
         .await?;
 
-        if !is_valid {
-            std::thread::sleep(std::time::Duration::from_millis(1500));
-            let current_config = app_state
-                .get_config()
-                .unwrap_or_else(|_| default_config.clone());
-            let updated_config = show_config_screen(current_config)?;
-            app_state.update_config(updated_config.clone()).ok();
-
-            if !updated_config.is_api_configured() {
-                return Ok(());
+        let message = match outcome {
+            // A check against a dead URL can hang until the connection times
+            // out; cancelling it goes back to the credentials
+            LoadOutcome::Cancelled => "Validation cancelled".to_string(),
+            LoadOutcome::Finished(status) => {
+                match status {
+                    ApiStatus::Ok => break,
+                    ApiStatus::Unreachable => {
+                        match show_offline_screen(cfg.api.url.clone(), is_beeper_running())? {
+                            OfflineAction::Retry => continue,
+                            OfflineAction::Quit => return Ok(()),
+                            OfflineAction::EditCredentials => {}
+                        }
+                    }
+                    _ if credentials_edited => {
+                        eprintln!("✗ {} Cannot continue.", status);
+                        return Ok(());
+                    }
+                    _ => {}
+                }
+                format!("✗ {}", status)
             }
+        };
 
-            // Validate again after reconfiguration
-            let url = updated_config.api.url.clone();
-            let token = updated_config.api.token.clone();
-            let is_valid_retry = show_loading_screen("Validating API credentials...", async move {
-                validate_api(&url, &token).await
-            })
-            .await?;
-
-            if !is_valid_retry {
-                eprintln!("✗ API credentials are still invalid. Cannot continue.");
-                return Ok(());
-            }
+        let updated_config = show_config_screen_with_message(cfg, message)?;
+        app_state.update_config(updated_config.clone()).ok();
+        credentials_edited = true;
+
+        if !updated_config.is_api_configured() {
+            return Ok(());
         }
     }
 
-    // Main application loop
+    // Main application loop, returning to the menu entry chosen last
+    let mut last_choice = None;
     loop {
         // Show main screen
         let current_config = app_state
             .get_config()
             .unwrap_or_else(|_| default_config.clone());
-        match show_main_screen(current_config)? {
+        let choice = show_main_screen(current_config, app_state.clone(), last_choice)?;
+        last_choice = choice;
+        match choice {
             Some(MenuOption::Module(idx)) => {
                 // Handle module selection
                 match idx {
@@ -128,41 +212,62 @@ async fn main() -> Result<()> {
                     _ => {}
                 }
             }
+            Some(MenuOption::TogglePause) => {
+                // Flip the global pause. A running service is told right away;
+                // otherwise it picks the saved change up on hot reload
+                app_state
+                    .with_config_mut(|config| {
+                        config.notifications.paused = !config.notifications.paused;
+                    })
+                    .ok();
+                if let Err(e) = app_state.save_config() {
+                    eprintln!("✗ Error saving configuration: {}", e);
+                }
+                if let Ok(config) = app_state.get_config() {
+                    let command = if config.notifications.paused {
+                        ControlCommand::Pause
+                    } else {
+                        ControlCommand::Resume
+                    };
+                    control::send(&config.control, &command).ok();
+                }
+            }
+            Some(MenuOption::ScheduledFocus) => {
+                show_scheduled_focus_screen(app_state.clone())?;
+            }
+            Some(MenuOption::NotificationHistory) => {
+                show_history_screen()?;
+            }
+            Some(MenuOption::ServiceLogs) => {
+                show_log_screen()?;
+            }
+            Some(MenuOption::InstallService) => {
+                // Installed from the menu itself, which shows how it went
+            }
+            Some(MenuOption::ExportConfiguration) => {
+                let current_config = app_state
+                    .get_config()
+                    .unwrap_or_else(|_| default_config.clone());
+                show_transfer_screen(current_config, TransferKind::Export)?;
+            }
+            Some(MenuOption::ImportConfiguration) => {
+                let current_config = app_state
+                    .get_config()
+                    .unwrap_or_else(|_| default_config.clone());
+                if let Some(new_config) =
+                    show_transfer_screen(current_config, TransferKind::Import)?
+                {
+                    app_state.update_config(new_config).ok();
+                }
+            }
             Some(MenuOption::ChangeConfiguration) => {
-                // Show configuration screen
+                // The screen checks new credentials itself and only saves
+                // them once they work; backing out keeps the current ones
                 let current_config = app_state
                     .get_config()
                     .unwrap_or_else(|_| default_config.clone());
-                match show_config_screen(current_config) {
-                    Ok(new_config) => {
-                        // Verify and validate configuration
-                        if new_config.is_api_configured() {
-                            let url = new_config.api.url.clone();
-                            let token = new_config.api.token.clone();
-                            let is_valid =
-                                show_loading_screen("Validating API credentials...", async move {
-                                    let r = validate_api(&url, &token).await;
-                                    // wait 1500 ms for user to read message
-                                    tokio::time::sleep(std::time::Duration::from_millis(1500))
-                                        .await;
-                                    r
-                                })
-                                .await?;
-
-                            if !is_valid {
-                                eprintln!("⚠ Configuration saved but API credentials are invalid.");
-                                break;
-                            }
-
-                            // Update app state with new config
-                            app_state.update_config(new_config).ok();
-                        } else {
-                            eprintln!("✗ Configuration is incomplete.");
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("✗ Error loading configuration: {}", e);
-                    }
+                if let Some(new_config) = show_config_screen_validated(current_config)? {
+                    app_state.update_config(new_config).ok();
                 }
                 // Loop back to main screen
             }
@@ -2,5 +2,5 @@ use anyhow::Result;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    beeper_automations::run_service().await
+    beeper_automations::run_service(beeper_automations::ServiceOptions::from_args()).await
 }
@@ -4,6 +4,9 @@
 // 
 // This binary runs the Beeper Automations service in the user's session
 // without showing a console window. It's designed to be used with Scheduled Tasks.
+//
+// Built with the `windows-service` feature it can also register itself with the
+// Service Control Manager: `install`, `uninstall`, `start` and `stop`.
 
 // Hide the console window at startup
 #[cfg(windows)]
@@ -18,7 +21,7 @@ fn hide_console_window() {
     }
 }
 
-async fn main_impl() -> anyhow::Result<()> {
+async fn main_impl(shutdown_rx: tokio::sync::mpsc::Receiver<()>) -> anyhow::Result<()> {
     use beeper_automations::logging::{data_dir, log_to_file};
     
     log_to_file("Beeper Automations User Service started (hidden window)");
@@ -41,11 +44,26 @@ async fn main_impl() -> anyhow::Result<()> {
     beeper_automations::logging::init_logging(true);
     log_to_file("File logging initialized");
 
+    // Run the service
+    log_to_file("Starting service loop");
+    let result = beeper_automations::run_service_with_shutdown(shutdown_rx).await;
+    
+    match &result {
+        Ok(_) => log_to_file("Service stopped gracefully"),
+        Err(e) => {
+            log_to_file(&format!("Service error: {}", e));
+            log_to_file(&format!("Error details: {:?}", e));
+        }
+    }
+    
+    result
+}
+
+async fn console_main() -> anyhow::Result<()> {
     // Create shutdown channel for clean exit
     let (shutdown_tx, shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
 
     // Set up Ctrl+C handler for graceful shutdown
-    #[cfg(windows)]
     {
         use tokio::signal::windows::ctrl_c;
         let mut ctrl_c = ctrl_c()?;
@@ -57,25 +75,241 @@ async fn main_impl() -> anyhow::Result<()> {
         });
     }
 
-    // Run the service
-    log_to_file("Starting service loop");
-    let result = beeper_automations::run_service_with_shutdown(shutdown_rx).await;
-    
-    match &result {
-        Ok(_) => log_to_file("Service stopped gracefully"),
-        Err(e) => {
+    main_impl(shutdown_rx).await
+}
+
+/// Service Control Manager integration: `install`, `uninstall`, `start`,
+/// `stop`, and the `service` entry point the SCM launches us with.
+#[cfg(feature = "windows-service")]
+mod scm {
+    use std::ffi::{OsStr, OsString};
+    use std::time::Duration;
+    use windows_service::service::{
+        ServiceAccess, ServiceControl, ServiceControlAccept, ServiceErrorControl, ServiceExitCode,
+        ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+    };
+    use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+    use windows_service::service_manager::{ServiceManager, ServiceManagerAccess};
+    use windows_service::{define_windows_service, service_dispatcher};
+
+    const SERVICE_NAME: &str = "BeeperAutomations";
+    const SERVICE_DISPLAY_NAME: &str = "Beeper Automations";
+    const SERVICE_DESCRIPTION: &str = "Runs Beeper Automations in the background";
+
+    const ERROR_ACCESS_DENIED: i32 = 5;
+    const ERROR_SERVICE_DOES_NOT_EXIST: i32 = 1060;
+    const ERROR_SERVICE_ALREADY_RUNNING: i32 = 1056;
+    const ERROR_SERVICE_NOT_ACTIVE: i32 = 1062;
+    const ERROR_SERVICE_EXISTS: i32 = 1073;
+
+    /// Turn common Win32 failures into messages a user can act on
+    fn explain(err: windows_service::Error) -> anyhow::Error {
+        let code = match &err {
+            windows_service::Error::Winapi(io_err) => io_err.raw_os_error(),
+            _ => None,
+        };
+
+        match code {
+            Some(ERROR_ACCESS_DENIED) => {
+                anyhow::anyhow!("Access denied. Run this command from an Administrator prompt.")
+            }
+            Some(ERROR_SERVICE_EXISTS) => anyhow::anyhow!(
+                "Service '{}' is already installed. Run 'uninstall' first to reinstall it.",
+                SERVICE_NAME
+            ),
+            Some(ERROR_SERVICE_DOES_NOT_EXIST) => anyhow::anyhow!(
+                "Service '{}' is not installed. Run 'install' first.",
+                SERVICE_NAME
+            ),
+            Some(ERROR_SERVICE_ALREADY_RUNNING) => {
+                anyhow::anyhow!("Service '{}' is already running.", SERVICE_NAME)
+            }
+            Some(ERROR_SERVICE_NOT_ACTIVE) => {
+                anyhow::anyhow!("Service '{}' is not running.", SERVICE_NAME)
+            }
+            _ => anyhow::anyhow!("{}", err),
+        }
+    }
+
+    fn connect(access: ServiceManagerAccess) -> anyhow::Result<ServiceManager> {
+        ServiceManager::local_computer(None::<&str>, access).map_err(explain)
+    }
+
+    fn install() -> anyhow::Result<()> {
+        let manager =
+            connect(ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE)?;
+
+        let mut launch_arguments = vec![OsString::from("service")];
+        let config_dir = beeper_automations::service_install::config_dir_arg()
+            .map_err(|e| anyhow::anyhow!(e))?;
+        if let Some(dir) = config_dir {
+            launch_arguments.push(OsString::from("--config-dir"));
+            launch_arguments.push(dir.into_os_string());
+        }
+
+        let service_info = ServiceInfo {
+            name: OsString::from(SERVICE_NAME),
+            display_name: OsString::from(SERVICE_DISPLAY_NAME),
+            service_type: ServiceType::OWN_PROCESS,
+            start_type: ServiceStartType::AutoStart,
+            error_control: ServiceErrorControl::Normal,
+            executable_path: std::env::current_exe()?,
+            launch_arguments,
+            dependencies: vec![],
+            account_name: None, // LocalSystem
+            account_password: None,
+        };
+
+        let service = manager
+            .create_service(&service_info, ServiceAccess::CHANGE_CONFIG)
+            .map_err(explain)?;
+        service.set_description(SERVICE_DESCRIPTION).map_err(explain)?;
+
+        println!("✓ Service '{}' installed (auto-start).", SERVICE_NAME);
+        println!("  Start it now with: auto-beeper-windows-service start");
+        Ok(())
+    }
+
+    fn uninstall() -> anyhow::Result<()> {
+        let manager = connect(ServiceManagerAccess::CONNECT)?;
+        let service = manager
+            .open_service(
+                SERVICE_NAME,
+                ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+            )
+            .map_err(explain)?;
+
+        if service.query_status().map_err(explain)?.current_state != ServiceState::Stopped {
+            println!("Stopping service...");
+            service.stop().map_err(explain)?;
+            std::thread::sleep(Duration::from_secs(2));
+        }
+
+        service.delete().map_err(explain)?;
+        println!("✓ Service '{}' uninstalled.", SERVICE_NAME);
+        Ok(())
+    }
+
+    fn start() -> anyhow::Result<()> {
+        let manager = connect(ServiceManagerAccess::CONNECT)?;
+        let service = manager
+            .open_service(SERVICE_NAME, ServiceAccess::START)
+            .map_err(explain)?;
+        service.start::<&OsStr>(&[]).map_err(explain)?;
+        println!("✓ Service '{}' started.", SERVICE_NAME);
+        Ok(())
+    }
+
+    fn stop() -> anyhow::Result<()> {
+        let manager = connect(ServiceManagerAccess::CONNECT)?;
+        let service = manager
+            .open_service(SERVICE_NAME, ServiceAccess::STOP)
+            .map_err(explain)?;
+        service.stop().map_err(explain)?;
+        println!("✓ Service '{}' stopped.", SERVICE_NAME);
+        Ok(())
+    }
+
+    define_windows_service!(ffi_service_main, service_main);
+
+    fn service_main(_arguments: Vec<OsString>) {
+        use beeper_automations::logging::log_to_file;
+
+        if let Err(e) = run_as_service() {
             log_to_file(&format!("Service error: {}", e));
-            log_to_file(&format!("Error details: {:?}", e));
         }
     }
-    
-    result
+
+    fn run_as_service() -> anyhow::Result<()> {
+        let (shutdown_tx, shutdown_rx) = tokio::sync::mpsc::channel::<()>(1);
+
+        let status_handle =
+            service_control_handler::register(SERVICE_NAME, move |control| match control {
+                ServiceControl::Stop | ServiceControl::Shutdown => {
+                    let _ = shutdown_tx.try_send(());
+                    ServiceControlHandlerResult::NoError
+                }
+                ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+                _ => ServiceControlHandlerResult::NotImplemented,
+            })?;
+
+        let set_state = |state: ServiceState, exit_code: u32| {
+            status_handle.set_service_status(ServiceStatus {
+                service_type: ServiceType::OWN_PROCESS,
+                current_state: state,
+                controls_accepted: if state == ServiceState::Running {
+                    ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN
+                } else {
+                    ServiceControlAccept::empty()
+                },
+                exit_code: ServiceExitCode::Win32(exit_code),
+                checkpoint: 0,
+                wait_hint: Duration::default(),
+                process_id: None,
+            })
+        };
+
+        set_state(ServiceState::Running, 0)?;
+
+        let runtime = tokio::runtime::Runtime::new()?;
+        let result = runtime.block_on(super::main_impl(shutdown_rx));
+
+        set_state(ServiceState::Stopped, if result.is_ok() { 0 } else { 1 })?;
+        result
+    }
+
+    /// Handle a subcommand; returns `None` if the argument isn't one of ours
+    pub fn handle_command(command: &str) -> Option<anyhow::Result<()>> {
+        Some(match command {
+            "install" => install(),
+            "uninstall" => uninstall(),
+            "start" => start(),
+            "stop" => stop(),
+            "service" => service_dispatcher::start(SERVICE_NAME, ffi_service_main)
+                .map_err(anyhow::Error::from),
+            _ => return None,
+        })
+    }
+}
+
+fn print_usage() {
+    eprintln!(
+        "Usage: auto-beeper-windows-service [--config-dir <DIR>] [install | uninstall | start | stop]"
+    );
+    eprintln!("  Without arguments, runs in the current user session with a hidden window.");
 }
 
 fn main() -> anyhow::Result<()> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    match beeper_automations::cli::take_config_dir(&mut args) {
+        Ok(Some(dir)) => beeper_automations::config::Config::set_config_dir(dir),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            print_usage();
+            std::process::exit(2);
+        }
+    }
+
+    if let Some(command) = args.into_iter().next() {
+        #[cfg(feature = "windows-service")]
+        if let Some(result) = scm::handle_command(&command) {
+            return result;
+        }
+
+        #[cfg(not(feature = "windows-service"))]
+        if matches!(command.as_str(), "install" | "uninstall" | "start" | "stop" | "service") {
+            anyhow::bail!("'{}' requires building with --features windows-service", command);
+        }
+
+        eprintln!("Unknown command: {}", command);
+        print_usage();
+        std::process::exit(2);
+    }
+
     // Hide console window to avoid showing cmd popup
     hide_console_window();
     
     let runtime = tokio::runtime::Runtime::new()?;
-    runtime.block_on(main_impl())
+    runtime.block_on(console_main())
 }
@@ -0,0 +1,399 @@
+// Headless configuration commands for the configurator, for machines where
+// the TUI can't run. Each command loads the config, changes it and saves it.
+
+use crate::api_check::validate_api;
+use crate::app_state::SharedAppState;
+use crate::config::{Config, PartialConfig, normalize_api_url};
+use crate::notifications::preflight;
+use crate::notifications::{
+    AutomationType, LoopConfig, LoopUntil, NotificationAutomation, Schedule,
+};
+use anyhow::{Result, anyhow, bail};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: auto-beeper-configurator [--config-dir <DIR>] [COMMAND]
+
+Without a command, opens the interactive configurator.
+
+Options:
+  --config-dir <DIR>                    Use the config in DIR instead of the default
+                                        location (also set by BEEPER_CONFIG_DIR)
+
+Commands:
+  set-api --url <URL> --token <TOKEN>   Set and validate the API connection
+  list                                  List automations
+  add-automation --name <NAME> --chat <CHAT_ID> [--chat <CHAT_ID>...] [--match <GLOB>]
+                 [--type immediate|loop|combined|new_chat]
+                 [--until message_seen|answer|for_a_time|once]
+                 [--time <MS>] [--interval <MS>] [--sound <PATH|builtin:NAME>] [--focus]
+                 [--desktop-notification] [--schedule \"mon-fri 09:00-17:00\"]
+                 [--disabled]
+  enable <ID|NAME>                      Enable an automation
+  disable <ID|NAME>                     Disable an automation
+  patch <FILE|->                        Merge a partial config in TOML into the saved one;
+                                        automations are matched by id, new ids are added
+  check                                 Check the API, and every enabled automation's chats
+                                        and sound files; exits non-zero if anything fails
+  help                                  Show this message";
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    SetApi { url: String, token: String },
+    List,
+    AddAutomation(Box<NotificationAutomation>, Option<u64>),
+    SetEnabled { automation: String, enabled: bool },
+    Patch { path: PathBuf },
+    Check,
+    Help,
+}
+
+impl Command {
+    /// Parse the arguments after the binary name; `None` means open the TUI
+    pub fn parse(args: &[String]) -> Result<Option<Self>> {
+        let Some((command, rest)) = args.split_first() else {
+            return Ok(None);
+        };
+
+        let command = match command.as_str() {
+            "set-api" => {
+                let mut url = None;
+                let mut token = None;
+                let mut rest = rest.iter();
+                while let Some(flag) = rest.next() {
+                    match flag.as_str() {
+                        "--url" => url = Some(flag_value(flag, rest.next())?),
+                        "--token" => token = Some(flag_value(flag, rest.next())?),
+                        other => bail!("Unknown option for set-api: {}", other),
+                    }
+                }
+                Command::SetApi {
+                    url: url.ok_or_else(|| anyhow!("set-api requires --url"))?,
+                    token: token.ok_or_else(|| anyhow!("set-api requires --token"))?,
+                }
+            }
+            "list" => Command::List,
+            "add-automation" => {
+                let (automation, check_interval) = parse_automation(rest)?;
+                Command::AddAutomation(Box::new(automation), check_interval)
+            }
+            "enable" | "disable" => Command::SetEnabled {
+                automation: rest
+                    .first()
+                    .cloned()
+                    .ok_or_else(|| anyhow!("{} requires an automation id or name", command))?,
+                enabled: command == "enable",
+            },
+            "patch" => Command::Patch {
+                path: rest
+                    .first()
+                    .map(PathBuf::from)
+                    .ok_or_else(|| anyhow!("patch requires a file, or - for stdin"))?,
+            },
+            "check" => Command::Check,
+            "help" | "--help" | "-h" => Command::Help,
+            other => bail!("Unknown command: {}", other),
+        };
+
+        Ok(Some(command))
+    }
+
+    /// Apply the command to the saved configuration
+    pub async fn run(self) -> Result<()> {
+        let mut config = Config::load()?;
+
+        match self {
+            Command::SetApi { url, token } => {
+                let url = normalize_api_url(&url).map_err(|e| anyhow!(e))?;
+                println!("Validating API credentials...");
+                let status = validate_api(&url, &token).await;
+                if !status.is_valid() {
+                    bail!("Could not connect to {}: {}", url, status);
+                }
+                config.api.url = url;
+                config.api.token = token.into();
+                config.save()?;
+                println!("✓ API configuration saved");
+            }
+            Command::List => {
+                if config.notifications.automations.is_empty() {
+                    println!("No automations configured");
+                }
+                for automation in &config.notifications.automations {
+                    let chats = match &automation.chat_match {
+                        Some(pattern) => {
+                            format!("{} chats + '{}'", automation.chat_ids.len(), pattern)
+                        }
+                        None => format!("{} chats", automation.chat_ids.len()),
+                    };
+                    println!(
+                        "[{}] {}  {} ({} - {})",
+                        if automation.enabled { "✓" } else { "✗" },
+                        automation.id,
+                        automation.name,
+                        automation.automation_type,
+                        chats
+                    );
+                }
+                for warning in config.lint() {
+                    println!("⚠ {}", warning);
+                }
+            }
+            Command::AddAutomation(mut automation, check_interval) => {
+                if let Some(loop_config) = &mut automation.loop_config {
+                    loop_config.check_interval =
+                        check_interval.unwrap_or(config.notifications.default_check_interval_ms);
+                }
+                println!("✓ Added automation '{}' ({})", automation.name, automation.id);
+                config.notifications.automations.push(*automation);
+                config.save()?;
+            }
+            Command::SetEnabled {
+                automation,
+                enabled,
+            } => {
+                let found = config
+                    .notifications
+                    .automations
+                    .iter_mut()
+                    .find(|a| a.id == automation || a.name == automation)
+                    .ok_or_else(|| anyhow!("No automation with id or name '{}'", automation))?;
+                found.enabled = enabled;
+                println!(
+                    "✓ {} automation '{}'",
+                    if enabled { "Enabled" } else { "Disabled" },
+                    found.name
+                );
+                config.save()?;
+            }
+            Command::Patch { path } => {
+                let content = if path.as_os_str() == "-" {
+                    std::io::read_to_string(std::io::stdin())?
+                } else {
+                    std::fs::read_to_string(&path)
+                        .map_err(|e| anyhow!("Could not read {}: {}", path.display(), e))?
+                };
+                config.merge(PartialConfig::from_toml(&content)?)?;
+                config.save()?;
+                println!("✓ Patch applied");
+                for warning in config.lint() {
+                    println!("⚠ {}", warning);
+                }
+            }
+            Command::Check => {
+                let status = validate_api(&config.api.url, config.api.token.reveal()).await;
+                if !status.is_valid() {
+                    bail!("API check failed for {}: {}", config.api.url, status);
+                }
+                println!("✓ {}", status);
+
+                let app_state = SharedAppState::new(config.clone());
+                let reports = preflight::check_automations(&app_state)
+                    .await
+                    .map_err(|e| anyhow!("Could not read the chat list: {}", e))?;
+                for report in &reports {
+                    let mark = if report.passed() { "✓" } else { "✗" };
+                    println!("{} {}  {}", mark, report.id, report.name);
+                    for problem in &report.problems {
+                        println!("    {}", problem);
+                    }
+                    for warning in &report.warnings {
+                        println!("    ⚠ {}", warning);
+                    }
+                }
+                let automations = &config.notifications.automations;
+                for automation in automations.iter().filter(|a| !a.enabled) {
+                    println!(
+                        "- {}  {} (disabled, skipped)",
+                        automation.id, automation.name
+                    );
+                }
+
+                let checked = reports.len();
+                let failed = reports.iter().filter(|report| !report.passed()).count();
+                if failed > 0 {
+                    bail!("{} of {} automations failed the check", failed, checked);
+                }
+                println!("✓ All {} enabled automations passed", checked);
+            }
+            Command::Help => println!("{}", USAGE),
+        }
+
+        Ok(())
+    }
+}
+
+/// Remove `--config-dir <DIR>` from the arguments, returning the directory
+pub fn take_config_dir(args: &mut Vec<String>) -> Result<Option<PathBuf>> {
+    let Some(index) = args.iter().position(|arg| arg == "--config-dir") else {
+        return Ok(None);
+    };
+    let dir = flag_value("--config-dir", args.get(index + 1))?;
+    args.drain(index..index + 2);
+    Ok(Some(PathBuf::from(dir)))
+}
+
+fn flag_value(flag: &str, value: Option<&String>) -> Result<String> {
+    value
+        .cloned()
+        .ok_or_else(|| anyhow!("{} requires a value", flag))
+}
+
+/// The automation described by the `add-automation` flags, and the check
+/// interval if `--interval` gave one
+fn parse_automation(args: &[String]) -> Result<(NotificationAutomation, Option<u64>)> {
+    let mut name = None;
+    let mut chat_ids = Vec::new();
+    let mut chat_match = None;
+    let mut automation_type = AutomationType::Immediate;
+    let mut until = LoopUntil::MessageSeen;
+    let mut time = None;
+    let mut check_interval = None;
+    let mut sound = None;
+    let mut focus = false;
+    let mut desktop_notification = false;
+    let mut schedule = None;
+    let mut enabled = true;
+
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "--name" => name = Some(flag_value(flag, args.next())?),
+            "--chat" => chat_ids.push(flag_value(flag, args.next())?),
+            "--match" => chat_match = Some(flag_value(flag, args.next())?),
+            "--type" => {
+                automation_type = match flag_value(flag, args.next())?.as_str() {
+                    "immediate" => AutomationType::Immediate,
+                    "loop" => AutomationType::Loop,
+                    "combined" => AutomationType::Combined,
+                    "new_chat" => AutomationType::NewChat,
+                    other => bail!("Unknown automation type: {}", other),
+                }
+            }
+            "--until" => {
+                until = match flag_value(flag, args.next())?.as_str() {
+                    "message_seen" => LoopUntil::MessageSeen,
+                    "answer" => LoopUntil::Answer,
+                    "for_a_time" => LoopUntil::ForATime,
+                    "once" => LoopUntil::Once,
+                    other => bail!("Unknown loop condition: {}", other),
+                }
+            }
+            "--time" => time = Some(parse_number(flag, args.next())?),
+            "--interval" => check_interval = Some(parse_number(flag, args.next())?),
+            "--sound" => sound = Some(flag_value(flag, args.next())?),
+            "--focus" => focus = true,
+            "--desktop-notification" => desktop_notification = true,
+            "--schedule" => {
+                schedule = Some(
+                    flag_value(flag, args.next())?
+                        .parse::<Schedule>()
+                        .map_err(|e| anyhow!(e))?,
+                )
+            }
+            "--disabled" => enabled = false,
+            other => bail!("Unknown option for add-automation: {}", other),
+        }
+    }
+
+    let name = name.ok_or_else(|| anyhow!("add-automation requires --name"))?;
+    if automation_type.watches_chats() && chat_ids.is_empty() && chat_match.is_none() {
+        bail!("add-automation requires at least one --chat or a --match pattern");
+    }
+
+    let mut automation =
+        NotificationAutomation::new(uuid::Uuid::new_v4().to_string(), name, chat_ids);
+    automation.chat_match = chat_match;
+    automation.automation_type = automation_type;
+    automation.notification_sound = sound;
+    automation.focus_chat = focus;
+    automation.desktop_notification = desktop_notification;
+    automation.schedule = schedule;
+    automation.enabled = enabled;
+    if automation_type.uses_loop_config() {
+        automation.loop_config = Some(LoopConfig {
+            until,
+            time,
+            check_interval: check_interval.unwrap_or_default(),
+            min_unread: None,
+        });
+    }
+
+    Ok((automation, check_interval))
+}
+
+fn parse_number(flag: &str, value: Option<&String>) -> Result<u64> {
+    let value = flag_value(flag, value)?;
+    value
+        .parse()
+        .map_err(|_| anyhow!("{} expects a number of milliseconds, got '{}'", flag, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_no_args_opens_tui() {
+        assert!(Command::parse(&[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_add_loop_automation() {
+        let command = Command::parse(&args(
+            "add-automation --name Nag --chat a --chat b --type loop --until for_a_time --time 60000",
+        ))
+        .unwrap();
+
+        let Some(Command::AddAutomation(automation, check_interval)) = command else {
+            panic!("expected add-automation, got {:?}", command);
+        };
+        assert_eq!(automation.name, "Nag");
+        assert_eq!(check_interval, None, "left to the config's default");
+        assert_eq!(automation.chat_ids, vec!["a".to_string(), "b".to_string()]);
+        let loop_config = automation.loop_config.expect("loop config");
+        assert_eq!(loop_config.until, LoopUntil::ForATime);
+        assert_eq!(loop_config.time, Some(60000));
+
+        let command =
+            Command::parse(&args("add-automation --name Oncall --match oncall-*")).unwrap();
+        let Some(Command::AddAutomation(automation, _)) = command else {
+            panic!("expected add-automation, got {:?}", command);
+        };
+        assert!(automation.chat_ids.is_empty());
+
+        let command = Command::parse(&args(
+            "add-automation --name Fast --chat a --type loop --interval 1500",
+        ))
+        .unwrap();
+        let Some(Command::AddAutomation(_, check_interval)) = command else {
+            panic!("expected add-automation, got {:?}", command);
+        };
+        assert_eq!(check_interval, Some(1500));
+        assert_eq!(automation.chat_match.as_deref(), Some("oncall-*"));
+    }
+
+    #[test]
+    fn test_take_config_dir() {
+        let mut with_dir = args("--config-dir /tmp/beeper list");
+        assert_eq!(take_config_dir(&mut with_dir).unwrap(), Some(PathBuf::from("/tmp/beeper")));
+        assert_eq!(with_dir, args("list"));
+
+        let mut without = args("list");
+        assert_eq!(take_config_dir(&mut without).unwrap(), None);
+        assert!(take_config_dir(&mut args("list --config-dir")).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Command::parse(&args("set-api --url http://localhost:23373")).is_err());
+        assert!(Command::parse(&args("add-automation --name NoChats")).is_err());
+        assert!(Command::parse(&args("enable")).is_err());
+        assert!(Command::parse(&args("patch")).is_err());
+        assert!(Command::parse(&args("frobnicate")).is_err());
+    }
+}
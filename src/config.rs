@@ -1,8 +1,15 @@
-use crate::notifications::NotificationAutomation;
+use crate::notifications::{NotificationAutomation, NotificationProfile, ScheduledFocus};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use thiserror::Error;
 
+/// Environment variable that overrides the config directory
+pub const CONFIG_DIR_ENV: &str = "BEEPER_CONFIG_DIR";
+
+/// Set from `--config-dir`; takes precedence over `BEEPER_CONFIG_DIR`
+static CONFIG_DIR_OVERRIDE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("IO error: {0}")]
@@ -11,8 +18,132 @@ pub enum ConfigError {
     TomlError(#[from] toml::de::Error),
     #[error("TOML serialization error: {0}")]
     TomlSerError(#[from] toml::ser::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("YAML error: {0}")]
+    YamlError(#[from] serde_yaml::Error),
     #[error("Missing configuration directory")]
     NoConfigDir,
+    #[error("Cannot write to {dir:?}: {source}. {}", not_writable_hint(.source))]
+    NotWritable {
+        dir: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid patch: {0}")]
+    InvalidPatch(String),
+}
+
+/// What to do about a directory the config can't be written to
+fn not_writable_hint(error: &std::io::Error) -> &'static str {
+    match error.kind() {
+        std::io::ErrorKind::StorageFull => "The disk is full; free up some space and save again",
+        std::io::ErrorKind::ReadOnlyFilesystem => {
+            "The disk is mounted read-only; use --config-dir or BEEPER_CONFIG_DIR to keep the config somewhere writable"
+        }
+        std::io::ErrorKind::PermissionDenied => {
+            "Check who owns the directory and its permissions, or use --config-dir or BEEPER_CONFIG_DIR to keep the config somewhere writable"
+        }
+        _ => "Use --config-dir or BEEPER_CONFIG_DIR to keep the config somewhere writable",
+    }
+}
+
+/// File written and removed again to find out whether the config directory
+/// can be written to
+pub const WRITE_PROBE_FILE: &str = ".write-probe";
+
+/// File formats the config can be kept in, told apart by file extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// The format of the file at `path`; anything not `.json`, `.yaml` or
+    /// `.yml` is read as TOML
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ConfigFormat::Json,
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => {
+                ConfigFormat::Yaml
+            }
+            _ => ConfigFormat::Toml,
+        }
+    }
+
+    pub(crate) fn parse<T: serde::de::DeserializeOwned>(
+        self,
+        content: &str,
+    ) -> Result<T, ConfigError> {
+        Ok(match self {
+            ConfigFormat::Toml => toml::from_str(content)?,
+            ConfigFormat::Json => serde_json::from_str(content)?,
+            ConfigFormat::Yaml => serde_yaml::from_str(content)?,
+        })
+    }
+
+    fn serialize<T: Serialize>(self, value: &T) -> Result<String, ConfigError> {
+        Ok(match self {
+            ConfigFormat::Toml => toml::to_string_pretty(value)?,
+            ConfigFormat::Json => serde_json::to_string_pretty(value)?,
+            ConfigFormat::Yaml => serde_yaml::to_string(value)?,
+        })
+    }
+}
+
+/// Config file names looked for in the config directory, in order of
+/// preference. A new config is written as the first.
+const CONFIG_FILE_NAMES: [&str; 4] = ["config.toml", "config.json", "config.yaml", "config.yml"];
+
+/// The config file in `dir`: the first of `CONFIG_FILE_NAMES` that exists,
+/// or `config.toml` when none does
+fn find_config_file(dir: &Path) -> PathBuf {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| dir.join(CONFIG_FILE_NAMES[0]))
+}
+
+/// Create `dir` if needed, then write and remove a probe file in it
+fn check_dir_writable(dir: &Path) -> Result<(), ConfigError> {
+    let not_writable = |source| ConfigError::NotWritable {
+        dir: dir.to_path_buf(),
+        source,
+    };
+    std::fs::create_dir_all(dir).map_err(not_writable)?;
+    let probe = dir.join(WRITE_PROBE_FILE);
+    std::fs::write(&probe, b"").map_err(not_writable)?;
+    std::fs::remove_file(&probe).ok();
+    Ok(())
+}
+
+/// A likely misconfiguration found by `Config::lint`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintWarning {
+    pub automation_id: String,
+    pub automation_name: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.automation_name.trim().is_empty() {
+            write!(f, "Automation {}: {}", self.automation_id, self.message)
+        } else {
+            write!(f, "'{}': {}", self.automation_name, self.message)
+        }
+    }
+}
+
+/// How imported automations are combined with the existing ones
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportMode {
+    /// Keep existing automations and add the imported ones
+    Merge,
+    /// Drop existing automations and use only the imported ones
+    Replace,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +152,65 @@ pub struct Config {
     pub api: ApiConfig,
     #[serde(default)]
     pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub control: ControlConfig,
+    /// Shared settings automations can refer to by name
+    #[serde(default)]
+    pub notification_profiles: Vec<NotificationProfile>,
+}
+
+/// Optional Prometheus endpoint, read when the service starts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Port to serve `/metrics` on; no endpoint when unset
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Address to listen on; use 0.0.0.0 to allow scraping from other machines
+    #[serde(default = "default_metrics_address")]
+    pub address: String,
+}
+
+fn default_metrics_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            port: None,
+            address: default_metrics_address(),
+        }
+    }
+}
+
+/// Local control port the configurator uses to command a running service,
+/// read when the service starts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlConfig {
+    #[serde(default = "default_control_enabled")]
+    pub enabled: bool,
+    /// Port on 127.0.0.1 to listen on
+    #[serde(default = "default_control_port")]
+    pub port: u16,
+}
+
+fn default_control_enabled() -> bool {
+    true
+}
+
+fn default_control_port() -> u16 {
+    23380
+}
+
+impl Default for ControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_control_enabled(),
+            port: default_control_port(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,19 +218,169 @@ pub struct ApiConfig {
     #[serde(default)]
     pub url: String,
     #[serde(default)]
-    pub token: String,
+    pub token: RedactedToken,
+}
+
+/// Tidy up a pasted API URL: add `http://` when the scheme is missing and
+/// drop trailing slashes. Returns an error for URLs that can't work.
+pub fn normalize_api_url(input: &str) -> Result<String, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("The API URL is empty".to_string());
+    }
+    if input.contains(char::is_whitespace) {
+        return Err(format!("The API URL '{}' contains spaces", input));
+    }
+
+    let (scheme, rest) = match input.split_once("://") {
+        Some((scheme, rest)) => (scheme.to_lowercase(), rest),
+        None => ("http".to_string(), input),
+    };
+    if scheme != "http" && scheme != "https" {
+        return Err(format!(
+            "The API URL must start with http:// or https://, got '{}://'",
+            scheme
+        ));
+    }
+
+    let rest = rest.trim_end_matches('/');
+    let authority = rest.split('/').next().unwrap_or_default();
+    let (host, port) = match authority.strip_prefix('[') {
+        // IPv6 literal, e.g. [::1]:23373
+        Some(bracketed) => match bracketed.split_once(']') {
+            Some((host, tail)) => (host, tail.strip_prefix(':')),
+            None => ("", None),
+        },
+        None => match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        },
+    };
+    if host.is_empty() {
+        return Err(format!("The API URL '{}' has no host", input));
+    }
+    if let Some(port) = port
+        && port.parse::<u16>().is_err()
+    {
+        return Err(format!("The API URL '{}' has an invalid port", input));
+    }
+
+    Ok(format!("{}://{}", scheme, rest))
+}
+
+/// A secret that formats as `****`, so a derived `Debug` or a log line
+/// never prints it. Use `reveal` where the real value is needed.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RedactedToken(String);
+
+impl RedactedToken {
+    /// The actual token, for building API clients and requests
+    pub fn reveal(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for RedactedToken {
+    fn from(token: String) -> Self {
+        Self(token)
+    }
+}
+
+impl std::fmt::Debug for RedactedToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "****")
+    }
+}
+
+impl std::fmt::Display for RedactedToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "****")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationsConfig {
     #[serde(default)]
     pub automations: Vec<NotificationAutomation>,
+    /// Log the actions automations would take instead of performing them
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Keep watching chats but suppress every automation's actions
+    #[serde(default)]
+    pub paused: bool,
+    /// Ceiling on Beeper API requests per minute across all automations
+    #[serde(default)]
+    pub max_requests_per_minute: Option<u32>,
+    /// Randomize poll timing so automations don't all hit the API at once
+    #[serde(default)]
+    pub jitter: bool,
+    /// Sound played when Beeper API requests start failing
+    #[serde(default)]
+    pub error_sound: Option<String>,
+    /// Sound played when the API answers again after `error_sound` played
+    #[serde(default)]
+    pub recovery_sound: Option<String>,
+    /// Check interval the configurator fills in for new automations
+    #[serde(default = "default_check_interval_ms")]
+    pub default_check_interval_ms: u64,
+    /// Chats to open at set times, whatever arrived
+    #[serde(default)]
+    pub scheduled_focus: Vec<ScheduledFocus>,
+    /// How long to remember where an automation was in a chat it stopped
+    /// watching. Adding the chat back within this time alerts for messages
+    /// that arrived meanwhile; after it, the chat starts fresh.
+    #[serde(default = "default_keep_removed_chats_secs")]
+    pub keep_removed_chats_secs: u64,
+    /// Shortest gap between two focus actions. Of the focus actions arriving
+    /// within it, only the most recent opens its chat once the gap is over.
+    #[serde(default = "default_focus_cooldown_ms")]
+    pub focus_cooldown_ms: u64,
+    /// Alerts a minute past which an automation counts as flapping, which
+    /// usually means it's misconfigured. 0 turns the check off.
+    #[serde(default = "default_flapping_alerts_per_minute")]
+    pub flapping_alerts_per_minute: u32,
+    /// Snooze a flapping automation for a while instead of only warning
+    #[serde(default)]
+    pub snooze_flapping: bool,
+}
+
+fn default_check_interval_ms() -> u64 {
+    3000
+}
+
+fn default_keep_removed_chats_secs() -> u64 {
+    60 * 60
+}
+
+fn default_focus_cooldown_ms() -> u64 {
+    2000
+}
+
+fn default_flapping_alerts_per_minute() -> u32 {
+    20
 }
 
 impl Default for NotificationsConfig {
     fn default() -> Self {
         Self {
             automations: Vec::new(),
+            dry_run: false,
+            paused: false,
+            max_requests_per_minute: None,
+            jitter: false,
+            error_sound: None,
+            recovery_sound: None,
+            default_check_interval_ms: default_check_interval_ms(),
+            scheduled_focus: Vec::new(),
+            keep_removed_chats_secs: default_keep_removed_chats_secs(),
+            focus_cooldown_ms: default_focus_cooldown_ms(),
+            flapping_alerts_per_minute: default_flapping_alerts_per_minute(),
+            snooze_flapping: false,
         }
     }
 }
@@ -49,7 +389,7 @@ impl Default for ApiConfig {
     fn default() -> Self {
         Self {
             url: "http://localhost:23373".to_string(),
-            token: String::new(),
+            token: RedactedToken::default(),
         }
     }
 }
@@ -59,57 +399,644 @@ impl Default for Config {
         Self {
             api: ApiConfig::default(),
             notifications: NotificationsConfig::default(),
+            metrics: MetricsConfig::default(),
+            control: ControlConfig::default(),
+            notification_profiles: Vec::new(),
+        }
+    }
+}
+
+/// Changes for `Config::merge`. Every field is optional; only the ones given
+/// change, so a patch can be as small as one automation's `enabled`. Keys
+/// the config doesn't have are rejected rather than ignored, so a typo
+/// doesn't pass for a change.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialConfig {
+    #[serde(default)]
+    pub api: Option<PartialApiConfig>,
+    #[serde(default)]
+    pub notifications: Option<PartialNotificationsConfig>,
+    #[serde(default)]
+    pub metrics: Option<PartialMetricsConfig>,
+    #[serde(default)]
+    pub control: Option<PartialControlConfig>,
+    /// Profiles to change or add, each with a `name`, merged like automations
+    #[serde(default)]
+    pub notification_profiles: Vec<toml::Table>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialApiConfig {
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub token: Option<RedactedToken>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialNotificationsConfig {
+    /// Automations to change or add, each with an `id`. One whose id exists
+    /// has just the given fields changed; the rest are added, with defaults
+    /// for the fields they leave out.
+    #[serde(default)]
+    pub automations: Vec<toml::Table>,
+    #[serde(default)]
+    pub dry_run: Option<bool>,
+    #[serde(default)]
+    pub paused: Option<bool>,
+    #[serde(default)]
+    pub max_requests_per_minute: Option<u32>,
+    #[serde(default)]
+    pub jitter: Option<bool>,
+    #[serde(default)]
+    pub error_sound: Option<String>,
+    #[serde(default)]
+    pub recovery_sound: Option<String>,
+    #[serde(default)]
+    pub default_check_interval_ms: Option<u64>,
+    #[serde(default)]
+    pub keep_removed_chats_secs: Option<u64>,
+    #[serde(default)]
+    pub focus_cooldown_ms: Option<u64>,
+    #[serde(default)]
+    pub flapping_alerts_per_minute: Option<u32>,
+    #[serde(default)]
+    pub snooze_flapping: Option<bool>,
+    /// Scheduled focus entries to change or add, each with an `id`, merged
+    /// like automations
+    #[serde(default)]
+    pub scheduled_focus: Vec<toml::Table>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialMetricsConfig {
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PartialControlConfig {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+impl PartialConfig {
+    /// Read a patch written as TOML, in the same layout as config.toml
+    pub fn from_toml(content: &str) -> Result<Self, ConfigError> {
+        Ok(toml::from_str(content)?)
+    }
+}
+
+/// Keys in `patch` that `merged`, the patched value read back and written out
+/// again, doesn't have: ones the type it was read as has no field for
+fn unknown_keys(patch: &toml::Table, merged: &toml::Table, prefix: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for (key, value) in patch {
+        let path = format!("{}{}", prefix, key);
+        match (value, merged.get(key)) {
+            (_, None) => unknown.push(path),
+            (toml::Value::Table(patch), Some(toml::Value::Table(merged))) => {
+                unknown.extend(unknown_keys(patch, merged, &format!("{}.", path)));
+            }
+            _ => {}
+        }
+    }
+    unknown
+}
+
+/// Merge `patches` into `entries`, matching them up by their `key` field.
+/// An entry that exists has just the given fields changed; the rest are
+/// added on top of what `new` makes for their key.
+fn merge_entries<T>(
+    entries: &mut Vec<T>,
+    patches: Vec<toml::Table>,
+    kind: &str,
+    key: &str,
+    key_of: impl Fn(&T) -> &str,
+    new: impl Fn(String) -> T,
+) -> Result<(), ConfigError>
+where
+    T: Clone + Serialize + serde::de::DeserializeOwned,
+{
+    for patch in patches {
+        let id = patch
+            .get(key)
+            .and_then(toml::Value::as_str)
+            .ok_or_else(|| ConfigError::InvalidPatch(format!("every {} needs `{}`", kind, key)))?
+            .to_string();
+        let invalid =
+            |message: String| ConfigError::InvalidPatch(format!("{} {}: {}", kind, id, message));
+
+        let index = entries.iter().position(|entry| key_of(entry) == id);
+        let base = match index {
+            Some(index) => entries[index].clone(),
+            None => new(id.clone()),
+        };
+        let mut value = toml::Value::try_from(&base)?;
+        if let toml::Value::Table(table) = &mut value {
+            merge_table(table, patch.clone());
+        }
+        let entry: T = value
+            .try_into()
+            .map_err(|e: toml::de::Error| invalid(e.to_string()))?;
+
+        if let toml::Value::Table(merged) = toml::Value::try_from(&entry)? {
+            let unknown = unknown_keys(&patch, &merged, "");
+            if !unknown.is_empty() {
+                return Err(invalid(format!("unknown field(s) {}", unknown.join(", "))));
+            }
+        }
+
+        match index {
+            Some(index) => entries[index] = entry,
+            None => entries.push(entry),
+        }
+    }
+    Ok(())
+}
+
+/// Copy `patch` over `target`, merging tables key by key and replacing
+/// everything else
+fn merge_table(target: &mut toml::Table, patch: toml::Table) {
+    for (key, value) in patch {
+        match (target.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(value)) => {
+                merge_table(existing, value)
+            }
+            (_, value) => {
+                target.insert(key, value);
+            }
         }
     }
 }
 
 impl Config {
-    /// Get the configuration file path
-    pub fn config_file_path() -> Result<PathBuf, ConfigError> {
+    /// Use `dir` as the config directory for the rest of the process
+    pub fn set_config_dir(dir: PathBuf) {
+        *CONFIG_DIR_OVERRIDE.lock().unwrap() = Some(dir);
+    }
+
+    /// Whether `--config-dir` or `BEEPER_CONFIG_DIR` replaced the default directory
+    pub fn config_dir_overridden() -> bool {
+        CONFIG_DIR_OVERRIDE.lock().unwrap().is_some()
+            || std::env::var_os(CONFIG_DIR_ENV).is_some_and(|dir| !dir.is_empty())
+    }
+
+    /// Get the configuration directory
+    pub fn config_dir() -> Result<PathBuf, ConfigError> {
+        if let Some(dir) = CONFIG_DIR_OVERRIDE.lock().unwrap().clone() {
+            return Ok(dir);
+        }
+        if let Some(dir) = std::env::var_os(CONFIG_DIR_ENV).filter(|dir| !dir.is_empty()) {
+            return Ok(PathBuf::from(dir));
+        }
         let config_dir = dirs::config_dir().ok_or(ConfigError::NoConfigDir)?;
-        Ok(config_dir.join("beeper-automations").join("config.toml"))
+        Ok(config_dir.join("beeper-automations"))
+    }
+
+    /// Get the configuration file path: `config.toml`, or a `config.json`
+    /// or `config.yaml` in its place
+    pub fn config_file_path() -> Result<PathBuf, ConfigError> {
+        Ok(find_config_file(&Self::config_dir()?))
     }
 
     /// Load configuration from file, creating default if it doesn't exist
     pub fn load() -> Result<Self, ConfigError> {
-        let config_path = Self::config_file_path()?;
+        Self::load_from(&Self::config_file_path()?)
+    }
 
+    /// Load configuration from the given file, in the format its extension
+    /// names, creating default if it doesn't exist. Where it can't be
+    /// created, the default is still returned; it just isn't on disk.
+    pub fn load_from(config_path: &Path) -> Result<Self, ConfigError> {
         if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)?;
-            Ok(toml::from_str(&content)?)
+            let content = std::fs::read_to_string(config_path)?;
+            let mut config: Config = ConfigFormat::from_path(config_path).parse(&content)?;
+            config.clear_expired_snoozes();
+            Ok(config)
         } else {
             // Create default config
             let config = Config::default();
-            config.save()?;
+            match config.save_to(config_path) {
+                Ok(()) => {}
+                Err(e @ ConfigError::NotWritable { .. }) => {
+                    tracing::warn!("Using the default config without saving it: {}", e);
+                }
+                Err(e) => return Err(e),
+            }
             Ok(config)
         }
     }
 
     /// Save configuration to file
     pub fn save(&self) -> Result<(), ConfigError> {
-        let config_path = Self::config_file_path()?;
+        self.save_to(&Self::config_file_path()?)
+    }
+
+    /// Save configuration to the given file, in the format its extension names
+    pub fn save_to(&self, config_path: &Path) -> Result<(), ConfigError> {
+        let dir = config_path.parent().unwrap_or(Path::new(""));
+        let not_writable = |source| ConfigError::NotWritable {
+            dir: dir.to_path_buf(),
+            source,
+        };
 
         // Create parent directories if they don't exist
-        if let Some(parent) = config_path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
+        std::fs::create_dir_all(dir).map_err(not_writable)?;
 
-        let content = toml::to_string_pretty(self)?;
-        std::fs::write(&config_path, content)?;
+        let content = ConfigFormat::from_path(config_path).serialize(self)?;
+        std::fs::write(config_path, content).map_err(not_writable)?;
 
         Ok(())
     }
 
+    /// Check that the config directory can be written to, so a config that
+    /// won't save is found out before anything is changed
+    pub fn check_writable() -> Result<(), ConfigError> {
+        check_dir_writable(&Self::config_dir()?)
+    }
+
+    /// Drop snoozes that have already run out
+    pub fn clear_expired_snoozes(&mut self) {
+        for automation in &mut self.notifications.automations {
+            if automation.snoozed_until.is_some() && !automation.is_snoozed() {
+                automation.snoozed_until = None;
+            }
+        }
+    }
+
     /// Check if API credentials are configured
     pub fn is_api_configured(&self) -> bool {
         !self.api.token.is_empty() && !self.api.url.is_empty()
     }
+
+    /// Check enabled automations for settings that are probably mistakes
+    pub fn lint(&self) -> Vec<LintWarning> {
+        use crate::notifications::{CronSchedule, LoopUntil, sounds};
+
+        let enabled: Vec<_> = self
+            .notifications
+            .automations
+            .iter()
+            .filter(|a| a.enabled)
+            .collect();
+        let mut warnings = Vec::new();
+
+        for automation in &enabled {
+            let mut warn = |message: String| {
+                warnings.push(LintWarning {
+                    automation_id: automation.id.clone(),
+                    automation_name: automation.name.clone(),
+                    message,
+                })
+            };
+
+            if automation.name.trim().is_empty() {
+                warn("has no name".to_string());
+            }
+
+            if automation.automation_type.watches_chats()
+                && automation.chat_ids.is_empty()
+                && automation.chat_match.is_none()
+            {
+                warn("no chats selected, it will never trigger".to_string());
+            }
+
+            if automation
+                .chat_match
+                .as_deref()
+                .is_some_and(|pattern| pattern.trim().is_empty())
+            {
+                warn("chat_match is empty and matches no chats".to_string());
+            }
+
+            if automation.mentions_only
+                && automation.mention_names.iter().all(|n| n.trim().is_empty())
+            {
+                warn("mentions_only is set but mention_names is empty".to_string());
+            }
+
+            for sound in automation
+                .notification_sound
+                .iter()
+                .chain(automation.chat_sound_overrides.values())
+                .chain(automation.sound_rules.iter().map(|rule| &rule.sound))
+            {
+                if sounds::is_builtin(sound) && sounds::builtin(sound).is_none() {
+                    warn(format!("'{}' is not a built-in sound", sound));
+                }
+            }
+
+            for rule in &automation.sound_rules {
+                if let Err(e) = regex::Regex::new(&rule.pattern) {
+                    warn(format!(
+                        "sound rule pattern '{}' is invalid and skipped: {}",
+                        rule.pattern, e
+                    ));
+                }
+            }
+
+            if let Some(webhook) = &automation.webhook {
+                if reqwest::Url::parse(webhook.url.trim()).is_err() {
+                    warn(format!("webhook URL '{}' is not a valid URL", webhook.url));
+                }
+                if webhook.http_method().is_none() {
+                    warn(format!("webhook method '{}' is not valid", webhook.method));
+                }
+            }
+
+            if let Some(Err(e)) = automation.cron.as_deref().map(str::parse::<CronSchedule>) {
+                warn(format!("cron expression is ignored: {}", e));
+            }
+
+            if let Some(profile) = &automation.profile
+                && !self.notification_profiles.iter().any(|p| &p.name == profile)
+            {
+                warn(format!("profile '{}' is not defined", profile));
+            }
+
+            if automation.automation_type.uses_loop_config() {
+                match &automation.loop_config {
+                    None => warn("loop automation has no loop settings".to_string()),
+                    Some(loop_config)
+                        if loop_config.until == LoopUntil::ForATime
+                            && loop_config.time.unwrap_or(0) == 0 =>
+                    {
+                        warn("'For A Time' loop has no duration set".to_string())
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            // The same chat in an immediate and a loop automation alerts twice
+            for other in &enabled {
+                if other.automation_type == automation.automation_type {
+                    continue;
+                }
+                for chat_id in automation
+                    .chat_ids
+                    .iter()
+                    .filter(|chat_id| other.chat_ids.contains(chat_id))
+                {
+                    warn(format!(
+                        "chat {} is also watched by {} automation '{}'",
+                        chat_id,
+                        other.automation_type.to_string().to_lowercase(),
+                        other.name
+                    ));
+                }
+            }
+        }
+
+        let scheduled_focus = self.notifications.scheduled_focus.iter();
+        for entry in scheduled_focus.filter(|e| e.enabled) {
+            let mut warn = |message: String| {
+                warnings.push(LintWarning {
+                    automation_id: entry.id.clone(),
+                    automation_name: entry.name.clone(),
+                    message,
+                })
+            };
+
+            if entry.chat_id.trim().is_empty() {
+                warn("scheduled focus has no chat".to_string());
+            }
+            if let Err(e) = entry.cron.parse::<CronSchedule>() {
+                warn(format!("scheduled focus has an invalid cron: {}", e));
+            }
+        }
+
+        warnings
+    }
+
+    /// Write a portable copy of the configuration, optionally without the API
+    /// token, in the format the path's extension names
+    pub fn export_to(&self, path: &Path, include_token: bool) -> Result<(), ConfigError> {
+        let mut exported = self.clone();
+        if !include_token {
+            exported.api.token = RedactedToken::default();
+        }
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let content = ConfigFormat::from_path(path).serialize(&exported)?;
+        std::fs::write(path, content)?;
+
+        Ok(())
+    }
+
+    /// Read and validate a configuration exported with `export_to`
+    pub fn import_from(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path)?;
+        ConfigFormat::from_path(path).parse(&content)
+    }
+
+    /// Names of imported automations that clash with existing ones
+    pub fn conflicting_automations(&self, imported: &Config) -> Vec<String> {
+        imported
+            .notifications
+            .automations
+            .iter()
+            .filter(|a| {
+                self.notifications
+                    .automations
+                    .iter()
+                    .any(|existing| existing.name == a.name)
+            })
+            .map(|a| a.name.clone())
+            .collect()
+    }
+
+    /// Bring in another config's automations. API settings are never imported.
+    /// When merging, `overwrite_conflicts` decides whether an imported
+    /// automation replaces an existing one with the same name or is skipped.
+    pub fn import_automations(
+        &mut self,
+        imported: Config,
+        mode: ImportMode,
+        overwrite_conflicts: bool,
+    ) {
+        if mode == ImportMode::Replace {
+            self.notifications.automations = imported.notifications.automations;
+            return;
+        }
+
+        for mut automation in imported.notifications.automations {
+            let existing = self
+                .notifications
+                .automations
+                .iter_mut()
+                .find(|a| a.name == automation.name);
+
+            match existing {
+                Some(existing) if overwrite_conflicts => {
+                    automation.id = existing.id.clone();
+                    *existing = automation;
+                }
+                Some(_) => {}
+                None => {
+                    if self
+                        .notifications
+                        .automations
+                        .iter()
+                        .any(|a| a.id == automation.id)
+                    {
+                        automation.id = uuid::Uuid::new_v4().to_string();
+                    }
+                    self.notifications.automations.push(automation);
+                }
+            }
+        }
+    }
+
+    /// Apply a partial update. Automations in the patch are matched by id:
+    /// a match has just the patched fields changed, an unknown id is added,
+    /// and automations the patch doesn't mention are left alone. Nothing
+    /// changes when any part of the patch is invalid.
+    pub fn merge(&mut self, patch: PartialConfig) -> Result<(), ConfigError> {
+        let mut merged = self.clone();
+
+        if let Some(api) = patch.api {
+            if let Some(url) = api.url {
+                merged.api.url = url;
+            }
+            if let Some(token) = api.token {
+                merged.api.token = token;
+            }
+        }
+
+        if let Some(metrics) = patch.metrics {
+            if let Some(port) = metrics.port {
+                merged.metrics.port = Some(port);
+            }
+            if let Some(address) = metrics.address {
+                merged.metrics.address = address;
+            }
+        }
+
+        if let Some(control) = patch.control {
+            if let Some(enabled) = control.enabled {
+                merged.control.enabled = enabled;
+            }
+            if let Some(port) = control.port {
+                merged.control.port = port;
+            }
+        }
+
+        if let Some(notifications) = patch.notifications {
+            let target = &mut merged.notifications;
+            if let Some(dry_run) = notifications.dry_run {
+                target.dry_run = dry_run;
+            }
+            if let Some(paused) = notifications.paused {
+                target.paused = paused;
+            }
+            if let Some(limit) = notifications.max_requests_per_minute {
+                target.max_requests_per_minute = Some(limit);
+            }
+            if let Some(jitter) = notifications.jitter {
+                target.jitter = jitter;
+            }
+            if let Some(sound) = notifications.error_sound {
+                target.error_sound = Some(sound);
+            }
+            if let Some(sound) = notifications.recovery_sound {
+                target.recovery_sound = Some(sound);
+            }
+            if let Some(interval) = notifications.default_check_interval_ms {
+                target.default_check_interval_ms = interval;
+            }
+            if let Some(secs) = notifications.keep_removed_chats_secs {
+                target.keep_removed_chats_secs = secs;
+            }
+            if let Some(cooldown) = notifications.focus_cooldown_ms {
+                target.focus_cooldown_ms = cooldown;
+            }
+            if let Some(per_minute) = notifications.flapping_alerts_per_minute {
+                target.flapping_alerts_per_minute = per_minute;
+            }
+            if let Some(snooze) = notifications.snooze_flapping {
+                target.snooze_flapping = snooze;
+            }
+
+            // A new automation starts from the defaults the configurator uses
+            merge_entries(
+                &mut target.automations,
+                notifications.automations,
+                "automation",
+                "id",
+                |automation| &automation.id,
+                |id| NotificationAutomation::new(id, String::new(), Vec::new()),
+            )?;
+            merge_entries(
+                &mut target.scheduled_focus,
+                notifications.scheduled_focus,
+                "scheduled focus",
+                "id",
+                |entry| &entry.id,
+                |id| ScheduledFocus {
+                    id,
+                    ..ScheduledFocus::new(String::new(), String::new(), String::new())
+                },
+            )?;
+        }
+
+        merge_entries(
+            &mut merged.notification_profiles,
+            patch.notification_profiles,
+            "profile",
+            "name",
+            |profile| &profile.name,
+            |name| NotificationProfile {
+                name,
+                notification_sound: None,
+                ntfy_config: None,
+                desktop_notification: false,
+            },
+        )?;
+
+        *self = merged;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_normalize_api_url() {
+        assert_eq!(
+            normalize_api_url("localhost:23373/").unwrap(),
+            "http://localhost:23373"
+        );
+        assert_eq!(
+            normalize_api_url(" HTTPS://beeper.example.com//").unwrap(),
+            "https://beeper.example.com"
+        );
+        assert_eq!(
+            normalize_api_url("http://[::1]:23373").unwrap(),
+            "http://[::1]:23373"
+        );
+
+        assert!(normalize_api_url("").is_err());
+        assert!(normalize_api_url("ftp://localhost").is_err());
+        assert!(normalize_api_url("http://:23373").is_err());
+        assert!(normalize_api_url("localhost:port").is_err());
+        assert!(normalize_api_url("local host:23373").is_err());
+    }
+
     #[test]
     fn test_default_config() {
         let config = Config::default();
@@ -117,12 +1044,285 @@ mod tests {
         assert!(config.api.token.is_empty());
     }
 
+    #[test]
+    fn test_load_creates_and_round_trips_config() {
+        let dir = std::env::temp_dir().join(format!("beeper-config-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("config.toml");
+
+        let config = Config::load_from(&path).unwrap();
+        assert!(path.exists());
+        assert!(!config.is_api_configured());
+
+        let mut config = config;
+        config.api.token = "test-token".to_string().into();
+        config.notifications.jitter = true;
+        config.save_to(&path).unwrap();
+
+        let loaded = Config::load_from(&path).unwrap();
+        assert_eq!(loaded.api.token.reveal(), "test-token");
+        assert!(loaded.notifications.jitter);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_config_file_in_json_or_yaml() {
+        let dir = std::env::temp_dir().join(format!("beeper-config-{}", uuid::Uuid::new_v4()));
+        assert_eq!(find_config_file(&dir), dir.join("config.toml"));
+
+        let mut config = Config::default();
+        config.notifications.jitter = true;
+        for name in ["config.json", "config.yaml"] {
+            let path = dir.join(name);
+            config.save_to(&path).unwrap();
+            assert_eq!(find_config_file(&dir), path);
+            assert!(Config::load_from(&path).unwrap().notifications.jitter);
+            std::fs::remove_file(&path).unwrap();
+        }
+
+        let path = dir.join("config.json");
+        config.save_to(&path).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with('{'));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_unwritable_config_dir() {
+        // A directory below a plain file can't be created, whoever runs the test
+        let file = std::env::temp_dir().join(format!("beeper-config-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&file, "").unwrap();
+        let dir = file.join("config");
+
+        let error = check_dir_writable(&dir).unwrap_err();
+        assert!(matches!(error, ConfigError::NotWritable { .. }));
+        assert!(error.to_string().contains("BEEPER_CONFIG_DIR"));
+        assert!(matches!(
+            Config::default().save_to(&dir.join("config.toml")),
+            Err(ConfigError::NotWritable { .. })
+        ));
+
+        // Loading falls back to the default rather than failing
+        let config = Config::load_from(&dir.join("config.toml")).unwrap();
+        assert!(!config.is_api_configured());
+
+        std::fs::remove_file(&file).ok();
+    }
+
+    #[test]
+    fn test_token_is_redacted_in_output() {
+        let mut config = Config::default();
+        config.api.token = "secret-token".to_string().into();
+
+        assert!(!format!("{:?}", config).contains("secret-token"));
+        assert_eq!(config.api.token.to_string(), "****");
+        assert!(toml::to_string(&config).unwrap().contains("token = \"secret-token\""));
+    }
+
+    #[test]
+    fn test_expired_snoozes_are_cleared_on_load() {
+        use crate::notifications::NotificationAutomation;
+        use std::time::{Duration, SystemTime};
+
+        let dir = std::env::temp_dir().join(format!("beeper-config-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("config.toml");
+
+        let mut config = Config::default();
+        let mut expired = NotificationAutomation::new("a".to_string(), "Old".to_string(), vec![]);
+        expired.snoozed_until = Some(SystemTime::now() - Duration::from_secs(60));
+        let mut running = NotificationAutomation::new("b".to_string(), "New".to_string(), vec![]);
+        running.snoozed_until = Some(SystemTime::now() + Duration::from_secs(600));
+        config.notifications.automations = vec![expired, running];
+        config.save_to(&path).unwrap();
+
+        let loaded = Config::load_from(&path).unwrap();
+        assert_eq!(loaded.notifications.automations[0].snoozed_until, None);
+        assert!(loaded.notifications.automations[1].is_snoozed());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn test_is_api_configured() {
         let mut config = Config::default();
         assert!(!config.is_api_configured());
 
-        config.api.token = "test-token".to_string();
+        config.api.token = "test-token".to_string().into();
         assert!(config.is_api_configured());
     }
+
+    #[test]
+    fn test_lint_flags_overlapping_and_incomplete_automations() {
+        use crate::notifications::{AutomationType, NotificationAutomation};
+
+        let mut config = Config::default();
+        let immediate =
+            NotificationAutomation::new("a".to_string(), "Work".to_string(), vec!["c1".to_string()]);
+        let mut looping =
+            NotificationAutomation::new("b".to_string(), "Nag".to_string(), vec!["c1".to_string()]);
+        looping.automation_type = AutomationType::Loop;
+        let empty = NotificationAutomation::new("c".to_string(), String::new(), vec![]);
+        config.notifications.automations = vec![immediate, looping, empty];
+
+        let warnings = config.lint();
+        let for_id = |id: &str| warnings.iter().filter(|w| w.automation_id == id).count();
+
+        assert_eq!(for_id("a"), 1); // overlaps with the loop automation
+        assert_eq!(for_id("b"), 2); // overlap + missing loop settings
+        assert_eq!(for_id("c"), 2); // no name + no chats
+    }
+
+    #[test]
+    fn test_import_merge_resolves_conflicts_by_name() {
+        use crate::notifications::NotificationAutomation;
+
+        let mut config = Config::default();
+        config.notifications.automations.push(NotificationAutomation::new(
+            "a1".to_string(),
+            "Work".to_string(),
+            vec!["old".to_string()],
+        ));
+
+        let mut imported = Config::default();
+        imported.notifications.automations.push(NotificationAutomation::new(
+            "b1".to_string(),
+            "Work".to_string(),
+            vec!["new".to_string()],
+        ));
+        imported.notifications.automations.push(NotificationAutomation::new(
+            "a1".to_string(),
+            "Family".to_string(),
+            vec![],
+        ));
+
+        assert_eq!(config.conflicting_automations(&imported), vec!["Work".to_string()]);
+
+        let mut skipped = config.clone();
+        skipped.import_automations(imported.clone(), ImportMode::Merge, false);
+        assert_eq!(skipped.notifications.automations.len(), 2);
+        assert_eq!(skipped.notifications.automations[0].chat_ids, vec!["old".to_string()]);
+
+        config.import_automations(imported, ImportMode::Merge, true);
+        assert_eq!(config.notifications.automations.len(), 2);
+        assert_eq!(config.notifications.automations[0].id, "a1");
+        // The imported "Family" reused an existing id and got a fresh one
+        assert_ne!(config.notifications.automations[1].id, "a1");
+        assert_eq!(config.notifications.automations[0].chat_ids, vec!["new".to_string()]);
+    }
+
+    #[test]
+    fn test_merge_updates_by_id_and_appends_new() {
+        use crate::notifications::NotificationAutomation;
+
+        let mut config = Config::default();
+        for (id, name) in [("a1", "Work"), ("b1", "Family")] {
+            let mut automation =
+                NotificationAutomation::new(id.to_string(), name.to_string(), vec![]);
+            automation.notification_sound = Some("ding.wav".to_string());
+            config.notifications.automations.push(automation);
+        }
+
+        let patch = PartialConfig::from_toml(
+            r#"
+            [notifications]
+            paused = true
+
+            [[notifications.automations]]
+            id = "a1"
+            enabled = false
+
+            [[notifications.automations]]
+            id = "c1"
+            name = "On call"
+            chat_ids = ["oncall"]
+            "#,
+        )
+        .unwrap();
+        config.merge(patch).unwrap();
+
+        assert!(config.notifications.paused);
+        let automations = &config.notifications.automations;
+        assert_eq!(automations.len(), 3);
+        // Only the patched field changed
+        assert!(!automations[0].enabled);
+        assert_eq!(automations[0].name, "Work");
+        assert_eq!(
+            automations[0].notification_sound.as_deref(),
+            Some("ding.wav")
+        );
+        // Unmentioned automations are untouched
+        assert!(automations[1].enabled);
+        assert_eq!(automations[2].id, "c1");
+        assert_eq!(automations[2].chat_ids, vec!["oncall".to_string()]);
+        assert_eq!(config.api.url, Config::default().api.url);
+
+        // A bad patch leaves the config as it was
+        let before = toml::to_string(&config).unwrap();
+        let bad = PartialConfig::from_toml(
+            r#"
+            [notifications]
+            jitter = true
+
+            [[notifications.automations]]
+            name = "No id"
+            "#,
+        )
+        .unwrap();
+        assert!(config.merge(bad).is_err());
+        let wrong_type = PartialConfig::from_toml(
+            r#"
+            [[notifications.automations]]
+            id = "a1"
+            enabled = "no"
+            "#,
+        )
+        .unwrap();
+        assert!(config.merge(wrong_type).is_err());
+        assert_eq!(toml::to_string(&config).unwrap(), before);
+    }
+
+    #[test]
+    fn test_merge_rejects_unknown_keys() {
+        use crate::notifications::NotificationAutomation;
+
+        let mut config = Config::default();
+        let automation = NotificationAutomation::new("a1".to_string(), "Work".to_string(), vec![]);
+        config.notifications.automations.push(automation);
+        let before = toml::to_string(&config).unwrap();
+
+        // Misspelled keys in an automation, nested or not
+        for automation in [
+            "enabeld = false",
+            "loop_config = { until = \"message_seen\", check_interval = 1000, tyme = 5 }",
+        ] {
+            let patch = format!("[[notifications.automations]]\nid = \"a1\"\n{}", automation);
+            let error = config
+                .merge(PartialConfig::from_toml(&patch).unwrap())
+                .unwrap_err();
+            assert!(error.to_string().contains("unknown field"), "{}", error);
+        }
+        // and in the sections around them
+        assert!(PartialConfig::from_toml("[notifications]\npuased = true").is_err());
+        assert!(PartialConfig::from_toml("[[notification_profile]]\nname = \"x\"").is_err());
+        assert_eq!(toml::to_string(&config).unwrap(), before);
+
+        // Profiles and scheduled focus entries can be patched too
+        let patch = PartialConfig::from_toml(
+            r#"
+            [[notification_profiles]]
+            name = "Loud"
+            desktop_notification = true
+
+            [[notifications.scheduled_focus]]
+            id = "standup"
+            name = "Standup"
+            chat_id = "!standup"
+            cron = "55 9 * * mon-fri"
+            "#,
+        )
+        .unwrap();
+        config.merge(patch).unwrap();
+        assert!(config.notification_profiles[0].desktop_notification);
+        assert_eq!(config.notifications.scheduled_focus[0].id, "standup");
+        assert!(config.notifications.scheduled_focus[0].enabled);
+    }
 }
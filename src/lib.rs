@@ -1,24 +1,111 @@
 pub mod api_check;
 pub mod app_state;
+pub mod beeper_api;
+pub mod cli;
 pub mod config;
 pub mod logging;
 pub mod notifications;
+pub mod service_install;
+#[cfg(target_os = "linux")]
+pub mod systemd;
 pub mod tui;
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use notify::{Event, RecursiveMode, Watcher};
 use tokio::signal;
 
-pub async fn run_service() -> Result<()> {
+/// Command-line options for the service binary
+#[derive(Debug, Clone, Default)]
+pub struct ServiceOptions {
+    /// Log actions instead of performing them, regardless of the config file
+    pub dry_run: bool,
+    /// Read and watch the config in this directory instead of the default one
+    pub config_dir: Option<std::path::PathBuf>,
+    /// Print the automations as JSON and exit without starting them
+    pub dump_automations: bool,
+    /// Run only the automation with this id or name, leaving the rest inert
+    pub only: Option<String>,
+}
+
+impl ServiceOptions {
+    /// Parse options from the process arguments
+    pub fn from_args() -> Self {
+        let mut options = Self::default();
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--dry-run" => options.dry_run = true,
+                "--dump-automations" => options.dump_automations = true,
+                "--only" => match args.next() {
+                    Some(automation) => options.only = Some(automation),
+                    None => eprintln!("⚠ --only requires an automation id or name"),
+                },
+                "--config-dir" => match args.next() {
+                    Some(dir) => options.config_dir = Some(dir.into()),
+                    None => eprintln!("⚠ --config-dir requires a directory"),
+                },
+                other => eprintln!("⚠ Ignoring unknown argument: {}", other),
+            }
+        }
+        options
+    }
+
+    /// Apply command-line overrides to a freshly loaded config. Fails when
+    /// `--only` names an automation the config doesn't have.
+    fn apply(&self, config: &mut config::Config) -> Result<()> {
+        if self.dry_run {
+            config.notifications.dry_run = true;
+        }
+
+        if let Some(only) = &self.only {
+            let automations = &mut config.notifications.automations;
+            automations.retain(|a| a.id == *only || a.name == *only);
+            if automations.is_empty() {
+                bail!("--only: no automation with id or name '{}'", only);
+            }
+            for automation in automations.iter().filter(|a| !a.enabled) {
+                println!(
+                    "⚠ --only: automation '{}' is disabled and won't run",
+                    automation.name
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+pub async fn run_service(options: ServiceOptions) -> Result<()> {
+    // Introspection only: keep stdout to the JSON and start nothing
+    if options.dump_automations {
+        if let Some(dir) = &options.config_dir {
+            config::Config::set_config_dir(dir.clone());
+        }
+        let config = config::Config::load()?;
+        return notifications::dump::dump_automations(&config).await;
+    }
+
     // Initialize logging for console mode
     crate::logging::init_logging(false);
 
     println!("Starting Beeper Automations Service...");
 
+    if let Some(dir) = &options.config_dir {
+        config::Config::set_config_dir(dir.clone());
+    }
+
     // Load configuration
-    let config = config::Config::load()?;
+    let mut config = config::Config::load()?;
+    options.apply(&mut config)?;
     let config_path = config::Config::config_file_path()?;
 
+    // The service only reads the config, so it runs on without write access;
+    // but its runtime state can't be kept either, so say so once up front
+    if let Err(e) = config::Config::check_writable() {
+        eprintln!("✗ {}", e);
+        eprintln!("  Running anyway; progress in state.json won't be kept across restarts.");
+        tracing::error!("Config directory is not writable: {}", e);
+    }
+
     // Check if API is configured, if not wait for hot reload
     if !config.is_api_configured() {
         println!("⚠ API configuration not found. Waiting for configuration...");
@@ -34,8 +121,11 @@ pub async fn run_service() -> Result<()> {
     let (reload_tx, reload_rx) = tokio::sync::mpsc::channel::<config::Config>(10);
 
     // Always start the service with the reload receiver
-    let _notification_service =
+    let notification_service =
         notifications::service::NotificationService::new(app_state.clone(), reload_rx);
+    notification_service.serve_metrics(&config.metrics);
+    let reload_now = std::sync::Arc::new(tokio::sync::Notify::new());
+    notification_service.serve_control(&config.control, reload_now.clone());
 
     // If API is configured, trigger initial load
     if config.is_api_configured() {
@@ -53,8 +143,11 @@ pub async fn run_service() -> Result<()> {
     // Set up config file watcher
     let (tx, mut rx) = tokio::sync::mpsc::channel::<Result<Event, notify::Error>>(100);
 
+    let watched_path = config_path.clone();
     let mut watcher = notify::recommended_watcher(move |res| {
-        let _ = tx.blocking_send(res);
+        if concerns_config_file(&res, &watched_path) {
+            let _ = tx.blocking_send(res);
+        }
     })?;
 
     if let Some(parent) = config_path.parent() {
@@ -65,42 +158,41 @@ pub async fn run_service() -> Result<()> {
     let config_path_clone = config_path.clone();
 
     tokio::spawn(async move {
-        while let Some(event) = rx.recv().await {
-            if let Ok(event) = event {
-                // Check if config file was modified
-                let config_modified = event.paths.iter().any(|p| p == &config_path_clone);
-
-                if config_modified && (event.kind.is_modify() || event.kind.is_create()) {
-                    println!("\n📝 Configuration file changed, reloading...");
-
-                    // Small delay to ensure file is fully written
-                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-                    match config::Config::load() {
-                        Ok(new_config) => {
-                            if new_config.is_api_configured() {
-                                print_config_status(&new_config);
-
-                                // Send reload signal to notification service
-                                if let Err(e) = reload_tx.send(new_config).await {
-                                    eprintln!("✗ Error sending reload signal: {}", e);
-                                }
-                            } else {
-                                println!("⚠ Configuration loaded but API is not configured yet.");
-                                println!("  Waiting for complete configuration...\n");
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("✗ Error reloading configuration: {}", e);
+        while wait_for_reload(&mut rx, &config_path_clone, &reload_now).await {
+
+            match config::Config::load() {
+                Ok(mut new_config) => {
+                    if let Err(e) = options.apply(&mut new_config) {
+                        eprintln!("✗ Not reloading: {}", e);
+                        continue;
+                    }
+                    if new_config.is_api_configured() {
+                        print_config_status(&new_config);
+
+                        // Send reload signal to notification service
+                        if let Err(e) = reload_tx.send(new_config).await {
+                            eprintln!("✗ Error sending reload signal: {}", e);
                         }
+                    } else {
+                        println!("⚠ Configuration loaded but API is not configured yet.");
+                        println!("  Waiting for complete configuration...\n");
                     }
                 }
+                Err(e) => {
+                    eprintln!("✗ Error reloading configuration: {}", e);
+                }
             }
         }
     });
 
+    #[cfg(target_os = "linux")]
+    {
+        systemd::notify_ready();
+        systemd::spawn_watchdog();
+    }
+
     // Wait for shutdown signal
-    match signal::ctrl_c().await {
+    match wait_for_shutdown().await {
         Ok(()) => {
             println!("\n\n🛑 Received shutdown signal. Stopping service...");
         }
@@ -109,18 +201,134 @@ pub async fn run_service() -> Result<()> {
         }
     }
 
+    #[cfg(target_os = "linux")]
+    systemd::notify_stopping();
+
+    notification_service.save_state().await;
+    notification_service.print_summary().await;
+
     println!("✓ Service stopped.");
 
     Ok(())
 }
 
+/// Quiet period after the last config file event before reloading
+const RELOAD_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(300);
+
+/// Files the service writes next to config.toml. The watcher sees the whole
+/// directory, so without this every state save would wake the reload task.
+/// New runtime files belong in `logging::data_dir()` rather than here.
+const IGNORED_CONFIG_DIR_FILES: &[&str] = &[
+    "state.json",
+    "state.json.tmp",
+    "quiet.json",
+    config::WRITE_PROBE_FILE,
+];
+
+/// Whether `path` is the config file, rather than another file in its directory
+fn is_config_file(path: &std::path::Path, config_path: &std::path::Path) -> bool {
+    let Some(name) = path.file_name() else {
+        return false;
+    };
+    let ignored = IGNORED_CONFIG_DIR_FILES
+        .iter()
+        .any(|ignored| name == *ignored);
+    !ignored && Some(name) == config_path.file_name()
+}
+
+/// Whether a watcher event is worth passing to the reload task. Runs on the
+/// watcher's thread, so events for other files never wake the task at all.
+fn concerns_config_file(
+    event: &Result<Event, notify::Error>,
+    config_path: &std::path::Path,
+) -> bool {
+    match event {
+        Ok(event) => event.paths.iter().any(|p| is_config_file(p, config_path)),
+        Err(e) => {
+            tracing::warn!("Config watcher error: {}", e);
+            false
+        }
+    }
+}
+
+/// Wait until the config file changes and then settles. Editors often emit
+/// several events per save (truncate, write, rename), which collapse into a
+/// single reload. Returns false once the watcher channel closes.
+async fn wait_for_config_change(
+    rx: &mut tokio::sync::mpsc::Receiver<Result<Event, notify::Error>>,
+    config_path: &std::path::Path,
+) -> bool {
+    let is_config_change = |event: &Result<Event, notify::Error>| {
+        matches!(event, Ok(event)
+            if (event.kind.is_modify() || event.kind.is_create())
+                && event.paths.iter().any(|p| is_config_file(p, config_path)))
+    };
+
+    // Wait for the first change to the config file
+    loop {
+        match rx.recv().await {
+            Some(event) if is_config_change(&event) => break,
+            Some(_) => continue,
+            None => return false,
+        }
+    }
+
+    // Then keep pushing the deadline back while more changes arrive
+    let mut deadline = tokio::time::Instant::now() + RELOAD_DEBOUNCE;
+    loop {
+        match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(Some(event)) => {
+                if is_config_change(&event) {
+                    deadline = tokio::time::Instant::now() + RELOAD_DEBOUNCE;
+                }
+            }
+            Ok(None) | Err(_) => return true,
+        }
+    }
+}
+
+/// Wait for the config file to change or for a reload requested over the
+/// control port. Returns false once the watcher channel closes.
+async fn wait_for_reload(
+    rx: &mut tokio::sync::mpsc::Receiver<Result<Event, notify::Error>>,
+    config_path: &std::path::Path,
+    reload_now: &tokio::sync::Notify,
+) -> bool {
+    tokio::select! {
+        changed = wait_for_config_change(rx, config_path) => {
+            if changed {
+                println!("\n📝 Configuration file changed, reloading...");
+            }
+            changed
+        }
+        _ = reload_now.notified() => {
+            println!("\n🎛 Reload requested, reloading...");
+            true
+        }
+    }
+}
+
+/// Wait for Ctrl+C, or SIGTERM on Unix (sent by systemd / launchd on stop)
+async fn wait_for_shutdown() -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        let mut sigterm = signal::unix::signal(signal::unix::SignalKind::terminate())?;
+        tokio::select! {
+            result = signal::ctrl_c() => result,
+            _ = sigterm.recv() => Ok(()),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        signal::ctrl_c().await
+    }
+}
+
 fn print_config_status(config: &config::Config) {
     println!("✓ Configuration loaded successfully!");
     println!("  API URL: {}", config.api.url);
-    println!(
-        "  Token: {}***",
-        &config.api.token[..config.api.token.len().min(4)]
-    );
+    println!("  Token: {}", config.api.token);
 
     // Display enabled automations
     let enabled_count = config
@@ -130,6 +338,19 @@ fn print_config_status(config: &config::Config) {
         .filter(|a| a.enabled)
         .count();
     println!("  Enabled automations: {}", enabled_count);
+
+    if config.notifications.paused {
+        println!("  ⏸ Automations are paused");
+    }
+
+    if config.notifications.dry_run {
+        println!("  Dry run: actions will be logged, not performed");
+    }
+
+    for warning in config.lint() {
+        println!("  ⚠ {}", warning);
+        tracing::warn!("Config warning: {}", warning);
+    }
 }
 
 /// Run the service with an external shutdown signal (for Windows service)
@@ -188,8 +409,11 @@ pub async fn run_service_with_shutdown(
 
     // Always start the service with the reload receiver
     tracing::info!("Creating notification service...");
-    let _notification_service =
+    let notification_service =
         notifications::service::NotificationService::new(app_state.clone(), reload_rx);
+    notification_service.serve_metrics(&config.metrics);
+    let reload_now = std::sync::Arc::new(tokio::sync::Notify::new());
+    notification_service.serve_control(&config.control, reload_now.clone());
 
     // If API is configured, trigger initial load
     if config.is_api_configured() {
@@ -208,8 +432,11 @@ pub async fn run_service_with_shutdown(
     tracing::info!("Setting up config file watcher...");
     let (tx, mut rx) = tokio::sync::mpsc::channel::<Result<Event, notify::Error>>(100);
 
+    let watched_path = config_path.clone();
     let mut watcher = match notify::recommended_watcher(move |res| {
-        let _ = tx.blocking_send(res);
+        if concerns_config_file(&res, &watched_path) {
+            let _ = tx.blocking_send(res);
+        }
     }) {
         Ok(w) => {
             tracing::info!("Config watcher created successfully");
@@ -233,36 +460,25 @@ pub async fn run_service_with_shutdown(
     let config_path_clone = config_path.clone();
 
     tokio::spawn(async move {
-        while let Some(event) = rx.recv().await {
-            if let Ok(event) = event {
-                // Check if config file was modified
-                let config_modified = event.paths.iter().any(|p| p == &config_path_clone);
-
-                if config_modified && (event.kind.is_modify() || event.kind.is_create()) {
-                    println!("\n📝 Configuration file changed, reloading...");
-
-                    // Small delay to ensure file is fully written
-                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-                    match config::Config::load() {
-                        Ok(new_config) => {
-                            if new_config.is_api_configured() {
-                                print_config_status(&new_config);
-
-                                // Send reload signal to notification service
-                                if let Err(e) = reload_tx.send(new_config).await {
-                                    eprintln!("✗ Error reloading signal: {}", e);
-                                }
-                            } else {
-                                println!("⚠ Configuration loaded but API is not configured yet.");
-                                println!("  Waiting for complete configuration...\n");
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("✗ Error reloading configuration: {}", e);
+        while wait_for_reload(&mut rx, &config_path_clone, &reload_now).await {
+
+            match config::Config::load() {
+                Ok(new_config) => {
+                    if new_config.is_api_configured() {
+                        print_config_status(&new_config);
+
+                        // Send reload signal to notification service
+                        if let Err(e) = reload_tx.send(new_config).await {
+                            eprintln!("✗ Error reloading signal: {}", e);
                         }
+                    } else {
+                        println!("⚠ Configuration loaded but API is not configured yet.");
+                        println!("  Waiting for complete configuration...\n");
                     }
                 }
+                Err(e) => {
+                    eprintln!("✗ Error reloading configuration: {}", e);
+                }
             }
         }
     });
@@ -275,9 +491,45 @@ pub async fn run_service_with_shutdown(
 
     tracing::info!("Service stopping...");
 
+    notification_service.save_state().await;
+    notification_service.print_summary().await;
+
     println!("✓ Service stopped.");
 
     println!("✓ Service stopped.");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::EventKind;
+    use notify::event::{CreateKind, ModifyKind};
+    use std::path::Path;
+
+    #[test]
+    fn test_watcher_only_passes_config_file_events() {
+        let dir = Path::new("/config");
+        let config_path = dir.join("config.toml");
+        let passes = |kind: EventKind, name: &str| {
+            concerns_config_file(&Ok(Event::new(kind).add_path(dir.join(name))), &config_path)
+        };
+        let modify = EventKind::Modify(ModifyKind::Any);
+        let create = EventKind::Create(CreateKind::File);
+
+        assert!(passes(modify, "config.toml"));
+        for name in IGNORED_CONFIG_DIR_FILES {
+            assert!(!passes(create, name));
+        }
+        assert!(!passes(modify, "cache.json"));
+        // A config kept as JSON is watched in the same way
+        let json_path = dir.join("config.json");
+        let json_event = Event::new(modify).add_path(json_path.clone());
+        assert!(concerns_config_file(&Ok(json_event), &json_path));
+        assert!(!passes(modify, "config.json"));
+
+        let error = Err(notify::Error::generic("watch failed"));
+        assert!(!concerns_config_file(&error, &config_path));
+    }
+}
@@ -1,6 +1,6 @@
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use tracing::Subscriber;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
@@ -11,36 +11,31 @@ pub static LOG_FILE_PATH: Mutex<Option<String>> = Mutex::new(None);
 
 /// Get log directory path
 fn log_dir() -> PathBuf {
-    #[cfg(windows)]
-    {
-        // On Windows, use AppData\Local
-        dirs::data_local_dir()
-            .unwrap_or_else(|| {
-                let mut path = std::env::var("LOCALAPPDATA").unwrap_or_else(|_| {
-                    std::env::var("USERPROFILE").unwrap_or_else(|_| ".".to_string())
-                });
-                path.push_str("\\AppData\\Local");
-                PathBuf::from(path)
-            })
-            .join("BeeperAutomations")
-    }
-
-    #[cfg(not(windows))]
-    {
-        // On Unix systems, use XDG state directory or fallback to ~/.local/state
-        dirs::state_dir().unwrap_or_else(|| {
-            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-            PathBuf::from(home).join(".local/state/beeper-automations")
-        })
-    }
+    data_dir()
 }
 
 /// Get log file path
-fn log_file_path() -> PathBuf {
+pub fn log_file_path() -> PathBuf {
     log_dir().join("service.log")
 }
 
-/// Get data directory path (for working directory and state files)
+/// Last `count` lines of the service log, oldest first. Empty if the
+/// service hasn't written a log yet.
+pub fn read_log_tail(count: usize) -> Vec<String> {
+    let content = std::fs::read_to_string(log_file_path()).unwrap_or_default();
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Copy the service log to `dest`, e.g. to attach it to a bug report
+pub fn export_log(dest: &Path) -> std::io::Result<u64> {
+    std::fs::copy(log_file_path(), dest)
+}
+
+/// Get data directory path (for working directory, logs, sounds and state files)
 pub fn data_dir() -> PathBuf {
     #[cfg(windows)]
     {
@@ -58,11 +53,15 @@ pub fn data_dir() -> PathBuf {
 
     #[cfg(not(windows))]
     {
-        // On Unix systems, use XDG state directory or fallback to ~/.local/state
-        dirs::state_dir().unwrap_or_else(|| {
-            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-            PathBuf::from(home).join(".local/state/beeper-automations")
-        })
+        // On Linux use the XDG state directory; macOS has none, so fall back to
+        // Application Support, then ~/.local/state
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .unwrap_or_else(|| {
+                let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+                PathBuf::from(home).join(".local/state")
+            })
+            .join("beeper-automations")
     }
 }
 
@@ -0,0 +1,142 @@
+// Actions automations can take on a chat beyond focusing it
+
+use crate::app_state::SharedAppState;
+use crate::notifications::focus_gate::{self, FOCUS_GATE};
+
+/// Send a text message to a chat
+pub async fn send_message(
+    app_state: &SharedAppState,
+    chat_id: &str,
+    text: &str,
+) -> Result<(), String> {
+    use beeper_desktop_api::SendMessageInput;
+
+    let input = SendMessageInput {
+        chat_id: chat_id.to_string(),
+        text: Some(text.to_string()),
+        reply_to_message_id: None,
+    };
+    let result = app_state
+        .with_client_async(|client| async move { client.send_message(input).await })
+        .await;
+
+    match result {
+        Ok(Ok(_)) => {
+            tracing::info!("Sent message to chat {}", chat_id);
+            Ok(())
+        }
+        Ok(Err(e)) => {
+            tracing::error!("Error sending message to chat {}: {}", chat_id, e);
+            Err(e)
+        }
+        Err(e) => {
+            tracing::error!("Error accessing client to send message: {}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Mark a chat as read up to the given message
+pub async fn mark_read(
+    app_state: &SharedAppState,
+    chat_id: &str,
+    message_id: &str,
+) -> Result<(), String> {
+    use beeper_desktop_api::MarkReadInput;
+
+    let input = MarkReadInput {
+        chat_id: chat_id.to_string(),
+        message_id: Some(message_id.to_string()),
+    };
+    let result = app_state
+        .with_client_async(|client| async move { client.mark_read(input).await })
+        .await;
+
+    match result {
+        Ok(Ok(_)) => {
+            tracing::info!("Marked chat {} as read", chat_id);
+            Ok(())
+        }
+        Ok(Err(e)) => {
+            tracing::error!("Error marking chat {} as read: {}", chat_id, e);
+            Err(e)
+        }
+        Err(e) => {
+            tracing::error!("Error accessing client to mark chat read: {}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Bring Beeper to the front on a chat, optionally scrolled to a message.
+/// Returns whether Beeper reported success. Waits out the focus cooldown
+/// first, and returns `Ok(false)` without focusing when a newer focus
+/// replaced this one meanwhile.
+pub async fn focus_chat(
+    app_state: &SharedAppState,
+    chat_id: &str,
+    message_id: Option<&str>,
+) -> Result<bool, String> {
+    use beeper_desktop_api::FocusAppInput;
+
+    if !FOCUS_GATE.wait_turn(focus_gate::cooldown(app_state)).await {
+        tracing::info!(
+            "Dropping focus on chat {}: a newer focus came in during the cooldown",
+            chat_id
+        );
+        return Ok(false);
+    }
+
+    let input = FocusAppInput {
+        chat_id: Some(chat_id.to_string()),
+        message_id: message_id.map(str::to_string),
+        draft: None,
+    };
+    let result = app_state
+        .with_client_async(|client| async move { client.focus_app(Some(input)).await })
+        .await;
+
+    match result {
+        Ok(Ok(response)) => {
+            tracing::info!("Focused chat {} (success: {})", chat_id, response.success);
+            Ok(response.success)
+        }
+        Ok(Err(e)) => {
+            tracing::error!("Error focusing chat {}: {}", chat_id, e);
+            Err(e)
+        }
+        Err(e) => {
+            tracing::error!("Error accessing client for focus: {}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beeper_api::mock::MockApi;
+    use crate::config::Config;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_send_message_forwards_to_the_client() {
+        let api = Arc::new(MockApi::default());
+        let app_state = SharedAppState::with_api(Config::default(), api.clone());
+
+        send_message(&app_state, "chat1", "on my way")
+            .await
+            .unwrap();
+        assert_eq!(
+            *api.sent.lock().unwrap(),
+            [("chat1".to_string(), "on my way".to_string())]
+        );
+
+        *api.send_error.lock().unwrap() = Some("chat not found".to_string());
+        assert_eq!(
+            send_message(&app_state, "chat2", "hello").await,
+            Err("chat not found".to_string())
+        );
+        assert_eq!(api.sent.lock().unwrap().len(), 1);
+    }
+}
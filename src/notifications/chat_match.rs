@@ -0,0 +1,50 @@
+// Glob patterns for picking an automation's chats by name
+
+/// Whether `text` matches `pattern`, ignoring case. `*` matches any run of
+/// characters and `?` matches exactly one; everything else is literal.
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it is currently matched up to
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` swallow one more character and retry
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("oncall-*", "oncall-web"));
+        assert!(glob_matches("oncall-*", "OnCall-"));
+        assert!(!glob_matches("oncall-*", "team oncall-web"));
+        assert!(glob_matches("*oncall*", "team oncall-web"));
+        assert!(glob_matches("team-?", "team-a"));
+        assert!(!glob_matches("team-?", "team-ab"));
+        assert!(glob_matches("a*b*c", "axxbyyc"));
+        assert!(!glob_matches("a*b*c", "axxbyy"));
+        assert!(glob_matches("Family", "family"));
+        assert!(!glob_matches("Family", "Family Chat"));
+    }
+}
@@ -0,0 +1,401 @@
+// Control port: lets the configurator command a running service directly,
+// instead of writing config.toml and waiting for the file watcher.
+//
+// The service listens on a localhost TCP port, which works the same on every
+// platform. A client connects, sends one command line and reads one reply
+// line: `ok`, `error: <reason>`, or for `status` a JSON object.
+//
+// Commands change the running service only. The configurator still saves the
+// same change to config.toml so it survives a restart.
+
+use crate::app_state::SharedAppState;
+use crate::config::ControlConfig;
+use crate::notifications::health::API_HEALTH;
+use crate::notifications::service::SharedStats;
+use crate::notifications::state::RuntimeState;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+
+/// Snooze length when `snooze` is sent without one
+const DEFAULT_SNOOZE_MINUTES: u64 = 30;
+
+/// Longest command line read; anything past it is ignored
+const MAX_COMMAND_BYTES: u64 = 1024;
+
+/// How long the configurator waits for the service before giving up
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(300);
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long the service waits for a client's command before hanging up
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlCommand {
+    Status,
+    /// Reload config.toml now, skipping the file watcher
+    Reload,
+    Pause,
+    Resume,
+    /// Snooze an automation for this many minutes; 0 ends a running snooze
+    Snooze {
+        automation_id: String,
+        minutes: u64,
+    },
+}
+
+impl ControlCommand {
+    /// Parse one command line, e.g. `snooze <automation id> 30`
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let command = match words.next().map(str::to_lowercase).as_deref() {
+            Some("status") => Self::Status,
+            Some("reload") => Self::Reload,
+            Some("pause") => Self::Pause,
+            Some("resume") => Self::Resume,
+            Some("snooze") => {
+                let automation_id = words
+                    .next()
+                    .ok_or("snooze needs an automation id")?
+                    .to_string();
+                let minutes = match words.next() {
+                    Some(minutes) => minutes
+                        .parse()
+                        .map_err(|_| format!("'{}' is not a number of minutes", minutes))?,
+                    None => DEFAULT_SNOOZE_MINUTES,
+                };
+                Self::Snooze {
+                    automation_id,
+                    minutes,
+                }
+            }
+            Some(other) => return Err(format!("unknown command '{}'", other)),
+            None => return Err("empty command".to_string()),
+        };
+
+        if let Some(extra) = words.next() {
+            return Err(format!("unexpected '{}'", extra));
+        }
+        Ok(command)
+    }
+}
+
+impl std::fmt::Display for ControlCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Status => write!(f, "status"),
+            Self::Reload => write!(f, "reload"),
+            Self::Pause => write!(f, "pause"),
+            Self::Resume => write!(f, "resume"),
+            Self::Snooze {
+                automation_id,
+                minutes,
+            } => write!(f, "snooze {} {}", automation_id, minutes),
+        }
+    }
+}
+
+/// Reply to `status`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServiceStatus {
+    pub paused: bool,
+    /// When Beeper started rejecting the API token, in Unix milliseconds,
+    /// while polling is paused for it
+    #[serde(default)]
+    pub token_rejected_ms: Option<u64>,
+    pub automations: Vec<AutomationStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationStatus {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    /// When the snooze ends, while snoozed
+    pub snoozed_until: Option<SystemTime>,
+    /// Times the automation alerted since the service started
+    pub triggered: u64,
+    pub errors: u64,
+    /// Alerting faster than `flapping_alerts_per_minute`
+    #[serde(default)]
+    pub flapping: bool,
+}
+
+/// What the control server acts on
+#[derive(Clone)]
+pub(crate) struct ControlContext {
+    pub(crate) app_state: SharedAppState,
+    pub(crate) stats: SharedStats,
+    /// Holds the flapping snoozes a configurator snooze replaces
+    pub(crate) runtime_state: Arc<tokio::sync::RwLock<RuntimeState>>,
+    /// Wakes the config reload task
+    pub(crate) reload_now: Arc<tokio::sync::Notify>,
+}
+
+impl ControlContext {
+    /// Carry out a command and return the reply line
+    async fn execute(&self, command: ControlCommand) -> String {
+        match command {
+            ControlCommand::Status => {
+                let stats = self.stats.read().await;
+                let now = Instant::now();
+                let status = self.app_state.with_config(|config| ServiceStatus {
+                    paused: config.notifications.paused,
+                    token_rejected_ms: API_HEALTH.token_rejected_ms(),
+                    automations: config
+                        .notifications
+                        .automations
+                        .iter()
+                        .map(|automation| {
+                            let stats = stats.get(&automation.id);
+                            AutomationStatus {
+                                id: automation.id.clone(),
+                                name: automation.name.clone(),
+                                enabled: automation.enabled,
+                                snoozed_until: automation
+                                    .snoozed_until
+                                    .filter(|_| automation.is_snoozed()),
+                                triggered: stats.map(|s| s.triggered).unwrap_or_default(),
+                                errors: stats.map(|s| s.errors).unwrap_or_default(),
+                                flapping: stats.is_some_and(|s| {
+                                    s.is_flapping(
+                                        config.notifications.flapping_alerts_per_minute,
+                                        now,
+                                    )
+                                }),
+                            }
+                        })
+                        .collect(),
+                });
+                match status.map(|status| serde_json::to_string(&status)) {
+                    Ok(Ok(json)) => json,
+                    Ok(Err(e)) => format!("error: {}", e),
+                    Err(e) => format!("error: {}", e),
+                }
+            }
+            ControlCommand::Reload => {
+                self.reload_now.notify_one();
+                "ok".to_string()
+            }
+            ControlCommand::Pause | ControlCommand::Resume => {
+                let paused = command == ControlCommand::Pause;
+                match self
+                    .app_state
+                    .with_config_mut(|config| config.notifications.paused = paused)
+                {
+                    Ok(()) => {
+                        if paused {
+                            println!("⏸ Automations paused from the configurator");
+                        } else {
+                            println!("▶ Automations resumed from the configurator");
+                        }
+                        "ok".to_string()
+                    }
+                    Err(e) => format!("error: {}", e),
+                }
+            }
+            ControlCommand::Snooze {
+                automation_id,
+                minutes,
+            } => {
+                let until =
+                    (minutes > 0).then(|| SystemTime::now() + Duration::from_secs(minutes * 60));
+                let mut name = None;
+                let result = self.app_state.with_config_mut(|config| {
+                    if let Some(automation) = config
+                        .notifications
+                        .automations
+                        .iter_mut()
+                        .find(|a| a.id == automation_id)
+                    {
+                        automation.snoozed_until = until;
+                        name = Some(automation.name.clone());
+                    }
+                });
+                match (result, name) {
+                    (Err(e), _) => format!("error: {}", e),
+                    (Ok(()), None) => format!("error: no automation with id '{}'", automation_id),
+                    (Ok(()), Some(name)) => {
+                        self.runtime_state
+                            .write()
+                            .await
+                            .flapping_snoozes
+                            .remove(&automation_id);
+                        if until.is_some() {
+                            println!(
+                                "💤 '{}' snoozed for {} min from the configurator",
+                                name, minutes
+                            );
+                        } else {
+                            println!("⏰ '{}' resumed from the configurator", name);
+                        }
+                        "ok".to_string()
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Accept commands until the task is aborted. Each connection gets one reply.
+pub(crate) async fn serve(port: u16, context: ControlContext) {
+    let listener = match tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!(
+                "✗ Failed to start control port on 127.0.0.1:{}: {}",
+                port, e
+            );
+            tracing::error!("Failed to bind control port {}: {}", port, e);
+            return;
+        }
+    };
+    tracing::info!("Control port listening on 127.0.0.1:{}", port);
+
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::warn!("Control port failed to accept a connection: {}", e);
+                continue;
+            }
+        };
+
+        let context = context.clone();
+        tokio::spawn(async move {
+            let (reader, mut writer) = stream.into_split();
+            let mut line = String::new();
+            let mut reader = tokio::io::BufReader::new(reader.take(MAX_COMMAND_BYTES));
+            match tokio::time::timeout(COMMAND_TIMEOUT, reader.read_line(&mut line)).await {
+                Ok(Ok(_)) => {}
+                Ok(Err(_)) => return,
+                Err(_) => {
+                    tracing::debug!("Control port client sent no command in time, closing");
+                    return;
+                }
+            }
+
+            let reply = match ControlCommand::parse(&line) {
+                Ok(command) => {
+                    tracing::info!("Control command: {}", command);
+                    context.execute(command).await
+                }
+                Err(e) => format!("error: {}", e),
+            };
+            let _ = writer.write_all(format!("{}\n", reply).as_bytes()).await;
+            let _ = writer.shutdown().await;
+        });
+    }
+}
+
+/// Send a command to the running service and return its reply. Fails fast
+/// when no service is listening.
+pub fn send(config: &ControlConfig, command: &ControlCommand) -> Result<String, String> {
+    if !config.enabled {
+        return Err("the control port is disabled".to_string());
+    }
+
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, config.port));
+    let mut stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)
+        .map_err(|e| format!("service not reachable on port {}: {}", config.port, e))?;
+    stream.set_read_timeout(Some(REPLY_TIMEOUT)).ok();
+    stream.set_write_timeout(Some(REPLY_TIMEOUT)).ok();
+    writeln!(stream, "{}", command).map_err(|e| e.to_string())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream)
+        .read_line(&mut reply)
+        .map_err(|e| e.to_string())?;
+    let reply = reply.trim();
+    match reply.strip_prefix("error: ") {
+        Some(e) => Err(e.to_string()),
+        None if reply.is_empty() => Err("no reply from the service".to_string()),
+        None => Ok(reply.to_string()),
+    }
+}
+
+/// Ask the running service what it's doing
+pub fn status(config: &ControlConfig) -> Result<ServiceStatus, String> {
+    let reply = send(config, &ControlCommand::Status)?;
+    serde_json::from_str(&reply).map_err(|e| format!("unexpected status reply: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beeper_api::mock::MockApi;
+    use crate::config::Config;
+    use crate::notifications::NotificationAutomation;
+    use std::collections::HashMap;
+    use tokio::sync::RwLock;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            ControlCommand::parse("status\n"),
+            Ok(ControlCommand::Status)
+        );
+        assert_eq!(ControlCommand::parse(" PAUSE "), Ok(ControlCommand::Pause));
+        assert_eq!(
+            ControlCommand::parse("snooze abc"),
+            Ok(ControlCommand::Snooze {
+                automation_id: "abc".to_string(),
+                minutes: DEFAULT_SNOOZE_MINUTES,
+            })
+        );
+
+        // Commands survive the round trip through the wire format
+        let snooze = ControlCommand::Snooze {
+            automation_id: "abc".to_string(),
+            minutes: 0,
+        };
+        assert_eq!(ControlCommand::parse(&snooze.to_string()), Ok(snooze));
+
+        assert!(ControlCommand::parse("").is_err());
+        assert!(ControlCommand::parse("snooze").is_err());
+        assert!(ControlCommand::parse("snooze abc soon").is_err());
+        assert!(ControlCommand::parse("pause now").is_err());
+        assert!(ControlCommand::parse("restart").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_applies_to_running_config() {
+        let mut config = Config::default();
+        config
+            .notifications
+            .automations
+            .push(NotificationAutomation::new(
+                "work".to_string(),
+                "Work".to_string(),
+                vec![],
+            ));
+        let context = ControlContext {
+            app_state: SharedAppState::with_api(config, Arc::new(MockApi::default())),
+            stats: Arc::new(RwLock::new(HashMap::new())),
+            runtime_state: Default::default(),
+            reload_now: Arc::new(tokio::sync::Notify::new()),
+        };
+
+        assert_eq!(context.execute(ControlCommand::Pause).await, "ok");
+        let snooze = ControlCommand::Snooze {
+            automation_id: "work".to_string(),
+            minutes: 10,
+        };
+        assert_eq!(context.execute(snooze).await, "ok");
+
+        let status: ServiceStatus =
+            serde_json::from_str(&context.execute(ControlCommand::Status).await).unwrap();
+        assert!(status.paused);
+        assert_eq!(status.automations.len(), 1);
+        assert!(status.automations[0].snoozed_until.is_some());
+
+        let missing = ControlCommand::Snooze {
+            automation_id: "missing".to_string(),
+            minutes: 10,
+        };
+        assert!(context.execute(missing).await.starts_with("error: "));
+    }
+}
@@ -0,0 +1,231 @@
+// Cron expressions for automations whose active times don't fit one set of
+// days and hours
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use std::str::FromStr;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// One of the five fields, with the values it accepts
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    /// Names for the values from `min` up, e.g. `jan` for month 1
+    names: &'static [&'static str],
+}
+
+const MINUTE: Field = Field {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const HOUR: Field = Field {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+};
+const DAY: Field = Field {
+    name: "day of month",
+    min: 1,
+    max: 31,
+    names: &[],
+};
+const MONTH: Field = Field {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &MONTH_NAMES,
+};
+// Both 0 and 7 are Sunday
+const WEEKDAY: Field = Field {
+    name: "day of week",
+    min: 0,
+    max: 7,
+    names: &WEEKDAY_NAMES,
+};
+
+/// When an automation may alert, as a standard five-field cron expression:
+/// `minute hour day-of-month month day-of-week`, e.g. `* 9-17 * * mon-fri`.
+///
+/// The automation is active during every minute the expression matches. As
+/// in cron, when both day fields are restricted a day matching either counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    /// Bit 0 is Sunday
+    weekdays: u64,
+    /// Whether the day-of-month field is unrestricted (`*`)
+    any_day: bool,
+    /// Whether the day-of-week field is unrestricted (`*`)
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    /// Whether alerts are allowed at the given local time
+    pub fn is_active_at(&self, at: NaiveDateTime) -> bool {
+        let day = has(self.days, at.day());
+        let weekday = has(self.weekdays, at.weekday().num_days_from_sunday());
+        let day_matches = if self.any_day || self.any_weekday {
+            day && weekday
+        } else {
+            day || weekday
+        };
+
+        day_matches
+            && has(self.minutes, at.minute())
+            && has(self.hours, at.hour())
+            && has(self.months, at.month())
+    }
+
+    /// Whether alerts are allowed right now
+    pub fn is_active_now(&self) -> bool {
+        self.is_active_at(chrono::Local::now().naive_local())
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "Expected 5 fields (minute hour day month weekday), got {}",
+                fields.len()
+            ));
+        };
+
+        // Fold Sunday-as-7 into Sunday-as-0
+        let mut weekdays = parse_field(weekday, &WEEKDAY)?;
+        if has(weekdays, 7) {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, &MINUTE)?,
+            hours: parse_field(hour, &HOUR)?,
+            days: parse_field(day, &DAY)?,
+            months: parse_field(month, &MONTH)?,
+            weekdays,
+            any_day: day.starts_with('*'),
+            any_weekday: weekday.starts_with('*'),
+        })
+    }
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+/// Parse a comma-separated list of values, ranges (`1-5`) and steps (`*/15`,
+/// `0-30/10`, `5/15`) into a bit per matching value
+fn parse_field(text: &str, field: &Field) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("Invalid step '{}' in {}", step, field.name))?;
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let (first, last) = if range == "*" {
+            (field.min, field.max)
+        } else if let Some((first, last)) = range.split_once('-') {
+            (parse_value(first, field)?, parse_value(last, field)?)
+        } else {
+            let value = parse_value(range, field)?;
+            // A single value with a step runs to the end, as in `5/15`
+            (value, if step.is_some() { field.max } else { value })
+        };
+        if first > last {
+            return Err(format!(
+                "Range '{}' in {} runs backwards",
+                range, field.name
+            ));
+        }
+
+        for value in (first..=last).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(text: &str, field: &Field) -> Result<u32, String> {
+    let text = text.trim().to_lowercase();
+    let value = match field.names.iter().position(|name| *name == text) {
+        Some(i) => field.min + i as u32,
+        None => text
+            .parse()
+            .map_err(|_| format!("Invalid {} '{}'", field.name, text))?,
+    };
+    if !(field.min..=field.max).contains(&value) {
+        return Err(format!(
+            "{} {} is outside {}-{}",
+            field.name, value, field.min, field.max
+        ));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    // 2025-06-02 is a Monday
+    const MONDAY: &str = "2025-06-02";
+    const SUNDAY: &str = "2025-06-08";
+
+    #[test]
+    fn test_work_hours_cron() {
+        let cron: CronSchedule = "*/15 9-17 * * mon-fri".parse().unwrap();
+
+        assert!(cron.is_active_at(at(MONDAY, "09:00")));
+        assert!(cron.is_active_at(at(MONDAY, "17:45")));
+        assert!(!cron.is_active_at(at(MONDAY, "09:05")));
+        assert!(!cron.is_active_at(at(MONDAY, "18:00")));
+        assert!(!cron.is_active_at(at(SUNDAY, "10:00")));
+
+        // Sunday may be written as 7
+        let weekend: CronSchedule = "* * * jun sat,7".parse().unwrap();
+        assert!(weekend.is_active_at(at(SUNDAY, "03:00")));
+        assert!(!weekend.is_active_at(at("2025-07-06", "03:00")));
+    }
+
+    #[test]
+    fn test_restricted_day_fields_match_either() {
+        // The 1st of the month, or any Monday
+        let cron: CronSchedule = "* * 1 * mon".parse().unwrap();
+        assert!(cron.is_active_at(at("2025-06-01", "12:00")));
+        assert!(cron.is_active_at(at(MONDAY, "12:00")));
+        assert!(!cron.is_active_at(at("2025-06-03", "12:00")));
+    }
+
+    #[test]
+    fn test_invalid_cron() {
+        assert!("* * * *".parse::<CronSchedule>().is_err());
+        assert!("60 * * * *".parse::<CronSchedule>().is_err());
+        assert!("* 17-9 * * *".parse::<CronSchedule>().is_err());
+        assert!("*/0 * * * *".parse::<CronSchedule>().is_err());
+        assert!("* * * * someday".parse::<CronSchedule>().is_err());
+    }
+}
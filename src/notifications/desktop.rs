@@ -0,0 +1,74 @@
+// Desktop notifications for new messages
+//
+// On Windows this shows a toast with an "Open in Beeper" button that focuses
+// the chat. Other platforms don't have desktop notifications yet.
+
+use crate::app_state::SharedAppState;
+
+/// Whether this platform can show desktop notifications
+pub const SUPPORTED: bool = cfg!(windows);
+
+/// What to show in a desktop notification and which chat it opens
+#[derive(Debug, Clone)]
+pub struct DesktopNotification {
+    pub title: String,
+    pub body: String,
+    pub chat_id: String,
+    pub message_id: Option<String>,
+}
+
+/// Show a notification; clicking it (or its button) focuses the chat.
+/// Must be called from within the Tokio runtime.
+#[cfg(windows)]
+pub fn show(app_state: &SharedAppState, notification: DesktopNotification) {
+    use tauri_winrt_notification::Toast;
+
+    const OPEN_ACTION: &str = "open";
+
+    let runtime = tokio::runtime::Handle::current();
+    let app_state = app_state.clone();
+    let chat_id = notification.chat_id.clone();
+    let message_id = notification.message_id.clone();
+
+    // Activation arrives on a WinRT thread; hop back onto the runtime to focus
+    let on_activated = move |_action: Option<String>| {
+        let app_state = app_state.clone();
+        let chat_id = chat_id.clone();
+        let message_id = message_id.clone();
+        runtime.spawn(async move {
+            if let Err(e) =
+                super::actions::focus_chat(&app_state, &chat_id, message_id.as_deref()).await
+            {
+                eprintln!("Error focusing chat {} from notification: {}", chat_id, e);
+            }
+        });
+        Ok(())
+    };
+
+    let toast = || {
+        Toast::new(Toast::POWERSHELL_APP_ID)
+            .title(&notification.title)
+            .text1(&notification.body)
+    };
+
+    let shown = toast()
+        .add_button("Open in Beeper", OPEN_ACTION)
+        .on_activated(on_activated)
+        .show();
+
+    // Older Windows builds reject interactive toasts; fall back to a plain one
+    if let Err(e) = shown {
+        tracing::warn!("Interactive toast failed ({}), showing a plain notification", e);
+        if let Err(e) = toast().show() {
+            tracing::error!("Failed to show desktop notification: {}", e);
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn show(_app_state: &SharedAppState, notification: DesktopNotification) {
+    tracing::debug!(
+        "Desktop notifications are only supported on Windows, skipping '{}'",
+        notification.title
+    );
+}
@@ -0,0 +1,135 @@
+// Read-only JSON view of the configured automations, for scripts and
+// monitoring (`auto-beeper-service --dump-automations`)
+
+use crate::config::Config;
+use crate::notifications::NotificationAutomation;
+use beeper_desktop_api::BeeperClient;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// Print every automation as a JSON array on stdout. Chat names come from one
+/// `list_chats` call; without an API connection they are left null.
+pub async fn dump_automations(config: &Config) -> anyhow::Result<()> {
+    let chat_names = if config.is_api_configured() {
+        let client = BeeperClient::new(config.api.token.reveal(), &config.api.url);
+        match client.list_chats(None, None).await {
+            Ok(response) => response
+                .items
+                .iter()
+                .map(|chat| (chat.id.clone(), chat.display_name()))
+                .collect(),
+            Err(e) => {
+                eprintln!("⚠ Could not list chats, names are omitted: {}", e);
+                HashMap::new()
+            }
+        }
+    } else {
+        eprintln!("⚠ API is not configured, chat names are omitted");
+        HashMap::new()
+    };
+
+    let automations: Vec<Value> = config
+        .notifications
+        .automations
+        .iter()
+        .map(|automation| automation_json(automation, &chat_names, config.notifications.paused))
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&automations)?);
+    Ok(())
+}
+
+/// One automation's settings and status. `chat_names` maps chat ids to names
+/// for the chats that could be looked up.
+fn automation_json(
+    automation: &NotificationAutomation,
+    chat_names: &HashMap<String, String>,
+    paused: bool,
+) -> Value {
+    let chats: Vec<Value> = automation
+        .chat_ids
+        .iter()
+        .map(|id| json!({ "id": id, "name": chat_names.get(id) }))
+        .collect();
+
+    let mut matched: Vec<Value> = chat_names
+        .iter()
+        .filter(|(id, name)| {
+            !automation.chat_ids.contains(id) && automation.matches_chat_name(name)
+        })
+        .map(|(id, name)| json!({ "id": id, "name": name }))
+        .collect();
+    matched.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+
+    let status = if !automation.enabled {
+        "disabled"
+    } else if paused {
+        "paused"
+    } else if automation.is_snoozed() {
+        "snoozed"
+    } else {
+        "active"
+    };
+
+    json!({
+        "id": automation.id,
+        "name": automation.name,
+        "type": automation.automation_type,
+        "enabled": automation.enabled,
+        "status": status,
+        "chat_ids": automation.chat_ids,
+        "chats": chats,
+        "chat_match": automation.chat_match,
+        "matched_chats": matched,
+        "loop": automation.loop_config,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::{AutomationType, LoopConfig, LoopUntil};
+
+    #[test]
+    fn test_automation_json() {
+        let mut automation = NotificationAutomation::new(
+            "a1".to_string(),
+            "On-call".to_string(),
+            vec!["!known".to_string(), "!unknown".to_string()],
+        );
+        automation.automation_type = AutomationType::Loop;
+        automation.loop_config = Some(LoopConfig {
+            until: LoopUntil::Answer,
+            time: None,
+            check_interval: 5000,
+            min_unread: None,
+        });
+        automation.chat_match = Some("oncall-*".to_string());
+
+        let names: HashMap<String, String> = [
+            ("!known", "Family"),
+            ("!web", "oncall-web"),
+            ("!other", "Work"),
+        ]
+        .into_iter()
+        .map(|(id, name)| (id.to_string(), name.to_string()))
+        .collect();
+
+        let value = automation_json(&automation, &names, false);
+        assert_eq!(value["type"], "loop");
+        assert_eq!(value["status"], "active");
+        assert_eq!(value["chats"][0]["name"], "Family");
+        assert!(value["chats"][1]["name"].is_null());
+        assert_eq!(
+            value["matched_chats"],
+            json!([{ "id": "!web", "name": "oncall-web" }])
+        );
+        assert_eq!(value["loop"]["until"], "answer");
+        assert_eq!(value["loop"]["check_interval"], 5000);
+
+        automation.enabled = false;
+        assert_eq!(
+            automation_json(&automation, &names, true)["status"],
+            "disabled"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+// Service-wide cooldown between focus actions, so automations firing close
+// together don't flip Beeper from chat to chat.
+//
+// Focus requests inside the cooldown wait out the rest of it. Only the most
+// recent of them goes ahead; the ones it replaced are dropped, as the chat
+// they would have shown would be covered up straight away anyway.
+
+use crate::app_state::SharedAppState;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub static FOCUS_GATE: FocusGate = FocusGate::new();
+
+/// The configured gap between focus actions
+pub fn cooldown(app_state: &SharedAppState) -> Duration {
+    let ms = app_state
+        .with_config(|config| config.notifications.focus_cooldown_ms)
+        .unwrap_or_default();
+    Duration::from_millis(ms)
+}
+
+/// What a focus request should do next
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Turn {
+    /// Focus now
+    Go,
+    /// Wait this long, then check whether the request is still the latest
+    Wait(Duration, u64),
+}
+
+#[derive(Debug)]
+struct GateState {
+    /// When the latest focus went ahead
+    last_focus: Option<Instant>,
+    /// Ticket of the most recent request; older waiting ones give way to it
+    latest: u64,
+}
+
+#[derive(Debug)]
+pub struct FocusGate {
+    state: Mutex<GateState>,
+}
+
+impl FocusGate {
+    const fn new() -> Self {
+        Self {
+            state: Mutex::new(GateState {
+                last_focus: None,
+                latest: 0,
+            }),
+        }
+    }
+
+    /// Take a turn for a focus request made at `now`
+    fn claim(&self, now: Instant, cooldown: Duration) -> Turn {
+        let Ok(mut state) = self.state.lock() else {
+            return Turn::Go;
+        };
+        state.latest += 1;
+
+        let wait = state
+            .last_focus
+            .map(|last| (last + cooldown).saturating_duration_since(now))
+            .unwrap_or_default();
+        if wait.is_zero() {
+            state.last_focus = Some(now);
+            Turn::Go
+        } else {
+            Turn::Wait(wait, state.latest)
+        }
+    }
+
+    /// After waiting, whether the request holding `ticket` still goes ahead
+    fn confirm(&self, ticket: u64, now: Instant) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return true;
+        };
+        if state.latest != ticket {
+            return false;
+        }
+        state.last_focus = Some(now);
+        true
+    }
+
+    /// Wait until a focus may go ahead. Returns false when a newer focus
+    /// request came in meanwhile and this one should be dropped.
+    pub async fn wait_turn(&self, cooldown: Duration) -> bool {
+        match self.claim(Instant::now(), cooldown) {
+            Turn::Go => true,
+            Turn::Wait(wait, ticket) => {
+                tokio::time::sleep(wait).await;
+                self.confirm(ticket, Instant::now())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_request_in_cooldown_wins() {
+        let gate = FocusGate::new();
+        let cooldown = Duration::from_secs(2);
+        let start = Instant::now();
+
+        assert_eq!(gate.claim(start, cooldown), Turn::Go);
+
+        // Two more arrive within the cooldown; only the second goes ahead
+        let first = gate.claim(start + Duration::from_millis(500), cooldown);
+        let second = gate.claim(start + Duration::from_secs(1), cooldown);
+        assert_eq!(first, Turn::Wait(Duration::from_millis(1500), 2));
+        assert_eq!(second, Turn::Wait(Duration::from_secs(1), 3));
+        let end = start + cooldown;
+        assert!(!gate.confirm(2, end));
+        assert!(gate.confirm(3, end));
+
+        // The cooldown now runs from the focus that went ahead
+        assert!(matches!(
+            gate.claim(end + Duration::from_secs(1), cooldown),
+            Turn::Wait(..)
+        ));
+        assert_eq!(gate.claim(end + cooldown, cooldown), Turn::Go);
+
+        // No cooldown lets everything through
+        assert_eq!(gate.claim(end + cooldown, Duration::ZERO), Turn::Go);
+    }
+}
@@ -0,0 +1,199 @@
+// Service-wide view of whether the Beeper API is answering, used to sound an
+// alert when automations go blind and again when they can see.
+//
+// A rejected token is tracked apart from other failures: it won't fix itself,
+// so polling pauses until the user enters a new one.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Failed requests in a row before the API counts as down, so a single
+/// timeout doesn't set off the error sound
+const FAILURES_BEFORE_ALERT: u32 = 3;
+
+/// Shortest gap between two error alerts while the API flaps up and down
+const ALERT_DEBOUNCE: Duration = Duration::from_secs(5 * 60);
+
+/// While the token is rejected, how long polling pauses before trying again
+const TOKEN_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+pub static API_HEALTH: ApiHealth = ApiHealth::new();
+
+/// A change in API health worth telling the user about
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HealthChange {
+    /// Requests started failing after working
+    Failing,
+    /// Beeper keeps rejecting the API token
+    TokenRejected,
+    /// Requests work again after a `Failing` alert
+    Recovered,
+}
+
+#[derive(Debug)]
+struct HealthState {
+    consecutive_failures: u32,
+    /// Whether a `Failing` alert is outstanding
+    alerted: bool,
+    last_alert: Option<Instant>,
+    /// Requests in a row rejected for a bad token
+    consecutive_rejections: u32,
+    /// Latest rejection once the token counts as rejected
+    token_rejected_at: Option<Instant>,
+    /// When the token first counted as rejected, in Unix milliseconds
+    token_rejected_ms: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct ApiHealth {
+    state: Mutex<HealthState>,
+}
+
+impl ApiHealth {
+    const fn new() -> Self {
+        Self {
+            state: Mutex::new(HealthState {
+                consecutive_failures: 0,
+                alerted: false,
+                last_alert: None,
+                consecutive_rejections: 0,
+                token_rejected_at: None,
+                token_rejected_ms: None,
+            }),
+        }
+    }
+
+    /// Count one API request. Returns a change when this request moved the
+    /// API from healthy to failing or back.
+    pub fn observe(&self, ok: bool, now: Instant) -> Option<HealthChange> {
+        let mut state = self.state.lock().ok()?;
+
+        if ok {
+            state.consecutive_failures = 0;
+            state.consecutive_rejections = 0;
+            state.token_rejected_at = None;
+            state.token_rejected_ms = None;
+            if state.alerted {
+                state.alerted = false;
+                return Some(HealthChange::Recovered);
+            }
+            return None;
+        }
+
+        state.consecutive_failures += 1;
+        state.consecutive_rejections = 0;
+        let debounced = state
+            .last_alert
+            .is_some_and(|last| now.duration_since(last) < ALERT_DEBOUNCE);
+        if state.alerted || state.consecutive_failures < FAILURES_BEFORE_ALERT || debounced {
+            return None;
+        }
+
+        state.alerted = true;
+        state.last_alert = Some(now);
+        Some(HealthChange::Failing)
+    }
+
+    /// Count one request turned away for a bad token. Returns `TokenRejected`
+    /// once enough arrive in a row; polling then pauses (see
+    /// [`Self::polling_paused`]) between retries until a request works.
+    pub fn observe_rejected(&self, now: Instant) -> Option<HealthChange> {
+        let mut state = self.state.lock().ok()?;
+        state.consecutive_failures += 1;
+        state.consecutive_rejections += 1;
+        if state.consecutive_rejections < FAILURES_BEFORE_ALERT {
+            return None;
+        }
+
+        state.token_rejected_at = Some(now);
+        if state.token_rejected_ms.is_some() {
+            return None;
+        }
+        state.token_rejected_ms = Some(crate::notifications::quiet::now_ms());
+        state.alerted = true;
+        state.last_alert = Some(now);
+        Some(HealthChange::TokenRejected)
+    }
+
+    /// Whether watchers should skip polling because the token was rejected
+    /// recently. Lets one round through every `TOKEN_RETRY_INTERVAL` in case
+    /// the token works again.
+    pub fn polling_paused(&self, now: Instant) -> bool {
+        self.state.lock().is_ok_and(|state| {
+            state
+                .token_rejected_at
+                .is_some_and(|at| now.saturating_duration_since(at) < TOKEN_RETRY_INTERVAL)
+        })
+    }
+
+    /// When the token was found rejected, in Unix milliseconds, while it still is
+    pub fn token_rejected_ms(&self) -> Option<u64> {
+        self.state.lock().ok()?.token_rejected_ms
+    }
+
+    /// Forget a rejected token, e.g. once the config has a new one, so
+    /// polling resumes right away
+    pub fn clear_token_rejection(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.consecutive_rejections = 0;
+            state.token_rejected_at = None;
+            state.token_rejected_ms = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failing_and_recovered_transitions() {
+        let health = ApiHealth::new();
+        let start = Instant::now();
+
+        // A short blip doesn't alert
+        assert_eq!(health.observe(false, start), None);
+        assert_eq!(health.observe(true, start), None);
+
+        assert_eq!(health.observe(false, start), None);
+        assert_eq!(health.observe(false, start), None);
+        assert_eq!(health.observe(false, start), Some(HealthChange::Failing));
+        assert_eq!(health.observe(false, start), None);
+        assert_eq!(health.observe(true, start), Some(HealthChange::Recovered));
+        assert_eq!(health.observe(true, start), None);
+
+        // Going down again soon after waits out the debounce before alerting
+        for _ in 0..FAILURES_BEFORE_ALERT {
+            assert_eq!(health.observe(false, start), None);
+        }
+        let later = start + ALERT_DEBOUNCE;
+        assert_eq!(health.observe(false, later), Some(HealthChange::Failing));
+    }
+
+    #[test]
+    fn test_rejected_token_pauses_polling_until_it_works() {
+        let health = ApiHealth::new();
+        let start = Instant::now();
+
+        for _ in 1..FAILURES_BEFORE_ALERT {
+            assert_eq!(health.observe_rejected(start), None);
+        }
+        assert!(!health.polling_paused(start));
+        assert_eq!(
+            health.observe_rejected(start),
+            Some(HealthChange::TokenRejected)
+        );
+        assert!(health.polling_paused(start));
+        assert!(health.token_rejected_ms().is_some());
+
+        // A retry that's rejected again pauses again without a second alert
+        let retry = start + TOKEN_RETRY_INTERVAL;
+        assert!(!health.polling_paused(retry));
+        assert_eq!(health.observe_rejected(retry), None);
+        assert!(health.polling_paused(retry));
+
+        assert_eq!(health.observe(true, retry), Some(HealthChange::Recovered));
+        assert!(!health.polling_paused(retry));
+        assert_eq!(health.token_rejected_ms(), None);
+    }
+}
@@ -0,0 +1,156 @@
+// Record of what automations fired, kept as JSON lines in the data directory
+// so the configurator can show it while the service writes it
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+/// Entries kept before the oldest are dropped
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// Lines in each history file this process appends to, counted on its first
+/// append there. Holding the lock also keeps appends to a file in order.
+static LINE_COUNTS: Mutex<BTreeMap<PathBuf, usize>> = Mutex::new(BTreeMap::new());
+
+/// One automation trigger and the actions it took
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Local time, formatted like the service log
+    pub timestamp: String,
+    pub automation_id: String,
+    pub automation: String,
+    pub chat_id: String,
+    /// Chat name when known, otherwise the chat id
+    pub chat: String,
+    pub sender: String,
+    /// Actions that ran, e.g. "sound" or "focus"
+    pub actions: Vec<String>,
+    /// The actions were only logged, not performed
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Get history file path
+pub fn history_file_path() -> PathBuf {
+    crate::logging::data_dir().join("history.jsonl")
+}
+
+/// Append an entry to the history file at `path`, dropping the oldest past
+/// the cap. The write happens on a blocking thread, so the caller's task
+/// never waits on the disk.
+pub fn record_to(path: &Path, entry: HistoryEntry) {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        if let Err(e) = append_to(&path, &entry, MAX_HISTORY_ENTRIES) {
+            tracing::error!("Failed to write notification history: {}", e);
+        }
+    });
+}
+
+/// The most recent entries, oldest first
+pub fn read_recent(limit: usize) -> Vec<HistoryEntry> {
+    read_from(&history_file_path(), limit)
+}
+
+/// Append one line. The file may grow to twice `max_entries` before it is
+/// rewritten with the newest `max_entries`, so most appends never read it.
+fn append_to(path: &Path, entry: &HistoryEntry, max_entries: usize) -> std::io::Result<()> {
+    let line = serde_json::to_string(entry)?;
+
+    let mut counts = LINE_COUNTS.lock().unwrap_or_else(PoisonError::into_inner);
+    let count = match counts.get(path) {
+        Some(count) => *count,
+        None => std::fs::read_to_string(path).map_or(0, |content| content.lines().count()),
+    };
+
+    if count < max_entries * 2 {
+        let mut f = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(f, "{}", line)?;
+        counts.insert(path.to_path_buf(), count + 1);
+        return Ok(());
+    }
+
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+    let mut lines: Vec<&str> = content.lines().collect();
+    lines.push(&line);
+    let skip_count = lines.len().saturating_sub(max_entries);
+
+    let mut f = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(path)?;
+    for line in &lines[skip_count..] {
+        writeln!(f, "{}", line)?;
+    }
+    counts.insert(path.to_path_buf(), lines.len() - skip_count);
+    Ok(())
+}
+
+fn read_from(path: &Path, limit: usize) -> Vec<HistoryEntry> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    // Skip lines that don't parse, e.g. one cut off mid-write
+    let entries: Vec<HistoryEntry> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    let skip_count = entries.len().saturating_sub(limit);
+    entries.into_iter().skip(skip_count).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(automation: &str) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: "2025-06-02 09:00:00".to_string(),
+            automation_id: "a1".to_string(),
+            automation: automation.to_string(),
+            chat_id: "!chat".to_string(),
+            chat: "Work".to_string(),
+            sender: "Alice".to_string(),
+            actions: vec!["sound".to_string()],
+            dry_run: false,
+        }
+    }
+
+    #[test]
+    fn test_history_rotation_and_read() {
+        let path = std::env::temp_dir().join(format!("history-{}.jsonl", uuid::Uuid::new_v4()));
+
+        // Up to twice the cap is kept, then it's trimmed back to the cap
+        for i in 0..6 {
+            append_to(&path, &entry(&format!("run {}", i)), 3).unwrap();
+        }
+        assert_eq!(read_from(&path, 10).len(), 6);
+        append_to(&path, &entry("run 6"), 3).unwrap();
+        append_to(&path, &entry("run 7"), 3).unwrap();
+        // A torn last line is ignored
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"timestamp\":")
+            .unwrap();
+
+        let names: Vec<String> = read_from(&path, 10).into_iter().map(|e| e.automation).collect();
+        assert_eq!(names, vec!["run 4", "run 5", "run 6", "run 7"]);
+
+        let names: Vec<String> = read_from(&path, 1).into_iter().map(|e| e.automation).collect();
+        assert_eq!(names, vec!["run 7"]);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,88 @@
+// Recognising my own messages when the API doesn't say who sent them, e.g.
+// messages I sent from my phone that sync in without `is_sender`.
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+pub static SELF_IDENTITY: SelfIdentity = SelfIdentity::new();
+
+#[derive(Debug)]
+struct SenderNames {
+    /// Lowercased sender names seen on messages marked as mine
+    mine: BTreeSet<String>,
+    /// Lowercased sender names seen on messages marked as someone else's
+    others: BTreeSet<String>,
+}
+
+/// The names my messages are sent under, learned from every polled message
+/// that carries `is_sender`. The account list doesn't include the user's own
+/// name, so this starts empty and fills in from the first polls at startup.
+#[derive(Debug)]
+pub struct SelfIdentity {
+    names: Mutex<SenderNames>,
+}
+
+impl SelfIdentity {
+    const fn new() -> Self {
+        Self {
+            names: Mutex::new(SenderNames {
+                mine: BTreeSet::new(),
+                others: BTreeSet::new(),
+            }),
+        }
+    }
+
+    /// Whether a message is mine. The API's `is_sender` wins and is
+    /// remembered for its sender name; without it, a name only ever seen on
+    /// my messages counts as me. `None` when neither tells.
+    pub fn resolve(&self, is_sender: Option<bool>, sender_name: Option<&str>) -> Option<bool> {
+        let name = sender_name
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty());
+        let Ok(mut names) = self.names.lock() else {
+            return is_sender;
+        };
+
+        match (is_sender, name) {
+            (Some(true), Some(name)) => {
+                names.mine.insert(name);
+                Some(true)
+            }
+            (Some(false), Some(name)) => {
+                names.others.insert(name);
+                Some(false)
+            }
+            (Some(is_sender), None) => Some(is_sender),
+            // A name shared with someone else proves nothing
+            (None, Some(name)) => {
+                (names.mine.contains(&name) && !names.others.contains(&name)).then_some(true)
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_learns_my_name() {
+        let identity = SelfIdentity::new();
+
+        // Nothing learned yet
+        assert_eq!(identity.resolve(None, Some("Erdem")), None);
+
+        assert_eq!(identity.resolve(Some(true), Some("Erdem")), Some(true));
+        assert_eq!(identity.resolve(Some(false), Some("Ada")), Some(false));
+
+        // Without is_sender, my name marks the message as mine
+        assert_eq!(identity.resolve(None, Some(" erdem ")), Some(true));
+        assert_eq!(identity.resolve(None, Some("Ada")), None);
+        assert_eq!(identity.resolve(None, None), None);
+
+        // Once someone else turns up with the same name, it no longer decides
+        assert_eq!(identity.resolve(Some(false), Some("Erdem")), Some(false));
+        assert_eq!(identity.resolve(None, Some("Erdem")), None);
+    }
+}
@@ -0,0 +1,300 @@
+// Counters for the optional Prometheus endpoint. Service-wide counters live
+// in a static so the poll helpers can record them without extra plumbing;
+// per-automation counts come from the service's session stats.
+
+use crate::notifications::service::{AutomationStats, SharedStats};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Upper bounds, in seconds, of the API latency histogram buckets
+const LATENCY_BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// Largest request read before answering; scrapers send a few hundred bytes
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// How long a scraper gets to send its request before the connection is dropped
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub static METRICS: Metrics = Metrics::new();
+
+#[derive(Debug)]
+struct Histogram {
+    /// Observations per bucket, not cumulative
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+/// Service-wide counters
+#[derive(Debug)]
+pub struct Metrics {
+    polls: AtomicU64,
+    api_errors: AtomicU64,
+    api_latency: Mutex<Histogram>,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            polls: AtomicU64::new(0),
+            api_errors: AtomicU64::new(0),
+            api_latency: Mutex::new(Histogram {
+                buckets: [0; LATENCY_BUCKETS.len()],
+                sum: 0.0,
+                count: 0,
+            }),
+        }
+    }
+
+    /// Count one request to the Beeper API and how long it took
+    pub fn record_api_call(&self, elapsed: Duration, ok: bool) {
+        self.polls.fetch_add(1, Ordering::Relaxed);
+        if !ok {
+            self.api_errors.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let seconds = elapsed.as_secs_f64();
+        if let Ok(mut histogram) = self.api_latency.lock() {
+            if let Some(bucket) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+                histogram.buckets[bucket] += 1;
+            }
+            histogram.sum += seconds;
+            histogram.count += 1;
+        }
+    }
+
+    /// Prometheus text exposition of these counters and the per-automation stats
+    pub fn render(&self, stats: &HashMap<String, AutomationStats>) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "# HELP beeper_automations_polls_total Requests made to the Beeper API."
+        );
+        let _ = writeln!(out, "# TYPE beeper_automations_polls_total counter");
+        let _ = writeln!(
+            out,
+            "beeper_automations_polls_total {}",
+            self.polls.load(Ordering::Relaxed)
+        );
+
+        let automation_errors: u64 = stats.values().map(|s| s.errors).sum();
+        let _ = writeln!(
+            out,
+            "# HELP beeper_automations_errors_total Failed API requests and automation errors."
+        );
+        let _ = writeln!(out, "# TYPE beeper_automations_errors_total counter");
+        let _ = writeln!(
+            out,
+            "beeper_automations_errors_total {}",
+            self.api_errors.load(Ordering::Relaxed) + automation_errors
+        );
+
+        let mut rows: Vec<(&String, &AutomationStats)> = stats.iter().collect();
+        rows.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+
+        let _ = writeln!(
+            out,
+            "# HELP beeper_automations_notifications_total Times each automation fired."
+        );
+        let _ = writeln!(out, "# TYPE beeper_automations_notifications_total counter");
+        for (id, row) in &rows {
+            let _ = writeln!(
+                out,
+                "beeper_automations_notifications_total{{automation_id=\"{}\",automation=\"{}\"}} {}",
+                escape_label(id),
+                escape_label(&row.name),
+                row.triggered
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP beeper_automations_automation_errors_total Errors per automation."
+        );
+        let _ = writeln!(
+            out,
+            "# TYPE beeper_automations_automation_errors_total counter"
+        );
+        for (id, row) in &rows {
+            let _ = writeln!(
+                out,
+                "beeper_automations_automation_errors_total{{automation_id=\"{}\",automation=\"{}\"}} {}",
+                escape_label(id),
+                escape_label(&row.name),
+                row.errors
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP beeper_automations_api_request_duration_seconds Beeper API request latency."
+        );
+        let _ = writeln!(
+            out,
+            "# TYPE beeper_automations_api_request_duration_seconds histogram"
+        );
+        if let Ok(histogram) = self.api_latency.lock() {
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "beeper_automations_api_request_duration_seconds_bucket{{le=\"{}\"}} {}",
+                    bound, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "beeper_automations_api_request_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "beeper_automations_api_request_duration_seconds_sum {}",
+                histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "beeper_automations_api_request_duration_seconds_count {}",
+                histogram.count
+            );
+        }
+
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serve `/metrics` until the task is aborted. Each connection gets one response.
+pub(crate) async fn serve(address: String, port: u16, stats: SharedStats) {
+    let listener = match tokio::net::TcpListener::bind((address.as_str(), port)).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!(
+                "✗ Failed to start metrics endpoint on {}:{}: {}",
+                address, port, e
+            );
+            tracing::error!(
+                "Failed to bind metrics endpoint on {}:{}: {}",
+                address,
+                port,
+                e
+            );
+            return;
+        }
+    };
+    println!(
+        "📈 Metrics available at http://{}:{}/metrics",
+        address, port
+    );
+
+    loop {
+        let (mut stream, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::warn!("Metrics endpoint failed to accept a connection: {}", e);
+                continue;
+            }
+        };
+
+        let stats = stats.clone();
+        tokio::spawn(async move {
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            let read_request = async {
+                while !request.windows(4).any(|w| w == b"\r\n\r\n")
+                    && request.len() < MAX_REQUEST_BYTES
+                {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+            };
+            if tokio::time::timeout(REQUEST_TIMEOUT, read_request)
+                .await
+                .is_err()
+            {
+                tracing::debug!("Metrics client sent no request in time, closing");
+                return;
+            }
+
+            let request_line = String::from_utf8_lossy(&request);
+            let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+            let (status, content_type, body) = if path == "/metrics" {
+                (
+                    "200 OK",
+                    "text/plain; version=0.0.4",
+                    METRICS.render(&*stats.read().await),
+                )
+            } else {
+                ("404 Not Found", "text/plain", "Not found\n".to_string())
+            };
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let metrics = Metrics::new();
+        metrics.record_api_call(Duration::from_millis(20), true);
+        metrics.record_api_call(Duration::from_millis(300), false);
+        metrics.record_api_call(Duration::from_secs(10), true);
+
+        let mut stats = HashMap::new();
+        stats.insert(
+            "a1".to_string(),
+            AutomationStats {
+                name: "Work \"urgent\"".to_string(),
+                triggered: 4,
+                errors: 1,
+                ..Default::default()
+            },
+        );
+
+        let text = metrics.render(&stats);
+        assert!(text.contains("beeper_automations_polls_total 3\n"));
+        assert!(text.contains("beeper_automations_errors_total 2\n"));
+        assert!(text.contains(
+            "beeper_automations_notifications_total{automation_id=\"a1\",automation=\"Work \\\"urgent\\\"\"} 4\n"
+        ));
+        assert!(
+            text.contains(
+                "beeper_automations_api_request_duration_seconds_bucket{le=\"0.025\"} 1\n"
+            )
+        );
+        assert!(
+            text.contains("beeper_automations_api_request_duration_seconds_bucket{le=\"0.5\"} 2\n")
+        );
+        assert!(
+            text.contains(
+                "beeper_automations_api_request_duration_seconds_bucket{le=\"+Inf\"} 3\n"
+            )
+        );
+    }
+}
@@ -1,4 +1,25 @@
+pub mod actions;
+pub mod chat_match;
+pub mod control;
+pub mod cron;
+pub mod desktop;
+pub mod dump;
+pub mod focus_gate;
+pub mod health;
+pub mod history;
+pub mod identity;
+pub mod metrics;
 pub mod models;
+pub mod preflight;
+pub mod quiet;
+pub mod rate_limit;
+pub mod schedule;
+pub mod scheduled_focus;
 pub mod service;
+pub mod sounds;
+pub mod state;
 
 pub use models::*;
+pub use cron::CronSchedule;
+pub use schedule::Schedule;
+pub use scheduled_focus::ScheduledFocus;
@@ -1,4 +1,7 @@
+use crate::config::RedactedToken;
+use crate::notifications::schedule::Schedule;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NtfyConfig {
@@ -10,6 +13,34 @@ pub struct NtfyConfig {
     pub message: String,
     #[serde(default = "default_priority")]
     pub priority: u8,
+    /// Topic appended to `url`, for when `url` is just the server address
+    #[serde(default)]
+    pub topic: Option<String>,
+    /// Access token, sent as a Bearer `Authorization` header
+    #[serde(default)]
+    pub token: Option<RedactedToken>,
+    /// Title template, supports the same variables as `message`
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Tags or emoji shortcodes, e.g. "speech_balloon"
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Alert through this topic even while switched off when the alert
+    /// sound can't play, and show a desktop notification where supported
+    #[serde(default)]
+    pub fallback_when_no_audio: bool,
+}
+
+impl NtfyConfig {
+    /// The URL to publish to, with the topic appended when one is set
+    pub fn endpoint(&self) -> String {
+        match self.topic.as_deref().map(str::trim) {
+            Some(topic) if !topic.is_empty() => {
+                format!("{}/{}", self.url.trim_end_matches('/'), topic.trim_start_matches('/'))
+            }
+            _ => self.url.clone(),
+        }
+    }
 }
 
 fn default_priority() -> u8 {
@@ -23,10 +54,39 @@ impl Default for NtfyConfig {
             url: String::new(),
             message: "New message from {sender} in {chat_name}".to_string(),
             priority: 5,
+            topic: None,
+            token: None,
+            title: None,
+            tags: Vec::new(),
+            fallback_when_no_audio: false,
         }
     }
 }
 
+/// An HTTP request sent with the trigger as JSON, for services like Slack
+/// or Zapier that take a webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// HTTP method, e.g. `PUT`
+    #[serde(default = "default_webhook_method")]
+    pub method: String,
+    /// Extra request headers, e.g. `Authorization`
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+fn default_webhook_method() -> String {
+    "POST".to_string()
+}
+
+impl WebhookConfig {
+    /// The method to send with, or `None` when `method` isn't a valid one
+    pub fn http_method(&self) -> Option<reqwest::Method> {
+        reqwest::Method::from_bytes(self.method.trim().to_uppercase().as_bytes()).ok()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationAutomation {
     pub id: String,
@@ -39,6 +99,97 @@ pub struct NotificationAutomation {
     pub enabled: bool,
     #[serde(default)]
     pub ntfy_config: Option<NtfyConfig>,
+    /// Send each trigger to this webhook, alongside ntfy
+    #[serde(default)]
+    pub webhook: Option<WebhookConfig>,
+    /// Don't alert on messages I sent myself
+    #[serde(default = "default_ignore_own_messages")]
+    pub ignore_own_messages: bool,
+    /// Mark the chat read after the other actions fire. With
+    /// `LoopUntil::MessageSeen` this ends the loop after the first alert,
+    /// since the chat's unread count drops to zero.
+    #[serde(default)]
+    pub mark_read: bool,
+    /// When focusing, jump to the triggering message instead of just the chat
+    #[serde(default)]
+    pub focus_message: bool,
+    /// Alert on the message that is already latest when the automation starts,
+    /// instead of silently using it as the baseline. For loop automations this
+    /// only changes `ForATime`, which otherwise waits for a new message.
+    #[serde(default)]
+    pub notify_on_startup: bool,
+    /// Show a desktop notification with an "Open in Beeper" button (Windows)
+    #[serde(default)]
+    pub desktop_notification: bool,
+    /// Only alert on these days and hours; polling continues outside them
+    #[serde(default)]
+    pub schedule: Option<Schedule>,
+    /// Cron expression for when the automation may alert, e.g.
+    /// `"* 9-17 * * mon-fri"`. Takes precedence over `schedule` when both
+    /// are set; an invalid one is ignored with a warning.
+    #[serde(default)]
+    pub cron: Option<String>,
+    /// Suppress alerts until this time, then resume on their own
+    #[serde(default)]
+    pub snoozed_until: Option<std::time::SystemTime>,
+    /// Name of a notification profile supplying settings this automation leaves unset
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Ignore messages sent longer ago than this, e.g. old history that
+    /// Beeper syncs in with a fresh sort key
+    #[serde(default)]
+    pub max_message_age_secs: Option<u64>,
+    /// Also watch every chat whose name matches this glob (`*`, `?`), e.g.
+    /// `"oncall-*"`. Resolved against the chat list whenever the service
+    /// (re)loads the config.
+    #[serde(default)]
+    pub chat_match: Option<String>,
+    /// Sound to play instead of `notification_sound` for particular chats,
+    /// keyed by chat ID
+    #[serde(default)]
+    pub chat_sound_overrides: HashMap<String, String>,
+    /// Sounds picked by message text, checked in order before the per-chat
+    /// and default sounds, e.g. a loud alarm for "urgent"
+    #[serde(default)]
+    pub sound_rules: Vec<SoundRule>,
+    /// Only alert on messages that mention me, e.g. in busy group chats
+    #[serde(default)]
+    pub mentions_only: bool,
+    /// Names or handles that count as a mention of me, matched as whole
+    /// words in the message text. Used with `mentions_only` since the API
+    /// doesn't report mentions itself.
+    #[serde(default)]
+    pub mention_names: Vec<String>,
+    /// Text for ntfy and desktop notifications, with `{sender}`,
+    /// `{chat_name}`, `{message}` and `{unread}` placeholders. Replaces the
+    /// ntfy `message` and the default desktop text when set.
+    #[serde(default)]
+    pub notification_template: Option<String>,
+}
+
+/// A sound for messages whose text matches a regular expression
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoundRule {
+    /// Regular expression searched for in the message text; prefix it with
+    /// `(?i)` to ignore case
+    pub pattern: String,
+    pub sound: String,
+}
+
+/// Named sound and ntfy settings that several automations can share
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationProfile {
+    pub name: String,
+    #[serde(default)]
+    pub notification_sound: Option<String>,
+    #[serde(default)]
+    pub ntfy_config: Option<NtfyConfig>,
+    #[serde(default)]
+    pub desktop_notification: bool,
+}
+
+fn default_ignore_own_messages() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -47,6 +198,35 @@ pub enum AutomationType {
     Loop,
     #[serde(rename = "immediate")]
     Immediate,
+    /// Alerts right away on each new message, then keeps reminding like a
+    /// loop automation
+    #[serde(rename = "combined")]
+    Combined,
+    /// Alerts when a chat appears in the chat list that wasn't there before,
+    /// e.g. a new DM or a group I was added to. Watches the whole chat list
+    /// rather than the selected chats.
+    #[serde(rename = "new_chat")]
+    NewChat,
+}
+
+impl AutomationType {
+    /// Every variant, in the order the configurator lists them
+    pub const ALL: [AutomationType; 4] = [
+        AutomationType::Immediate,
+        AutomationType::Loop,
+        AutomationType::Combined,
+        AutomationType::NewChat,
+    ];
+
+    /// Whether this type reads its `loop_config`
+    pub fn uses_loop_config(self) -> bool {
+        matches!(self, AutomationType::Loop | AutomationType::Combined)
+    }
+
+    /// Whether this type watches the chats it is given, rather than the chat list
+    pub fn watches_chats(self) -> bool {
+        self != AutomationType::NewChat
+    }
 }
 
 impl std::fmt::Display for AutomationType {
@@ -54,6 +234,8 @@ impl std::fmt::Display for AutomationType {
         match self {
             AutomationType::Loop => write!(f, "Loop"),
             AutomationType::Immediate => write!(f, "Immediate"),
+            AutomationType::Combined => write!(f, "Combined"),
+            AutomationType::NewChat => write!(f, "New Chat"),
         }
     }
 }
@@ -64,6 +246,10 @@ pub struct LoopConfig {
     pub time: Option<u64>,
     #[serde(default = "default_check_interval")]
     pub check_interval: u64,
+    /// For `MessageSeen`, how many unread messages the chat needs before
+    /// alerting. Defaults to 1.
+    #[serde(default)]
+    pub min_unread: Option<u32>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -74,6 +260,19 @@ pub enum LoopUntil {
     Answer,
     #[serde(rename = "for_a_time")]
     ForATime,
+    /// Alert once per new message, as long as it is still unread
+    #[serde(rename = "once")]
+    Once,
+}
+
+impl LoopUntil {
+    /// Every variant, in the order the configurator lists them
+    pub const ALL: [LoopUntil; 4] = [
+        LoopUntil::MessageSeen,
+        LoopUntil::Answer,
+        LoopUntil::ForATime,
+        LoopUntil::Once,
+    ];
 }
 
 impl std::fmt::Display for LoopUntil {
@@ -82,6 +281,7 @@ impl std::fmt::Display for LoopUntil {
             LoopUntil::MessageSeen => write!(f, "Message Seen"),
             LoopUntil::Answer => write!(f, "Answer"),
             LoopUntil::ForATime => write!(f, "For A Time"),
+            LoopUntil::Once => write!(f, "Once"),
         }
     }
 }
@@ -102,6 +302,169 @@ impl NotificationAutomation {
             loop_config: None,
             enabled: true,
             ntfy_config: None,
+            webhook: None,
+            ignore_own_messages: true,
+            mark_read: false,
+            focus_message: false,
+            notify_on_startup: false,
+            desktop_notification: false,
+            schedule: None,
+            cron: None,
+            snoozed_until: None,
+            profile: None,
+            max_message_age_secs: None,
+            chat_match: None,
+            chat_sound_overrides: HashMap::new(),
+            sound_rules: Vec::new(),
+            mentions_only: false,
+            mention_names: Vec::new(),
+            notification_template: None,
         }
     }
+
+    /// The sound for a message in this chat: its override if it has one,
+    /// otherwise the automation's sound
+    pub fn sound_for_chat(&self, chat_id: &str) -> Option<&String> {
+        self.chat_sound_overrides
+            .get(chat_id)
+            .or(self.notification_sound.as_ref())
+    }
+
+    /// The sound for a message: from the first sound rule its text matches,
+    /// otherwise the chat's sound. Rules with an invalid pattern are skipped.
+    pub fn sound_for_message(&self, chat_id: &str, message_text: &str) -> Option<&String> {
+        self.sound_rules
+            .iter()
+            .find(|rule| {
+                regex::Regex::new(&rule.pattern).is_ok_and(|pattern| pattern.is_match(message_text))
+            })
+            .map(|rule| &rule.sound)
+            .or_else(|| self.sound_for_chat(chat_id))
+    }
+
+    /// Whether a chat with this name is picked up by the automation's `chat_match`
+    pub fn matches_chat_name(&self, name: &str) -> bool {
+        self.chat_match
+            .as_deref()
+            .is_some_and(|pattern| crate::notifications::chat_match::glob_matches(pattern, name))
+    }
+
+    /// The automation with its profile's settings filled in wherever it
+    /// doesn't set its own. Unchanged without a profile or if the profile is missing.
+    pub fn resolve_profile(&self, profiles: &[NotificationProfile]) -> Self {
+        let mut resolved = self.clone();
+        let Some(profile) = self
+            .profile
+            .as_ref()
+            .and_then(|name| profiles.iter().find(|p| &p.name == name))
+        else {
+            return resolved;
+        };
+
+        if resolved.notification_sound.is_none() {
+            resolved.notification_sound = profile.notification_sound.clone();
+        }
+        if resolved.ntfy_config.is_none() {
+            resolved.ntfy_config = profile.ntfy_config.clone();
+        }
+        resolved.desktop_notification |= profile.desktop_notification;
+        resolved
+    }
+
+    /// Whether a snooze is still running
+    pub fn is_snoozed(&self) -> bool {
+        self.snoozed_until
+            .is_some_and(|until| until > std::time::SystemTime::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_profile_keeps_inline_settings() {
+        let profiles = vec![NotificationProfile {
+            name: "loud".to_string(),
+            notification_sound: Some("alarm.mp3".to_string()),
+            ntfy_config: Some(NtfyConfig {
+                enabled: true,
+                url: "https://ntfy.sh/me".to_string(),
+                ..Default::default()
+            }),
+            desktop_notification: true,
+        }];
+
+        let mut automation =
+            NotificationAutomation::new("a".to_string(), "Work".to_string(), vec![]);
+        automation.profile = Some("loud".to_string());
+        automation.notification_sound = Some("ping.wav".to_string());
+
+        let resolved = automation.resolve_profile(&profiles);
+        assert_eq!(resolved.notification_sound.as_deref(), Some("ping.wav"));
+        assert_eq!(resolved.ntfy_config.unwrap().url, "https://ntfy.sh/me");
+        assert!(resolved.desktop_notification);
+
+        automation.profile = Some("missing".to_string());
+        assert!(automation.resolve_profile(&profiles).ntfy_config.is_none());
+    }
+
+    #[test]
+    fn test_sound_for_chat() {
+        let mut automation =
+            NotificationAutomation::new("a".to_string(), "Work".to_string(), vec![]);
+        assert_eq!(automation.sound_for_chat("boss"), None);
+
+        automation.notification_sound = Some("ping.wav".to_string());
+        automation
+            .chat_sound_overrides
+            .insert("boss".to_string(), "alarm.mp3".to_string());
+        assert_eq!(automation.sound_for_chat("boss").unwrap(), "alarm.mp3");
+        assert_eq!(automation.sound_for_chat("team").unwrap(), "ping.wav");
+    }
+
+    #[test]
+    fn test_sound_rules_pick_first_match() {
+        let mut automation =
+            NotificationAutomation::new("a".to_string(), "Work".to_string(), vec![]);
+        automation.notification_sound = Some("soft.wav".to_string());
+        automation
+            .chat_sound_overrides
+            .insert("boss".to_string(), "boss.wav".to_string());
+        let rule = |pattern: &str, sound: &str| SoundRule {
+            pattern: pattern.to_string(),
+            sound: sound.to_string(),
+        };
+        automation.sound_rules = vec![
+            rule("(?i)urgent|asap", "alarm.wav"),
+            rule("[unclosed", "never.wav"),
+            rule("(?i)urgent", "second.wav"),
+            rule("deploy", "deploy.wav"),
+        ];
+
+        let sound =
+            |chat_id: &str, text: &str| automation.sound_for_message(chat_id, text).cloned();
+        // Earlier rules win, and rules win over the chat's own sound
+        assert_eq!(sound("team", "URGENT: call me").unwrap(), "alarm.wav");
+        assert_eq!(sound("boss", "need this asap").unwrap(), "alarm.wav");
+        assert_eq!(sound("team", "deploy is done").unwrap(), "deploy.wav");
+        // No rule matches: per-chat sound, then the automation's sound
+        assert_eq!(sound("boss", "lunch?").unwrap(), "boss.wav");
+        assert_eq!(sound("team", "lunch?").unwrap(), "soft.wav");
+    }
+
+    #[test]
+    fn test_ntfy_endpoint() {
+        let mut ntfy = NtfyConfig {
+            url: "https://ntfy.example.com/".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(ntfy.endpoint(), "https://ntfy.example.com/");
+
+        ntfy.topic = Some("beeper".to_string());
+        assert_eq!(ntfy.endpoint(), "https://ntfy.example.com/beeper");
+
+        ntfy.topic = Some("  ".to_string());
+        assert_eq!(ntfy.endpoint(), "https://ntfy.example.com/");
+    }
 }
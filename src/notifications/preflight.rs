@@ -0,0 +1,170 @@
+// Preflight check of a config before it is deployed: what the service would
+// only find out at runtime, checked up front and reported per automation.
+// Builds on `Config::lint` with what needs the live chat list or the disk.
+
+use crate::app_state::SharedAppState;
+use crate::notifications::rate_limit::RateLimiter;
+use crate::notifications::{service, sounds};
+
+/// What the check found for one enabled automation
+#[derive(Debug, Clone)]
+pub struct AutomationReport {
+    pub id: String,
+    pub name: String,
+    pub problems: Vec<String>,
+    /// Worth a look, but not a failure
+    pub warnings: Vec<String>,
+}
+
+impl AutomationReport {
+    pub fn passed(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Check every enabled automation: its lint warnings, whether its chats are
+/// in the chat list, whether its chat_match finds any, and whether its sound
+/// files exist. The API connection should be validated first; failing to
+/// read the chat list is an error for the whole check. Only the first pages
+/// of a long chat list are read, so a chat missing from those is a warning.
+pub async fn check_automations(
+    app_state: &SharedAppState,
+) -> Result<Vec<AutomationReport>, String> {
+    let config = app_state.get_config().map_err(|e| e.to_string())?;
+    let limiter = RateLimiter::new(config.notifications.max_requests_per_minute);
+    let (listed, listed_all) = service::list_chat_ids(app_state, &limiter).await?;
+    let warnings = config.lint();
+
+    let mut reports = Vec::new();
+    for automation in config
+        .notifications
+        .automations
+        .iter()
+        .filter(|a| a.enabled)
+    {
+        let automation = automation.resolve_profile(&config.notification_profiles);
+        let mut problems: Vec<String> = warnings
+            .iter()
+            .filter(|warning| warning.automation_id == automation.id)
+            .map(|warning| warning.message.clone())
+            .collect();
+        let mut warnings = Vec::new();
+
+        for chat_id in automation
+            .chat_ids
+            .iter()
+            .filter(|id| !listed.contains(*id))
+        {
+            if listed_all {
+                problems.push(format!("chat {} is not in the chat list", chat_id));
+            } else {
+                warnings.push(format!(
+                    "chat {} is not among the {} most recent chats; check it's still there",
+                    chat_id,
+                    listed.len()
+                ));
+            }
+        }
+
+        if let Some(pattern) = automation.chat_match.as_deref() {
+            match service::resolve_chat_match(app_state, &limiter, pattern).await {
+                Ok(matched) if matched.is_empty() => {
+                    problems.push(format!("chat_match '{}' matches no chats", pattern));
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    problems.push(format!("could not resolve chat_match '{}': {}", pattern, e))
+                }
+            }
+        }
+
+        for sound in automation
+            .notification_sound
+            .iter()
+            .chain(automation.chat_sound_overrides.values())
+            .chain(automation.sound_rules.iter().map(|rule| &rule.sound))
+            .filter(|sound| !sound.is_empty() && !sounds::is_builtin(sound))
+        {
+            let path = service::resolve_sound_path(sound);
+            if !path.exists() {
+                problems.push(format!("sound file {:?} not found", path));
+            }
+        }
+
+        reports.push(AutomationReport {
+            id: automation.id.clone(),
+            name: automation.name.clone(),
+            problems,
+            warnings,
+        });
+    }
+
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beeper_api::mock::MockApi;
+    use crate::config::Config;
+    use crate::notifications::NotificationAutomation;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_reports_missing_chats_and_sounds() {
+        let api = Arc::new(MockApi::default());
+        api.add_chat("!work", "Work");
+
+        let mut config = Config::default();
+        let mut good = NotificationAutomation::new(
+            "good".to_string(),
+            "Good".to_string(),
+            vec!["!work".to_string()],
+        );
+        good.notification_sound = Some("builtin:chime".to_string());
+        let mut bad = NotificationAutomation::new(
+            "bad".to_string(),
+            "Bad".to_string(),
+            vec!["!gone".to_string()],
+        );
+        bad.chat_match = Some("Family*".to_string());
+        bad.notification_sound = Some("/no/such/sound.wav".to_string());
+        let mut disabled = bad.clone();
+        disabled.id = "disabled".to_string();
+        disabled.enabled = false;
+        config.notifications.automations = vec![good, bad, disabled];
+
+        let app_state = SharedAppState::with_api(config, api);
+        let reports = check_automations(&app_state).await.unwrap();
+
+        assert_eq!(reports.len(), 2, "disabled automations aren't checked");
+        assert!(reports[0].passed(), "{:?}", reports[0].problems);
+        assert_eq!(reports[1].problems.len(), 3, "{:?}", reports[1].problems);
+        assert!(reports[1].problems[0].contains("!gone"));
+        assert!(reports[1].problems[1].contains("matches no chats"));
+        assert!(reports[1].problems[2].contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_chat_past_the_page_cap_is_a_warning() {
+        let api = Arc::new(MockApi::default());
+        for i in 0..10 {
+            api.add_chat(&format!("!chat{}", i), "Chat");
+        }
+        *api.page_size.lock().unwrap() = Some(1);
+
+        let mut config = Config::default();
+        config.notifications.automations = vec![NotificationAutomation::new(
+            "old".to_string(),
+            "Old".to_string(),
+            vec!["!chat9".to_string()],
+        )];
+
+        let app_state = SharedAppState::with_api(config, api);
+        let reports = check_automations(&app_state).await.unwrap();
+
+        assert!(reports[0].passed(), "{:?}", reports[0].problems);
+        assert_eq!(reports[0].warnings.len(), 1);
+        assert!(reports[0].warnings[0].contains("!chat9"));
+    }
+}
@@ -0,0 +1,83 @@
+// Quiet requests: the configurator asks the running service to stop a loop
+// alerting for a chat until that chat's next message.
+//
+// The two run as separate processes that otherwise only share config.toml,
+// so requests go through `quiet.json` next to it: a map from chat id to when
+// the chat was quieted, in Unix milliseconds. The service checks the file's
+// modification time every couple of seconds and applies requests made since
+// it started.
+
+use crate::config::{Config, ConfigError};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Requests older than this are dropped whenever the file is written
+const KEEP_REQUESTS_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Chat id to the time it was quieted, in Unix milliseconds
+pub type QuietRequests = HashMap<String, u64>;
+
+/// Get quiet request file path
+pub fn quiet_file_path() -> Result<PathBuf, ConfigError> {
+    Ok(Config::config_dir()?.join("quiet.json"))
+}
+
+/// Current time in Unix milliseconds, the unit requests are stored in
+pub fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Quiet these chats as of now
+pub fn request(chat_ids: &[String]) -> anyhow::Result<()> {
+    request_at(&quiet_file_path()?, chat_ids, now_ms())?;
+    Ok(())
+}
+
+/// Every request in the file; a missing or unreadable file has none
+pub fn load_from(path: &Path) -> QuietRequests {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn request_at(path: &Path, chat_ids: &[String], now: u64) -> std::io::Result<()> {
+    let mut requests = load_from(path);
+    requests.retain(|_, at| now.saturating_sub(*at) < KEEP_REQUESTS_MS);
+    for chat_id in chat_ids {
+        requests.insert(chat_id.clone(), now);
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(&requests)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_round_trip() {
+        let dir = std::env::temp_dir().join(format!("beeper-quiet-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("quiet.json");
+        assert!(load_from(&path).is_empty());
+
+        let day = KEEP_REQUESTS_MS;
+        request_at(&path, &["old".to_string()], 1_000).unwrap();
+        request_at(&path, &["a".to_string(), "b".to_string()], 1_000 + day / 2).unwrap();
+        request_at(&path, &["a".to_string()], 1_000 + day).unwrap();
+
+        // The day-old request is dropped, "a" keeps its latest time
+        let requests = load_from(&path);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests["a"], 1_000 + day);
+        assert_eq!(requests["b"], 1_000 + day / 2);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
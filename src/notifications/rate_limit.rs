@@ -0,0 +1,111 @@
+// Token-bucket limiter for the service's overall Beeper API request budget
+
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+#[derive(Debug)]
+struct Bucket {
+    max_per_minute: Option<u32>,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(max_per_minute: Option<u32>, now: Instant) -> Self {
+        Self {
+            max_per_minute,
+            tokens: max_per_minute.unwrap_or(0) as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Take one token, or return how long to wait until one is available
+    fn take(&mut self, now: Instant) -> Option<Duration> {
+        let capacity = match self.max_per_minute {
+            Some(max) if max > 0 => max as f64,
+            _ => return None, // Unlimited
+        };
+        let refill_per_sec = capacity / 60.0;
+
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * refill_per_sec).min(capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / refill_per_sec))
+        }
+    }
+}
+
+/// Caps the number of API requests per minute across all automations
+#[derive(Debug)]
+pub struct RateLimiter {
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    /// Create a limiter; `None` or `Some(0)` means unlimited
+    pub fn new(max_per_minute: Option<u32>) -> Self {
+        Self {
+            bucket: Mutex::new(Bucket::new(max_per_minute, Instant::now())),
+        }
+    }
+
+    /// Change the budget, e.g. after a config reload
+    pub async fn set_limit(&self, max_per_minute: Option<u32>) {
+        let mut bucket = self.bucket.lock().await;
+        if bucket.max_per_minute != max_per_minute {
+            *bucket = Bucket::new(max_per_minute, Instant::now());
+        }
+    }
+
+    /// Wait until a request is allowed under the budget
+    pub async fn acquire(&self) {
+        loop {
+            let wait = self.bucket.lock().await.take(Instant::now());
+
+            match wait {
+                None => return,
+                Some(wait) => {
+                    tracing::info!(
+                        "API request budget exhausted, throttling for {} ms",
+                        wait.as_millis()
+                    );
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlimited_never_waits() {
+        let now = Instant::now();
+        let mut bucket = Bucket::new(None, now);
+        for _ in 0..1000 {
+            assert!(bucket.take(now).is_none());
+        }
+    }
+
+    #[test]
+    fn test_budget_exhausts_and_refills() {
+        let now = Instant::now();
+        let mut bucket = Bucket::new(Some(60), now);
+
+        for _ in 0..60 {
+            assert!(bucket.take(now).is_none());
+        }
+        let wait = bucket.take(now).expect("budget should be exhausted");
+        assert!(wait <= Duration::from_secs(1));
+
+        // 60 per minute refills one token per second
+        assert!(bucket.take(now + Duration::from_secs(1)).is_none());
+    }
+}
@@ -0,0 +1,232 @@
+// Per-automation active days and hours
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+const DAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+const ALL_DAYS: u8 = 0b111_1111;
+
+/// When an automation may alert, stored as a compact string such as
+/// `"mon-fri 09:00-17:30"` or `"daily 22:00-06:00"`.
+///
+/// A window that ends before it starts runs overnight and belongs to the day
+/// it starts on; equal start and end times cover the whole day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Schedule {
+    /// Active weekdays, bit 0 is Monday and bit 6 is Sunday
+    pub days: u8,
+    /// Window start, in minutes after midnight
+    pub start: u16,
+    /// Window end, in minutes after midnight
+    pub end: u16,
+}
+
+impl Schedule {
+    /// Build a schedule from its days part (`mon-fri`, `sat,sun`, `daily`)
+    /// and hours part (`09:00-17:00`)
+    pub fn parse(days: &str, hours: &str) -> Result<Self, String> {
+        let days = parse_days(days)?;
+        let (start, end) = hours
+            .trim()
+            .split_once('-')
+            .ok_or_else(|| format!("Expected hours like 09:00-17:00, got '{}'", hours.trim()))?;
+
+        Ok(Self {
+            days,
+            start: parse_clock(start)?,
+            end: parse_clock(end)?,
+        })
+    }
+
+    /// Whether alerts are allowed at the given local time
+    pub fn is_active_at(&self, at: NaiveDateTime) -> bool {
+        let minute = (at.hour() * 60 + at.minute()) as u16;
+        let today = at.weekday().num_days_from_monday();
+        let yesterday = (today + 6) % 7;
+
+        if self.start == self.end {
+            self.has_day(today)
+        } else if self.start < self.end {
+            self.has_day(today) && (self.start..self.end).contains(&minute)
+        } else {
+            (self.has_day(today) && minute >= self.start)
+                || (self.has_day(yesterday) && minute < self.end)
+        }
+    }
+
+    /// Whether alerts are allowed right now
+    pub fn is_active_now(&self) -> bool {
+        self.is_active_at(chrono::Local::now().naive_local())
+    }
+
+    fn has_day(&self, day: u32) -> bool {
+        self.days & (1 << day) != 0
+    }
+
+    /// The days part, with consecutive days collapsed into ranges
+    pub fn days_str(&self) -> String {
+        if self.days == ALL_DAYS {
+            return "daily".to_string();
+        }
+
+        let mut parts = Vec::new();
+        let mut day = 0;
+        while day < 7 {
+            if !self.has_day(day) {
+                day += 1;
+                continue;
+            }
+            let first = day;
+            while day + 1 < 7 && self.has_day(day + 1) {
+                day += 1;
+            }
+            parts.push(match day - first {
+                0 => DAY_NAMES[first as usize].to_string(),
+                1 => format!("{},{}", DAY_NAMES[first as usize], DAY_NAMES[day as usize]),
+                _ => format!("{}-{}", DAY_NAMES[first as usize], DAY_NAMES[day as usize]),
+            });
+            day += 1;
+        }
+        parts.join(",")
+    }
+
+    /// The hours part, e.g. `09:00-17:00`
+    pub fn hours_str(&self) -> String {
+        format!("{}-{}", format_clock(self.start), format_clock(self.end))
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.days_str(), self.hours_str())
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (days, hours) = s
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| format!("Expected a schedule like 'mon-fri 09:00-17:00', got '{}'", s))?;
+        Self::parse(days, hours)
+    }
+}
+
+impl TryFrom<String> for Schedule {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Schedule> for String {
+    fn from(schedule: Schedule) -> Self {
+        schedule.to_string()
+    }
+}
+
+fn parse_day(name: &str) -> Result<u32, String> {
+    let name = name.trim().to_lowercase();
+    DAY_NAMES
+        .iter()
+        .position(|day| name.starts_with(day))
+        .map(|i| i as u32)
+        .ok_or_else(|| format!("Unknown day '{}'", name))
+}
+
+fn parse_days(days: &str) -> Result<u8, String> {
+    let days = days.trim();
+    if days.eq_ignore_ascii_case("daily") {
+        return Ok(ALL_DAYS);
+    }
+
+    let mut flags = 0u8;
+    for part in days.split(',') {
+        match part.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (parse_day(first)?, parse_day(last)?);
+                // Ranges may wrap around the week, e.g. fri-mon
+                let mut day = first;
+                loop {
+                    flags |= 1 << day;
+                    if day == last {
+                        break;
+                    }
+                    day = (day + 1) % 7;
+                }
+            }
+            None => flags |= 1 << parse_day(part)?,
+        }
+    }
+    Ok(flags)
+}
+
+fn parse_clock(time: &str) -> Result<u16, String> {
+    let time = time.trim();
+    let invalid = || format!("Expected a time like 09:30, got '{}'", time);
+    let (hours, minutes) = time.split_once(':').ok_or_else(invalid)?;
+    let hours: u16 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u16 = minutes.parse().map_err(|_| invalid())?;
+    // 24:00 is allowed as the end of the day
+    if hours > 24 || minutes > 59 || (hours == 24 && minutes > 0) {
+        return Err(invalid());
+    }
+    Ok(hours * 60 + minutes)
+}
+
+fn format_clock(minutes: u16) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    // 2025-06-02 is a Monday
+    const MONDAY: &str = "2025-06-02";
+    const FRIDAY: &str = "2025-06-06";
+    const SATURDAY: &str = "2025-06-07";
+
+    #[test]
+    fn test_work_hours_schedule() {
+        let schedule: Schedule = "mon-fri 09:00-17:00".parse().unwrap();
+
+        assert!(schedule.is_active_at(at(MONDAY, "09:00")));
+        assert!(schedule.is_active_at(at(FRIDAY, "16:59")));
+        assert!(!schedule.is_active_at(at(FRIDAY, "17:00")));
+        assert!(!schedule.is_active_at(at(MONDAY, "08:59")));
+        assert!(!schedule.is_active_at(at(SATURDAY, "12:00")));
+    }
+
+    #[test]
+    fn test_overnight_schedule_belongs_to_start_day() {
+        let schedule: Schedule = "fri 22:00-06:00".parse().unwrap();
+
+        assert!(schedule.is_active_at(at(FRIDAY, "23:30")));
+        assert!(schedule.is_active_at(at(SATURDAY, "05:59")));
+        assert!(!schedule.is_active_at(at(SATURDAY, "23:30")));
+        assert!(!schedule.is_active_at(at(FRIDAY, "05:00")));
+    }
+
+    #[test]
+    fn test_schedule_round_trip() {
+        let schedule: Schedule = "sat,sun,mon-wed 08:30-12:00".parse().unwrap();
+        assert_eq!(schedule.to_string(), "mon-wed,sat,sun 08:30-12:00");
+        assert_eq!(schedule.to_string().parse::<Schedule>().unwrap(), schedule);
+
+        assert_eq!("daily 00:00-24:00".parse::<Schedule>().unwrap().days, ALL_DAYS);
+        assert!("mon-fri".parse::<Schedule>().is_err());
+        assert!("someday 09:00-17:00".parse::<Schedule>().is_err());
+        assert!("mon 25:00-26:00".parse::<Schedule>().is_err());
+    }
+}
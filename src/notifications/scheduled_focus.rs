@@ -0,0 +1,142 @@
+// Scheduled focus: bring Beeper up on a chat at set times, e.g. the standup
+// chat a few minutes before standup, whether or not anything new arrived.
+
+use crate::app_state::SharedAppState;
+use crate::notifications::actions;
+use crate::notifications::cron::CronSchedule;
+use chrono::{NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often the schedules are checked; well under a minute so no minute is missed
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Open a chat at the times a cron expression matches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledFocus {
+    pub id: String,
+    pub name: String,
+    pub chat_id: String,
+    /// When to open the chat, e.g. `"55 9 * * mon-fri"` for 9:55 on weekdays
+    pub cron: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ScheduledFocus {
+    pub fn new(name: String, chat_id: String, cron: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            chat_id,
+            cron,
+            enabled: true,
+        }
+    }
+}
+
+/// The enabled entries due in the minute of `now` that haven't fired in it
+/// yet, recording them in `fired` so each fires once per matching minute
+fn take_due<'a>(
+    entries: &'a [ScheduledFocus],
+    now: NaiveDateTime,
+    fired: &mut HashMap<String, NaiveDateTime>,
+) -> Vec<&'a ScheduledFocus> {
+    let minute = now
+        .with_second(0)
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or(now);
+
+    entries
+        .iter()
+        .filter(|entry| entry.enabled)
+        .filter(|entry| {
+            entry
+                .cron
+                .parse::<CronSchedule>()
+                .is_ok_and(|cron| cron.is_active_at(minute))
+        })
+        .filter(|entry| fired.insert(entry.id.clone(), minute) != Some(minute))
+        .collect()
+}
+
+/// Open chats as their schedules come due, until the task is aborted.
+/// Reads the entries from the live config, so reloads apply on their own.
+pub(crate) async fn run(app_state: SharedAppState) {
+    let mut fired = HashMap::new();
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let Ok((entries, paused, dry_run)) = app_state.with_config(|config| {
+            (
+                config.notifications.scheduled_focus.clone(),
+                config.notifications.paused,
+                config.notifications.dry_run,
+            )
+        }) else {
+            continue;
+        };
+
+        let now = chrono::Local::now().naive_local();
+        for entry in take_due(&entries, now, &mut fired) {
+            if paused {
+                tracing::info!("Skipping scheduled focus '{}' while paused", entry.name);
+                continue;
+            }
+            if dry_run {
+                println!(
+                    "[dry-run] 🎯 Would open chat {} for '{}'",
+                    entry.chat_id, entry.name
+                );
+                continue;
+            }
+
+            println!("🎯 Opening chat {} for '{}'", entry.chat_id, entry.name);
+            if let Err(e) = actions::focus_chat(&app_state, &entry.chat_id, None).await {
+                eprintln!("✗ Scheduled focus '{}' failed: {}", entry.name, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_once_per_matching_minute() {
+        let standup = ScheduledFocus::new(
+            "Standup".to_string(),
+            "!standup".to_string(),
+            "55 9 * * mon-fri".to_string(),
+        );
+        let mut disabled = ScheduledFocus::new(
+            "Off".to_string(),
+            "!off".to_string(),
+            "* * * * *".to_string(),
+        );
+        disabled.enabled = false;
+        let entries = vec![standup, disabled];
+        let mut fired = HashMap::new();
+
+        // 2025-06-02 is a Monday
+        let at = |time: &str| {
+            NaiveDateTime::parse_from_str(&format!("2025-06-02 {}", time), "%Y-%m-%d %H:%M:%S")
+                .unwrap()
+        };
+
+        assert!(take_due(&entries, at("09:54:50"), &mut fired).is_empty());
+        let due = take_due(&entries, at("09:55:05"), &mut fired);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].name, "Standup");
+        // Later checks in the same minute don't fire again
+        assert!(take_due(&entries, at("09:55:40"), &mut fired).is_empty());
+        assert!(take_due(&entries, at("09:56:00"), &mut fired).is_empty());
+    }
+}
@@ -1,86 +1,147 @@
 // Service logic for notification automations will be implemented here
 
-use crate::app_state::SharedAppState;
+use crate::app_state::{AppStateError, SharedAppState};
 use crate::config::Config;
+use crate::notifications::actions;
+use crate::notifications::control;
+use crate::notifications::cron::CronSchedule;
+use crate::notifications::desktop::{self, DesktopNotification};
+use crate::notifications::focus_gate::{self, FOCUS_GATE};
+use crate::notifications::health::{API_HEALTH, HealthChange};
+use crate::notifications::history::{self, HistoryEntry};
+use crate::notifications::identity::SELF_IDENTITY;
+use crate::notifications::metrics::{self, METRICS};
 use crate::notifications::models::{AutomationType, NotificationAutomation};
-use std::collections::HashMap;
-use std::path::Path;
+use crate::notifications::quiet::{self, QuietRequests};
+use crate::notifications::rate_limit::RateLimiter;
+use crate::notifications::scheduled_focus;
+use crate::notifications::sounds;
+use crate::notifications::state::{AutomationState, ChatProgress, RuntimeState};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use user_idle2::UserIdle;
 
-/// Play a sound file (supports .wav and .mp3)
-fn play_sound(sound_path: &str) {
+/// Whether an audio output device was found. Checked once, since opening a
+/// stream fails the same way every time on a headless machine.
+fn audio_available() -> bool {
+    static AUDIO_AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+
+    *AUDIO_AVAILABLE.get_or_init(|| match rodio::OutputStream::try_default() {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!(
+                "⚠ No audio output device ({}). Sounds are off; automations with ntfy or \
+                 desktop notifications set up will use those instead.",
+                e
+            );
+            tracing::warn!("No audio output device, sounds disabled: {}", e);
+            false
+        }
+    })
+}
+
+/// Play a sound file (supports .wav and .mp3), or a bundled sound named like
+/// `builtin:chime`. Returns false when it can't be played: no audio device,
+/// or the file or bundled sound doesn't exist.
+fn play_sound(sound_path: &str) -> bool {
+    if !audio_available() {
+        tracing::debug!("No audio device, not playing {}", sound_path);
+        return false;
+    }
+
     tracing::info!("Playing sound: {}", sound_path);
-    use rodio::{Decoder, OutputStream, Sink};
     use std::fs::File;
-    use std::io::BufReader;
-
-    let path = Path::new(sound_path);
+    use std::io::{BufReader, Cursor};
 
-    // If relative path, try to resolve from common locations
-    let resolved_path = if path.is_absolute() {
-        path.to_path_buf()
-    } else {
-        // Try current directory first
-        if Path::new(sound_path).exists() {
-            Path::new(sound_path).to_path_buf()
-        } else {
-            // Try in AppData\Local/BeeperAutomations/sounds (Windows) or XDG state (Linux)
-            #[cfg(windows)]
-            let sounds_dir = {
-                let app_data = std::env::var("LOCALAPPDATA")
-                    .unwrap_or_else(|_| {
-                        let mut path = std::env::var("USERPROFILE")
-                            .unwrap_or_else(|_| ".".to_string());
-                        path.push_str("\\AppData\\Local");
-                        path
-                    });
-                Path::new(&app_data).join("BeeperAutomations").join("sounds")
-            };
-            
-            #[cfg(not(windows))]
-            let sounds_dir = {
-                let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-                Path::new(&home).join(".local/state/beeper-automations/sounds")
-            };
-            
-            sounds_dir.join(sound_path)
-        }
-    };
+    if sounds::is_builtin(sound_path) {
+        let Some(data) = sounds::builtin(sound_path) else {
+            eprintln!("Unknown built-in sound: {}", sound_path);
+            return false;
+        };
+        std::thread::spawn(move || play_source(Cursor::new(data)));
+        return true;
+    }
 
+    let resolved_path = resolve_sound_path(sound_path);
     if !resolved_path.exists() {
         eprintln!("Sound file not found: {:?}", resolved_path);
-        return;
+        return false;
     }
 
     // Spawn a thread to play sound asynchronously
-    let resolved_path = resolved_path.clone();
-    std::thread::spawn(move || {
-        match File::open(&resolved_path) {
-            Ok(file) => {
-                let buf_reader = BufReader::new(file);
-                match Decoder::new(buf_reader) {
-                    Ok(source) => {
-                        // Create output stream and sink
-                        match OutputStream::try_default() {
-                            Ok((_stream, stream_handle)) => match Sink::try_new(&stream_handle) {
-                                Ok(sink) => {
-                                    sink.append(source);
-                                    sink.sleep_until_end();
-                                }
-                                Err(e) => eprintln!("Failed to create audio sink: {}", e),
-                            },
-                            Err(e) => eprintln!("Failed to create audio output stream: {}", e),
-                        }
+    std::thread::spawn(move || match File::open(&resolved_path) {
+        Ok(file) => play_source(BufReader::new(file)),
+        Err(e) => eprintln!("Failed to open sound file {:?}: {}", resolved_path, e),
+    });
+    true
+}
+
+/// Where a sound file setting points. A relative path is looked up in the
+/// current directory first, then in the sounds folder of the data directory.
+pub(crate) fn resolve_sound_path(sound_path: &str) -> PathBuf {
+    let path = Path::new(sound_path);
+    if path.is_absolute() || path.exists() {
+        path.to_path_buf()
+    } else {
+        crate::logging::data_dir().join("sounds").join(sound_path)
+    }
+}
+
+/// Decode and play a sound to the end, blocking the calling thread
+fn play_source<R>(reader: R)
+where
+    R: std::io::Read + std::io::Seek + Send + Sync + 'static,
+{
+    use rodio::{Decoder, OutputStream, Sink};
+
+    match Decoder::new(reader) {
+        Ok(source) => {
+            // Create output stream and sink
+            match OutputStream::try_default() {
+                Ok((_stream, stream_handle)) => match Sink::try_new(&stream_handle) {
+                    Ok(sink) => {
+                        sink.append(source);
+                        sink.sleep_until_end();
                     }
-                    Err(e) => eprintln!("Failed to decode sound file: {}", e),
-                }
+                    Err(e) => eprintln!("Failed to create audio sink: {}", e),
+                },
+                Err(e) => eprintln!("Failed to create audio output stream: {}", e),
             }
-            Err(e) => eprintln!("Failed to open sound file {:?}: {}", resolved_path, e),
         }
-    });
+        Err(e) => eprintln!("Failed to decode sound file: {}", e),
+    }
+}
+
+/// The ntfy settings to alert with. When the sound couldn't play, ntfy that
+/// is set up but switched off is used as a fallback if it opted in with
+/// `fallback_when_no_audio`.
+fn ntfy_for_alert(
+    ntfy_config: Option<&crate::notifications::models::NtfyConfig>,
+    sound_failed: bool,
+) -> Option<crate::notifications::models::NtfyConfig> {
+    let mut ntfy_config = ntfy_config?.clone();
+    if sound_failed
+        && !ntfy_config.enabled
+        && ntfy_config.fallback_when_no_audio
+        && !ntfy_config.url.is_empty()
+    {
+        ntfy_config.enabled = true;
+    }
+    Some(ntfy_config)
+}
+
+/// Whether to show a desktop notification. When the sound couldn't play it
+/// stands in for it under the same `fallback_when_no_audio` opt-in as ntfy.
+fn desktop_for_alert(automation: &NotificationAutomation, sound_failed: bool) -> bool {
+    automation.desktop_notification
+        || (sound_failed
+            && automation
+                .ntfy_config
+                .as_ref()
+                .is_some_and(|ntfy| ntfy.fallback_when_no_audio))
 }
 
 /// Check if the user is currently active (not idle)
@@ -106,35 +167,203 @@ fn is_user_active() -> bool {
     }
 }
 
-/// Send a notification to ntfy.sh or compatible server
+/// Check whether all automations are globally paused
+fn is_paused(app_state: &SharedAppState) -> bool {
+    app_state
+        .with_config(|config| config.notifications.paused)
+        .unwrap_or(false)
+}
+
+/// Check whether the automation is snoozed, going by the running config so a
+/// snooze sent over the control port applies without restarting the watcher
+fn is_snoozed(app_state: &SharedAppState, automation: &NotificationAutomation) -> bool {
+    app_state
+        .with_config(|config| {
+            config
+                .notifications
+                .automations
+                .iter()
+                .find(|a| a.id == automation.id)
+                .map(|a| a.is_snoozed())
+        })
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| automation.is_snoozed())
+}
+
+/// Check whether the automation's schedule, if any, allows alerting right
+/// now. A valid cron expression wins over the days and hours.
+fn is_in_schedule(automation: &NotificationAutomation) -> bool {
+    if let Some(Ok(cron)) = automation.cron.as_deref().map(str::parse::<CronSchedule>) {
+        return cron.is_active_now();
+    }
+    automation
+        .schedule
+        .is_none_or(|schedule| schedule.is_active_now())
+}
+
+/// What triggered an automation, built once per trigger and shared by every action
+#[derive(Debug, Clone)]
+struct TriggerContext {
+    chat_id: String,
+    chat_name: String,
+    sender: String,
+    message_text: String,
+    unread_count: Option<u32>,
+}
+
+impl TriggerContext {
+    fn new(chat_id: &str, chat_name: Option<&str>, message: &PolledMessage, unread_count: Option<u32>) -> Self {
+        Self {
+            chat_id: chat_id.to_string(),
+            chat_name: chat_name.unwrap_or(chat_id).to_string(),
+            sender: message.sender_name.clone().unwrap_or_else(|| "Unknown".to_string()),
+            message_text: message.text.clone().unwrap_or_default(),
+            unread_count,
+        }
+    }
+
+    /// Desktop notification for this trigger, opening the given message. The
+    /// automation's `notification_template` replaces the default body.
+    fn desktop_notification(
+        &self,
+        automation: &NotificationAutomation,
+        message_id: Option<&str>,
+    ) -> DesktopNotification {
+        let body = match notification_template(automation) {
+            Some(template) => self.render(template, &automation.name),
+            None if self.message_text.is_empty() => format!("New message from {}", self.sender),
+            None => format!("{}: {}", self.sender, self.message_text),
+        };
+
+        DesktopNotification {
+            title: self.chat_name.clone(),
+            body,
+            chat_id: self.chat_id.clone(),
+            message_id: message_id.map(String::from),
+        }
+    }
+
+    /// Substitute `{placeholder}` variables in a user template
+    fn render(&self, template: &str, automation_name: &str) -> String {
+        let unread_count = self
+            .unread_count
+            .map(|count| count.to_string())
+            .unwrap_or_default();
+
+        render_template(
+            template,
+            &[
+                ("sender", &self.sender),
+                ("chat_name", &self.chat_name),
+                ("chat_id", &self.chat_id),
+                ("message", &self.message_text),
+                ("unread", &unread_count),
+                ("unread_count", &unread_count),
+                ("automation_name", automation_name),
+            ],
+        )
+    }
+}
+
+/// The automation's own notification text, if it sets a non-empty one
+fn notification_template(automation: &NotificationAutomation) -> Option<&str> {
+    automation
+        .notification_template
+        .as_deref()
+        .filter(|template| !template.trim().is_empty())
+}
+
+/// Replace each `{name}` in `template` with its value from `vars`, in a
+/// single pass so braces inside a value are never expanded. Unknown names
+/// and unmatched braces are left as written.
+fn render_template(template: &str, vars: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        rendered.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let known = after.find('}').and_then(|close| {
+            vars.iter()
+                .find(|(name, _)| *name == &after[..close])
+                .map(|(_, value)| (close, value))
+        });
+        match known {
+            Some((close, value)) => {
+                rendered.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Send a notification to ntfy.sh or compatible server. Returns false when
+/// ntfy isn't enabled for the automation.
 fn send_ntfy_notification(
     ntfy_config: &crate::notifications::models::NtfyConfig,
-    automation_name: &str,
-    sender: &str,
-    chat_name: &str,
-) {
+    automation: &NotificationAutomation,
+    context: &TriggerContext,
+) -> bool {
     if !ntfy_config.enabled || ntfy_config.url.is_empty() {
-        return;
+        return false;
     }
+    let automation_name = automation.name.as_str();
+
+    // The automation's template takes precedence over the ntfy message
+    let template = notification_template(automation).unwrap_or(&ntfy_config.message);
+    let message = context.render(template, automation_name);
+
+    let title = ntfy_config
+        .title
+        .as_deref()
+        .filter(|t| !t.is_empty())
+        .map(|title| context.render(title, automation_name));
+    post_ntfy(ntfy_config, title, message);
+    true
+}
 
-    // Replace message variables
-    let message = ntfy_config.message
-        .replace("{sender}", sender)
-        .replace("{chat_name}", chat_name)
-        .replace("{automation_name}", automation_name);
-
-    let url = ntfy_config.url.clone();
+/// Send a message to the ntfy topic from a background thread
+fn post_ntfy(
+    ntfy_config: &crate::notifications::models::NtfyConfig,
+    title: Option<String>,
+    message: String,
+) {
+    let url = ntfy_config.endpoint();
     let priority = ntfy_config.priority;
     tracing::info!("Sending ntfy notification to {}: {} (priority: {})", url, message, priority);
 
-    // Spawn a thread to send HTTP request asynchronously
+    let token = ntfy_config
+        .token
+        .as_ref()
+        .map(|t| t.reveal().to_string())
+        .filter(|t| !t.is_empty());
+    let tags = ntfy_config.tags.join(",");
+
+    // Spawn a thread to send HTTP request asynchronously. The blocking client
+    // is built in there too: creating or dropping it on an async task panics.
     std::thread::spawn(move || {
-        match reqwest::blocking::Client::new()
+        let mut request = reqwest::blocking::Client::new()
             .post(&url)
-            .header("X-Priority", priority.to_string())
-            .body(message.clone())
-            .send()
-        {
+            .header("X-Priority", priority.to_string());
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(title) = title {
+            request = request.header("Title", title);
+        }
+        if !tags.is_empty() {
+            request = request.header("Tags", tags);
+        }
+
+        match request.body(message).send() {
             Ok(response) => {
                 if response.status().is_success() {
                     tracing::info!("Successfully sent ntfy notification");
@@ -150,6 +379,755 @@ fn send_ntfy_notification(
     });
 }
 
+/// How long a webhook request may take before it is given up on
+const WEBHOOK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// JSON body sent to an automation's webhook
+#[derive(Debug, serde::Serialize)]
+struct WebhookPayload<'a> {
+    automation: &'a str,
+    chat_id: &'a str,
+    chat_name: &'a str,
+    sender: &'a str,
+    message: &'a str,
+    unread: Option<u32>,
+    /// When the automation fired, in RFC 3339
+    timestamp: String,
+}
+
+impl<'a> WebhookPayload<'a> {
+    fn new(
+        automation: &'a NotificationAutomation,
+        context: &'a TriggerContext,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        Self {
+            automation: &automation.name,
+            chat_id: &context.chat_id,
+            chat_name: &context.chat_name,
+            sender: &context.sender,
+            message: &context.message_text,
+            unread: context.unread_count,
+            timestamp: timestamp.to_rfc3339(),
+        }
+    }
+}
+
+/// Send a trigger to the automation's webhook from a background thread.
+/// Returns false when the webhook has no URL or can't be sent.
+fn send_webhook(
+    webhook: &crate::notifications::models::WebhookConfig,
+    automation: &NotificationAutomation,
+    context: &TriggerContext,
+) -> bool {
+    if webhook.url.trim().is_empty() {
+        return false;
+    }
+    let Some(method) = webhook.http_method() else {
+        tracing::error!(
+            "Webhook for '{}' has an invalid method '{}'",
+            automation.name,
+            webhook.method
+        );
+        return false;
+    };
+    let payload = WebhookPayload::new(automation, context, chrono::Utc::now());
+    let body = match serde_json::to_string(&payload) {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!(
+                "Failed to build webhook payload for '{}': {}",
+                automation.name,
+                e
+            );
+            return false;
+        }
+    };
+    tracing::info!(
+        "Sending webhook for '{}' to {} {}",
+        automation.name,
+        method,
+        webhook.url
+    );
+
+    let webhook = webhook.clone();
+    std::thread::spawn(move || {
+        let client = match reqwest::blocking::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Failed to send webhook: {}", e);
+                return;
+            }
+        };
+        let mut request = client
+            .request(method, &webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json");
+        for (name, value) in &webhook.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+
+        match request.body(body).send() {
+            Ok(response) => {
+                if response.status().is_success() {
+                    tracing::info!("Successfully sent webhook to {}", webhook.url);
+                } else {
+                    tracing::error!(
+                        "Webhook to {} failed: HTTP {}",
+                        webhook.url,
+                        response.status()
+                    );
+                    eprintln!(
+                        "Webhook to {} failed: HTTP {}",
+                        webhook.url,
+                        response.status()
+                    );
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to send webhook to {}: {}", webhook.url, e);
+                eprintln!("Failed to send webhook to {}: {}", webhook.url, e);
+            }
+        }
+    });
+    true
+}
+
+/// Append a trigger to the notification history shown in the configurator,
+/// if the service keeps one
+fn record_history(
+    history_file: Option<&Path>,
+    automation: &NotificationAutomation,
+    context: &TriggerContext,
+    actions: Vec<&str>,
+    dry_run: bool,
+) {
+    let Some(path) = history_file else {
+        return;
+    };
+    history::record_to(path, HistoryEntry {
+        timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        automation_id: automation.id.clone(),
+        automation: automation.name.clone(),
+        chat_id: context.chat_id.clone(),
+        chat: context.chat_name.clone(),
+        sender: context.sender.clone(),
+        actions: actions.into_iter().map(String::from).collect(),
+        dry_run,
+    });
+}
+
+/// What firing an automation's actions needs besides the automation itself
+struct ActionContext<'a> {
+    app_state: &'a SharedAppState,
+    limiter: &'a RateLimiter,
+    stats: &'a SharedStats,
+    trigger: &'a TriggerContext,
+    /// The message that set the automation off. A test alert has none, so it
+    /// focuses the chat rather than a message and marks nothing read.
+    message_id: Option<&'a str>,
+    /// Who is firing, for log lines, e.g. "Loop automation"
+    label: &'a str,
+    dry_run: bool,
+}
+
+/// Fire an automation's configured actions for one trigger: focus, sound,
+/// ntfy, desktop notification and finally mark read. Returns the actions
+/// taken, for the history.
+async fn fire_actions(
+    automation: &NotificationAutomation,
+    ctx: &ActionContext<'_>,
+) -> Vec<&'static str> {
+    let ActionContext {
+        app_state,
+        limiter,
+        stats,
+        trigger,
+        message_id,
+        label,
+        dry_run,
+    } = *ctx;
+    let chat_id = &trigger.chat_id;
+    let mut actions_taken = Vec::new();
+
+    // Trigger focus action (only if user is active)
+    if automation.focus_chat && dry_run {
+        println!(
+            "[dry-run] {} '{}': would focus chat {}",
+            label, automation.name, chat_id
+        );
+        actions_taken.push("focus");
+    } else if automation.focus_chat {
+        if is_user_active() {
+            tracing::info!(
+                "User is active, proceeding with focus chat action for automation '{}'",
+                automation.name
+            );
+            if FOCUS_GATE.wait_turn(focus_gate::cooldown(app_state)).await {
+                limiter.acquire().await;
+                let focus_input = beeper_desktop_api::FocusAppInput {
+                    // Without a chat, e.g. testing a chat_match automation, just bring Beeper up
+                    chat_id: (!chat_id.is_empty()).then(|| chat_id.clone()),
+                    message_id: message_id
+                        .filter(|_| automation.focus_message)
+                        .map(String::from),
+                    draft: None,
+                };
+                let result = app_state
+                    .with_client_async(
+                        |client| async move { client.focus_app(Some(focus_input)).await },
+                    )
+                    .await;
+
+                match result {
+                    Ok(Ok(response)) => {
+                        if response.success {
+                            tracing::info!(
+                                "Successfully focused chat {} for automation '{}'",
+                                chat_id,
+                                automation.name
+                            );
+                            record_stat(stats, automation, |s| s.focus_actions += 1).await;
+                            actions_taken.push("focus");
+                        }
+                    }
+                    Ok(Err(e)) => {
+                        record_stat(stats, automation, |s| s.errors += 1).await;
+                        tracing::error!("Error focusing chat {}: {}", chat_id, e);
+                        eprintln!("Error focusing chat {}: {}", chat_id, e);
+                    }
+                    Err(e) => {
+                        record_stat(stats, automation, |s| s.errors += 1).await;
+                        tracing::error!("Error accessing client for focus: {}", e);
+                        eprintln!("Error accessing client for focus: {}", e);
+                    }
+                }
+            } else {
+                tracing::info!(
+                    "Dropping focus for automation '{}': a newer focus came in during the cooldown",
+                    automation.name
+                );
+            }
+        } else {
+            tracing::info!(
+                "User is idle, skipping focus chat action for automation '{}'",
+                automation.name
+            );
+        }
+    }
+
+    // Trigger notification sound if configured
+    let mut sound_failed = false;
+    if let Some(sound_path) = automation.sound_for_message(chat_id, &trigger.message_text) {
+        if !sound_path.is_empty() && dry_run {
+            println!(
+                "[dry-run] {} '{}': would play sound {}",
+                label, automation.name, sound_path
+            );
+            actions_taken.push("sound");
+        } else if !sound_path.is_empty() {
+            println!(
+                "▶ Playing notification sound for '{}': {}",
+                automation.name, sound_path
+            );
+            if play_sound(sound_path) {
+                record_stat(stats, automation, |s| s.sounds_played += 1).await;
+                actions_taken.push("sound");
+            } else {
+                sound_failed = true;
+            }
+        }
+    }
+
+    // Trigger ntfy notification if configured, or in place of a sound that couldn't play
+    if let Some(ntfy_config) = &ntfy_for_alert(automation.ntfy_config.as_ref(), sound_failed) {
+        if dry_run {
+            if ntfy_config.enabled {
+                println!(
+                    "[dry-run] {} '{}': would send ntfy to {}",
+                    label, automation.name, ntfy_config.url
+                );
+                actions_taken.push("ntfy");
+            }
+        } else if send_ntfy_notification(ntfy_config, automation, trigger) {
+            actions_taken.push("ntfy");
+        }
+    }
+
+    // Send to the webhook if configured, whether or not ntfy fired
+    if let Some(webhook) = &automation.webhook {
+        if dry_run {
+            println!(
+                "[dry-run] {} '{}': would send webhook to {}",
+                label, automation.name, webhook.url
+            );
+            actions_taken.push("webhook");
+        } else if send_webhook(webhook, automation, trigger) {
+            actions_taken.push("webhook");
+        }
+    }
+
+    // Show a desktop notification if configured, where the platform has them;
+    // elsewhere `desktop::show` only logs, so it isn't reported as taken
+    if desktop::SUPPORTED && automation.desktop_notification && dry_run {
+        println!(
+            "[dry-run] {} '{}': would show a desktop notification for chat {}",
+            label, automation.name, chat_id
+        );
+        actions_taken.push("desktop");
+    } else if desktop::SUPPORTED && desktop_for_alert(automation, sound_failed) {
+        desktop::show(
+            app_state,
+            trigger.desktop_notification(automation, message_id),
+        );
+        actions_taken.push("desktop");
+    }
+
+    // Mark the chat read last, after every other action has fired; for
+    // MessageSeen loops this ends the loop on the next check
+    if let Some(message_id) = message_id.filter(|_| automation.mark_read) {
+        if dry_run {
+            println!(
+                "[dry-run] {} '{}': would mark chat {} read",
+                label, automation.name, chat_id
+            );
+            actions_taken.push("mark read");
+        } else {
+            limiter.acquire().await;
+            if let Err(e) = actions::mark_read(app_state, chat_id, message_id).await {
+                record_stat(stats, automation, |s| s.errors += 1).await;
+                eprintln!("Error marking chat {} read: {}", chat_id, e);
+            } else {
+                actions_taken.push("mark read");
+            }
+        }
+    }
+
+    actions_taken
+}
+
+/// Fire an automation's actions once for a made-up message, so the
+/// configurator can check its sound, notifications and focus end to end.
+/// Uses the automation's first chat, if it has one. Nothing is marked read
+/// or written to the history. Returns the actions taken.
+pub async fn fire_test_alert(
+    app_state: &SharedAppState,
+    automation: &NotificationAutomation,
+) -> Vec<&'static str> {
+    let automation = app_state
+        .with_config(|config| automation.resolve_profile(&config.notification_profiles))
+        .unwrap_or_else(|_| automation.clone());
+    let chat_id = automation.chat_ids.first().cloned().unwrap_or_default();
+    let trigger = TriggerContext {
+        chat_name: format!("Test: {}", automation.name),
+        chat_id,
+        sender: "Beeper Automations".to_string(),
+        message_text: "This is a test alert".to_string(),
+        unread_count: Some(1),
+    };
+
+    fire_actions(
+        &automation,
+        &ActionContext {
+            app_state,
+            limiter: &RateLimiter::new(None),
+            stats: &Arc::new(RwLock::new(HashMap::new())),
+            trigger: &trigger,
+            message_id: None,
+            label: "Test alert",
+            dry_run: false,
+        },
+    )
+    .await
+}
+
+/// How long a polled chat result is shared before watchers fetch it again
+const POLL_CACHE_TTL: std::time::Duration = std::time::Duration::from_millis(1500);
+
+/// The error of a call made through `with_client_async`, if it failed
+fn api_error<T>(result: &Result<Result<T, String>, AppStateError>) -> Option<String> {
+    match result {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(e.clone()),
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// Record a Beeper API request for the metrics, and sound the error or
+/// recovery alert when it changes whether the API is answering
+fn record_api_call(app_state: &SharedAppState, elapsed: std::time::Duration, error: Option<&str>) {
+    METRICS.record_api_call(elapsed, error.is_none());
+
+    let now = std::time::Instant::now();
+    let change = match error {
+        Some(e) if crate::api_check::is_unauthorized(e) => API_HEALTH.observe_rejected(now),
+        _ => API_HEALTH.observe(error.is_none(), now),
+    };
+    let Some(change) = change else {
+        return;
+    };
+    let notifications = app_state.get_config().ok().map(|config| config.notifications);
+    let sound = match change {
+        HealthChange::Failing => {
+            eprintln!("⚠ Beeper API requests are failing; automations can't see new messages");
+            tracing::warn!("Beeper API requests are failing");
+            notifications.and_then(|n| n.error_sound)
+        }
+        HealthChange::TokenRejected => {
+            eprintln!(
+                "✗ Beeper token invalid — reconfigure required. Polling is paused until \
+                 a new token is set in the configurator."
+            );
+            tracing::error!("Beeper rejected the API token, pausing polling");
+            alert_token_rejected(app_state);
+            notifications.and_then(|n| n.error_sound)
+        }
+        HealthChange::Recovered => {
+            println!("✓ Beeper API is answering again");
+            tracing::info!("Beeper API recovered");
+            notifications.and_then(|n| n.recovery_sound)
+        }
+    };
+
+    if let Some(sound) = sound.filter(|s| !s.is_empty()) {
+        play_sound(&sound);
+    }
+}
+
+/// Tell the user on every ntfy topic the enabled automations push to that
+/// the token needs replacing; it's the only alert that reaches them away
+/// from the machine
+fn alert_token_rejected(app_state: &SharedAppState) {
+    let Ok(automations) = app_state.with_config(|config| config.notifications.automations.clone())
+    else {
+        return;
+    };
+
+    let mut endpoints = std::collections::HashSet::new();
+    for ntfy_config in automations
+        .iter()
+        .filter(|a| a.enabled)
+        .filter_map(|a| a.ntfy_config.as_ref())
+        .filter(|ntfy| ntfy.enabled && !ntfy.url.is_empty())
+    {
+        if endpoints.insert(ntfy_config.endpoint()) {
+            post_ntfy(
+                ntfy_config,
+                Some("Beeper Automations".to_string()),
+                "Beeper token invalid — reconfigure required".to_string(),
+            );
+        }
+    }
+}
+
+/// The fields of a chat's latest message that the watchers act on
+#[derive(Debug, Clone)]
+struct PolledMessage {
+    id: String,
+    sort_key: String,
+    sender_name: Option<String>,
+    text: Option<String>,
+    is_sender: Option<bool>,
+    /// When the message was sent, if the API gave a readable timestamp
+    sent_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Whether the message mentions me, if the API says. The Desktop API
+    /// doesn't report mentions yet, so this is `None` and `mention_names` decides.
+    mentions_me: Option<bool>,
+}
+
+/// Latest message per chat with its fetch time, shared by all automations
+type PollCache = Arc<RwLock<HashMap<String, (std::time::Instant, Option<PolledMessage>)>>>;
+
+/// Fetch the latest message for a chat, reusing a recent result from another
+/// automation watching the same chat when one is available
+async fn fetch_latest_message(
+    app_state: &SharedAppState,
+    poll_cache: &PollCache,
+    limiter: &RateLimiter,
+    chat_id: &str,
+) -> Result<Option<PolledMessage>, String> {
+    if let Some((_, latest)) = poll_cache
+        .read()
+        .await
+        .get(chat_id)
+        .filter(|(fetched_at, _)| fetched_at.elapsed() < POLL_CACHE_TTL)
+    {
+        return Ok(latest.clone());
+    }
+
+    limiter.acquire().await;
+    let started = std::time::Instant::now();
+    let result = app_state
+        .with_client_async(|client| async move { client.list_messages(chat_id, None).await })
+        .await;
+    record_api_call(app_state, started.elapsed(), api_error(&result).as_deref());
+
+    let latest = match result {
+        Ok(Ok(messages_response)) => {
+            // Older messages on the page teach SELF_IDENTITY my name too
+            for message in messages_response.items.iter().skip(1) {
+                SELF_IDENTITY.resolve(message.is_sender, message.sender_name.as_deref());
+            }
+            messages_response
+                .items
+                .first()
+                .map(|message| PolledMessage {
+                    id: message.id.clone(),
+                    sort_key: message.sort_key.clone(),
+                    sender_name: message.sender_name.clone(),
+                    text: message.text.clone(),
+                    // Fills in is_sender from my known names when the API leaves it out
+                    is_sender: SELF_IDENTITY
+                        .resolve(message.is_sender, message.sender_name.as_deref()),
+                    sent_at: chrono::DateTime::parse_from_rfc3339(&message.timestamp)
+                        .ok()
+                        .map(|t| t.with_timezone(&chrono::Utc)),
+                    mentions_me: None,
+                })
+        }
+        Ok(Err(e)) => return Err(e),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    poll_cache
+        .write()
+        .await
+        .insert(chat_id.to_string(), (std::time::Instant::now(), latest.clone()));
+
+    Ok(latest)
+}
+
+/// Fetch the latest message of several chats at once, in the order given.
+/// Each fetch is an async client call in its own task, so the requests are in
+/// flight together and a slow chat doesn't hold up the rest.
+async fn fetch_latest_messages(
+    app_state: &SharedAppState,
+    poll_cache: &PollCache,
+    limiter: &Arc<RateLimiter>,
+    chat_ids: &[String],
+) -> Vec<Result<Option<PolledMessage>, String>> {
+    let handles: Vec<_> = chat_ids
+        .iter()
+        .map(|chat_id| {
+            let app_state = app_state.clone();
+            let poll_cache = poll_cache.clone();
+            let limiter = limiter.clone();
+            let chat_id = chat_id.clone();
+            tokio::spawn(async move {
+                fetch_latest_message(&app_state, &poll_cache, &limiter, &chat_id).await
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(
+            handle
+                .await
+                .unwrap_or_else(|e| Err(format!("Poll task failed: {}", e))),
+        );
+    }
+    results
+}
+
+/// How long loop automations reuse the first chat list page for unread counts.
+/// Shorter than the default check interval, so each poll still sees fresh counts.
+const CHAT_LIST_TTL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Most chat list pages read while looking up an automation's chats, so a
+/// chat that no longer exists doesn't page through the whole account
+const MAX_CHAT_PAGES: usize = 5;
+
+/// One page of the chat list: items, the cursor for the next page, and whether more exist
+type ChatPage<T> = (Vec<T>, Option<String>, bool);
+
+/// Fetch one page of chats. The first page comes from the shared chat list
+/// cache, so automations polling at the same time share a single request.
+async fn fetch_chat_page(
+    app_state: &SharedAppState,
+    limiter: &RateLimiter,
+    cursor: Option<String>,
+) -> Result<ChatPage<beeper_desktop_api::Chat>, String> {
+    let Some(cursor) = cursor else {
+        let chat_list = match app_state.cached_chats(CHAT_LIST_TTL) {
+            Some(chat_list) => chat_list,
+            None => {
+                limiter.acquire().await;
+                let started = std::time::Instant::now();
+                let chat_list = app_state
+                    .get_chats_cached_async(CHAT_LIST_TTL)
+                    .await
+                    .map_err(|e| e.to_string());
+                record_api_call(
+                    app_state,
+                    started.elapsed(),
+                    chat_list.as_ref().err().map(String::as_str),
+                );
+                chat_list?
+            }
+        };
+        return Ok((
+            chat_list.chats.clone(),
+            chat_list.oldest_cursor.clone(),
+            chat_list.has_more,
+        ));
+    };
+
+    limiter.acquire().await;
+    let started = std::time::Instant::now();
+    let response = app_state
+        .with_client_async(|client| async move { client.list_chats(Some(&cursor)).await })
+        .await;
+    record_api_call(
+        app_state,
+        started.elapsed(),
+        api_error(&response).as_deref(),
+    );
+    let response = response.map_err(|e| e.to_string())??;
+
+    Ok((response.items, response.oldest_cursor, response.has_more))
+}
+
+/// Ids of the chats whose names match `pattern`, from the first
+/// `MAX_CHAT_PAGES` pages of the chat list
+pub(crate) async fn resolve_chat_match(
+    app_state: &SharedAppState,
+    limiter: &RateLimiter,
+    pattern: &str,
+) -> Result<Vec<String>, String> {
+    let mut matched = Vec::new();
+    let mut cursor = None;
+
+    for _ in 0..MAX_CHAT_PAGES {
+        let (items, next_cursor, has_more) = fetch_chat_page(app_state, limiter, cursor).await?;
+        matched.extend(
+            items
+                .into_iter()
+                .filter(|chat| crate::notifications::chat_match::glob_matches(pattern, &chat.title))
+                .map(|chat| chat.id),
+        );
+
+        match next_cursor {
+            Some(next) if has_more => cursor = Some(next),
+            _ => break,
+        }
+    }
+
+    Ok(matched)
+}
+
+/// Look up the given chats, reading pages only until all of them are found
+/// and never more than `MAX_CHAT_PAGES`. Chats that weren't found are left out.
+async fn find_chats<T, F, Fut>(
+    chat_ids: &[String],
+    id_of: impl Fn(&T) -> &str,
+    mut fetch_page: F,
+) -> Result<HashMap<String, T>, String>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: std::future::Future<Output = Result<ChatPage<T>, String>>,
+{
+    let mut found = HashMap::new();
+    let mut cursor = None;
+
+    for _ in 0..MAX_CHAT_PAGES {
+        let (items, next_cursor, has_more) = fetch_page(cursor.take()).await?;
+        for item in items {
+            let id = id_of(&item);
+            if chat_ids.iter().any(|chat_id| chat_id == id) {
+                found.insert(id.to_string(), item);
+            }
+        }
+
+        if chat_ids.iter().all(|chat_id| found.contains_key(chat_id)) || !has_more {
+            break;
+        }
+        match next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    Ok(found)
+}
+
+/// Trigger context for a new message the immediate watcher saw. The chat's
+/// title and unread count come from the chat list like in the other watchers;
+/// a chat the list doesn't turn up goes by its id.
+async fn immediate_trigger_context(
+    app_state: &SharedAppState,
+    limiter: &RateLimiter,
+    chat_id: &str,
+    message: &PolledMessage,
+) -> TriggerContext {
+    let chat_ids = [chat_id.to_string()];
+    let chat = match find_chats(
+        &chat_ids,
+        |chat: &beeper_desktop_api::Chat| chat.id.as_str(),
+        |cursor| fetch_chat_page(app_state, limiter, cursor),
+    )
+    .await
+    {
+        Ok(mut chats) => chats.remove(chat_id),
+        Err(e) => {
+            tracing::warn!("Failed to look up chat {} for its name: {}", chat_id, e);
+            None
+        }
+    };
+
+    TriggerContext::new(
+        chat_id,
+        chat.as_ref().map(|chat| chat.title.as_str()),
+        message,
+        chat.as_ref().map(|chat| chat.unread_count),
+    )
+}
+
+/// Ids of every chat in the first `MAX_CHAT_PAGES` pages of the chat list,
+/// and whether that was the whole list
+pub(crate) async fn list_chat_ids(
+    app_state: &SharedAppState,
+    limiter: &RateLimiter,
+) -> Result<(BTreeSet<String>, bool), String> {
+    let mut chat_ids = BTreeSet::new();
+    let mut cursor = None;
+
+    for _ in 0..MAX_CHAT_PAGES {
+        let (items, next_cursor, has_more) = fetch_chat_page(app_state, limiter, cursor).await?;
+        chat_ids.extend(items.into_iter().map(|chat| chat.id));
+
+        match next_cursor {
+            Some(next) if has_more => cursor = Some(next),
+            _ => return Ok((chat_ids, true)),
+        }
+    }
+
+    Ok((chat_ids, false))
+}
+
+/// The chats in `listed` that aren't in `known` yet, adding them to it
+fn take_new_chats<T>(
+    known: &mut BTreeSet<String>,
+    listed: Vec<T>,
+    id_of: impl Fn(&T) -> &str,
+) -> Vec<T> {
+    listed
+        .into_iter()
+        .filter(|chat| known.insert(id_of(chat).to_string()))
+        .collect()
+}
+
+/// Whether `new` sorts after `old`. Keys are compared numerically when both
+/// are integers, otherwise by length and then lexically, so "10" is newer than "9".
+fn sort_key_is_newer(old: &str, new: &str) -> bool {
+    match (old.parse::<u128>(), new.parse::<u128>()) {
+        (Ok(old), Ok(new)) => new > old,
+        _ => (new.len(), new) > (old.len(), old),
+    }
+}
+
 #[allow(unused)]
 #[derive(Debug, Clone)]
 struct LastMessageCache {
@@ -158,18 +1136,636 @@ struct LastMessageCache {
     notification_start_time: Option<std::time::Instant>,
 }
 
+/// Where a chat is in its `LoopUntil::ForATime` notification window
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NotificationWindow {
+    /// No window running; wait for a new message
+    Idle,
+    /// Window running; keep notifying
+    Active,
+    /// Window just ran out on this check; reported once, then `Idle`
+    Ended,
+}
+
+impl LastMessageCache {
+    /// Record the chat's latest message, returning whether it is newer than
+    /// the cached one. A new message (re)starts the window when `timed`.
+    fn observe(
+        &mut self,
+        message_id: &str,
+        sort_key: &str,
+        timed: bool,
+        now: std::time::Instant,
+    ) -> bool {
+        if !sort_key_is_newer(&self.sort_key, sort_key) {
+            return false;
+        }
+
+        self.message_id = message_id.to_string();
+        self.sort_key = sort_key.to_string();
+        self.notification_start_time = timed.then_some(now);
+        true
+    }
+
+    /// Take `message_id` as already seen without alerting, closing any
+    /// running `ForATime` window
+    fn reseed(&mut self, message_id: &str, sort_key: &str) {
+        self.message_id = message_id.to_string();
+        self.sort_key = sort_key.to_string();
+        self.notification_start_time = None;
+    }
+
+    /// Advance the `ForATime` window, closing it once `time_limit` ms have passed
+    fn check_window(
+        &mut self,
+        time_limit: Option<u64>,
+        now: std::time::Instant,
+    ) -> NotificationWindow {
+        let (Some(start_time), Some(time_limit)) = (self.notification_start_time, time_limit)
+        else {
+            return NotificationWindow::Idle;
+        };
+
+        if now.saturating_duration_since(start_time).as_millis() >= time_limit as u128 {
+            self.notification_start_time = None;
+            NotificationWindow::Ended
+        } else {
+            NotificationWindow::Active
+        }
+    }
+}
+
+/// How often immediate automations, and combined ones, poll their chats
+const IMMEDIATE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Combined automations poll at the immediate rate but only re-check a chat's
+/// loop condition once `check_interval` has passed since it was last checked
+fn reminder_due(
+    last_checked: Option<std::time::Instant>,
+    check_interval: std::time::Duration,
+    now: std::time::Instant,
+) -> bool {
+    last_checked.is_none_or(|at| now.duration_since(at) >= check_interval)
+}
+
+/// `LoopUntil::Once` alerts only on the check that first sees a message, and
+/// only if that message hasn't been read in the meantime
+fn fires_once(is_new_message: bool, has_unread: bool) -> bool {
+    is_new_message && has_unread
+}
+
+/// Whether a message was sent longer ago than `max_age_secs`. Messages
+/// without a readable timestamp are never considered stale.
+fn is_stale(
+    sent_at: Option<chrono::DateTime<chrono::Utc>>,
+    max_age_secs: Option<u64>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    match (sent_at, max_age_secs) {
+        (Some(sent_at), Some(max_age)) => {
+            (now - sent_at).num_seconds() > i64::try_from(max_age).unwrap_or(i64::MAX)
+        }
+        _ => false,
+    }
+}
+
+/// Whether `text` contains `name` as a whole word, ignoring case and a
+/// leading `@` on the name
+fn contains_name(text: &str, name: &str) -> bool {
+    let name = name.trim().trim_start_matches('@').to_lowercase();
+    if name.is_empty() {
+        return false;
+    }
+
+    let text = text.to_lowercase();
+    text.match_indices(&name).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + name.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Whether a message passes an automation's `mentions_only` filter. Mention
+/// data from the API wins; otherwise the text is searched for one of
+/// `mention_names`, which also catches names merely written in passing.
+fn passes_mention_filter(automation: &NotificationAutomation, message: &PolledMessage) -> bool {
+    if !automation.mentions_only {
+        return true;
+    }
+
+    match message.mentions_me {
+        Some(mentioned) => mentioned,
+        None => message.text.as_deref().is_some_and(|text| {
+            automation
+                .mention_names
+                .iter()
+                .any(|name| contains_name(text, name))
+        }),
+    }
+}
+
+/// `LoopUntil::MessageSeen` keeps alerting while at least `min_unread`
+/// messages are unread. A threshold of 0 is treated as 1 so a read chat
+/// never alerts.
+fn meets_unread_threshold(unread_count: u32, min_unread: Option<u32>) -> bool {
+    unread_count >= min_unread.unwrap_or(1).max(1)
+}
+
+/// Span the flapping check counts an automation's alerts over
+const FLAPPING_WINDOW: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How long a flapping automation is snoozed when `snooze_flapping` is set
+const FLAPPING_SNOOZE: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// Per-automation activity counters, reported when the service shuts down
+#[derive(Debug, Clone, Default)]
+pub struct AutomationStats {
+    pub name: String,
+    pub triggered: u64,
+    pub sounds_played: u64,
+    pub focus_actions: u64,
+    pub errors: u64,
+    /// When the alerts within the last `FLAPPING_WINDOW` fired, oldest first
+    pub(crate) recent_triggers: VecDeque<std::time::Instant>,
+}
+
+impl AutomationStats {
+    /// Count an alert fired at `now`. Returns how many fired within the
+    /// last `FLAPPING_WINDOW`, this one included.
+    fn record_trigger(&mut self, now: std::time::Instant) -> usize {
+        self.triggered += 1;
+        while self
+            .recent_triggers
+            .front()
+            .is_some_and(|at| now.saturating_duration_since(*at) >= FLAPPING_WINDOW)
+        {
+            self.recent_triggers.pop_front();
+        }
+        self.recent_triggers.push_back(now);
+        self.recent_triggers.len()
+    }
+
+    /// Whether more than `per_minute` alerts fired in the last minute before
+    /// `now`. A limit of 0 turns the check off.
+    pub fn is_flapping(&self, per_minute: u32, now: std::time::Instant) -> bool {
+        let recent = self
+            .recent_triggers
+            .iter()
+            .filter(|at| now.saturating_duration_since(**at) < FLAPPING_WINDOW)
+            .count();
+        per_minute > 0 && recent > per_minute as usize
+    }
+}
+
+pub(crate) type SharedStats = Arc<RwLock<HashMap<String, AutomationStats>>>;
+
+/// Update the counters for an automation, creating its entry on first use
+async fn record_stat<F>(stats: &SharedStats, automation: &NotificationAutomation, f: F)
+where
+    F: FnOnce(&mut AutomationStats),
+{
+    let mut stats = stats.write().await;
+    let entry = stats
+        .entry(automation.id.clone())
+        .or_insert_with(|| AutomationStats {
+            name: automation.name.clone(),
+            ..Default::default()
+        });
+    f(entry);
+}
+
+/// Count an alert for the session stats, and catch an automation alerting
+/// so often it is probably misconfigured, e.g. a loop with a tiny interval
+/// on a chat that stays unread. Warns once as it crosses the configured rate,
+/// and snoozes it for `FLAPPING_SNOOZE` when `snooze_flapping` is set. The
+/// snooze goes in the runtime state too, so config reloads keep it.
+async fn record_trigger(
+    app_state: &SharedAppState,
+    stats: &SharedStats,
+    runtime_state: &RwLock<RuntimeState>,
+    automation: &NotificationAutomation,
+    chat_id: &str,
+) {
+    let mut recent = 0;
+    record_stat(stats, automation, |s| {
+        recent = s.record_trigger(std::time::Instant::now())
+    })
+    .await;
+
+    let Ok((per_minute, snooze)) = app_state.with_config(|config| {
+        (
+            config.notifications.flapping_alerts_per_minute,
+            config.notifications.snooze_flapping,
+        )
+    }) else {
+        return;
+    };
+    if per_minute == 0 || recent != per_minute as usize + 1 {
+        return;
+    }
+
+    eprintln!(
+        "⚠ Automation '{}' is flapping: {} alerts in the last minute. Check its chats and interval.",
+        automation.name, recent
+    );
+    tracing::warn!(
+        "Automation '{}' is flapping: {} alerts in the last minute",
+        automation.name,
+        recent
+    );
+    if !snooze {
+        return;
+    }
+
+    // Like a snooze from the configurator, the config file is left alone
+    let until = std::time::SystemTime::now() + FLAPPING_SNOOZE;
+    let minutes = FLAPPING_SNOOZE.as_secs() / 60;
+    let snoozed = app_state.with_config_mut(|config| {
+        if let Some(running) = config
+            .notifications
+            .automations
+            .iter_mut()
+            .find(|a| a.id == automation.id)
+        {
+            running.snoozed_until = Some(until);
+        }
+    });
+    if let Err(e) = snoozed {
+        tracing::error!(
+            "Failed to snooze flapping automation '{}': {}",
+            automation.name,
+            e
+        );
+        return;
+    }
+    let until_ms = quiet::now_ms() + FLAPPING_SNOOZE.as_millis() as u64;
+    runtime_state
+        .write()
+        .await
+        .flapping_snoozes
+        .insert(automation.id.clone(), until_ms);
+    println!(
+        "💤 '{}' snoozed for {} min for flapping",
+        automation.name, minutes
+    );
+
+    // Tell the user through whatever the automation alerts with
+    let title = format!("Snoozed '{}'", automation.name);
+    let body = format!(
+        "It alerted {} times in a minute, so it is snoozed for {} min",
+        recent, minutes
+    );
+    let ntfy_config = automation
+        .ntfy_config
+        .as_ref()
+        .filter(|ntfy| ntfy.enabled && !ntfy.url.is_empty());
+    if let Some(ntfy_config) = ntfy_config {
+        post_ntfy(ntfy_config, Some(title.clone()), body.clone());
+    }
+    if desktop::SUPPORTED {
+        desktop::show(
+            app_state,
+            DesktopNotification {
+                title,
+                body,
+                chat_id: chat_id.to_string(),
+                message_id: None,
+            },
+        );
+    } else if ntfy_config.is_none() {
+        // Nothing else would show it; the error sound, if set, tells it
+        // apart from an ordinary alert
+        let error_sound = app_state
+            .with_config(|config| config.notifications.error_sound.clone())
+            .ok()
+            .flatten();
+        if let Some(sound) = error_sound.or_else(|| automation.notification_sound.clone()) {
+            play_sound(&sound);
+        }
+    }
+}
+
+/// Wall-clock time beyond the expected poll delay that counts as the machine
+/// having slept, or the clock having been changed
+const WAKE_THRESHOLD: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Whether the wall clock moved much further than a `slept` long sleep should
+/// take, in either direction. The sleep itself runs on the monotonic clock,
+/// which stands still while the machine is suspended.
+fn is_wake_gap(
+    before: std::time::SystemTime,
+    after: std::time::SystemTime,
+    slept: std::time::Duration,
+) -> bool {
+    match after.duration_since(before) {
+        Ok(elapsed) => elapsed > slept + WAKE_THRESHOLD,
+        Err(e) => e.duration() > WAKE_THRESHOLD,
+    }
+}
+
+/// Sleep until the next poll. After a suspend or clock jump, mark every
+/// chat's current latest message as seen so messages that arrived while
+/// asleep don't all alert at once on wake.
+async fn sleep_until_next_poll(
+    app_state: &SharedAppState,
+    poll_cache: &PollCache,
+    limiter: &Arc<RateLimiter>,
+    automation: &NotificationAutomation,
+    last_messages: &mut HashMap<String, LastMessageCache>,
+    delay: std::time::Duration,
+) {
+    let before = std::time::SystemTime::now();
+    tokio::time::sleep(delay).await;
+    if !is_wake_gap(before, std::time::SystemTime::now(), delay) {
+        return;
+    }
+
+    println!(
+        "⏰ Automation '{}': woke from sleep or the clock changed, catching up without alerting",
+        automation.name
+    );
+    tracing::info!(
+        "Automation '{}': wall-clock jump detected, re-seeding chat tracking",
+        automation.name
+    );
+
+    let latest_messages =
+        fetch_latest_messages(app_state, poll_cache, limiter, &automation.chat_ids).await;
+    for (chat_id, latest) in automation.chat_ids.iter().zip(latest_messages) {
+        match (last_messages.get_mut(chat_id), latest) {
+            (Some(cached), Ok(Some(message))) => cached.reseed(&message.id, &message.sort_key),
+            (None, Ok(Some(message))) => {
+                last_messages.insert(
+                    chat_id.clone(),
+                    LastMessageCache {
+                        message_id: message.id,
+                        sort_key: message.sort_key,
+                        notification_start_time: None,
+                    },
+                );
+            }
+            // Still close a running window so it doesn't end the moment polling resumes
+            (Some(cached), _) => cached.notification_start_time = None,
+            (None, _) => {}
+        }
+    }
+}
+
+/// First restart delay after an automation task panics; doubles on each
+/// consecutive crash up to `MAX_RESTART_DELAY`
+const RESTART_BASE_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+const MAX_RESTART_DELAY: std::time::Duration = std::time::Duration::from_secs(300);
+/// Give up on an automation after this many crashes in a row
+const MAX_RESTARTS: u32 = 5;
+/// A run this long resets the consecutive crash counter
+const STABLE_RUN: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// Aborts the wrapped task when dropped, so aborting a supervisor also
+/// stops the automation it is watching
+struct AbortOnDrop(JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Which message each automation/chat pair already alerted on at startup,
+/// kept across task restarts so `notify_on_startup` fires only once per message
+type StartupAlerts = Arc<RwLock<HashMap<(String, String), String>>>;
+
+/// Claim the startup alert for a chat's current message; false if already sent
+async fn claim_startup_alert(
+    startup_alerts: &StartupAlerts,
+    automation_id: &str,
+    chat_id: &str,
+    message_id: &str,
+) -> bool {
+    let mut alerts = startup_alerts.write().await;
+    let key = (automation_id.to_string(), chat_id.to_string());
+    if alerts.get(&key).is_some_and(|id| id == message_id) {
+        return false;
+    }
+    alerts.insert(key, message_id.to_string());
+    true
+}
+
+/// Random offset in `0..interval` before an automation's first poll, so
+/// automations started by the same reload don't poll in lockstep
+fn initial_poll_delay(interval: std::time::Duration) -> std::time::Duration {
+    interval.mul_f64(rand::random::<f64>())
+}
+
+/// Delay until the next poll: the interval itself, or within ±10% of it
+/// when jitter is enabled
+fn next_poll_delay(interval: std::time::Duration, jitter: bool) -> std::time::Duration {
+    if jitter {
+        interval.mul_f64(rand::random_range(0.9..1.1))
+    } else {
+        interval
+    }
+}
+
+/// Service-wide state handed to every automation task
+#[derive(Clone)]
+struct SharedHandles {
+    stats: SharedStats,
+    poll_cache: PollCache,
+    limiter: Arc<RateLimiter>,
+    startup_alerts: StartupAlerts,
+    /// Quiet requests from the configurator made since the service started
+    quiet_requests: Arc<RwLock<QuietRequests>>,
+    /// Each automation's progress through its chats, kept across restarts
+    runtime_state: Arc<RwLock<RuntimeState>>,
+    /// Where `runtime_state` is saved; kept in memory only when unset
+    state_file: Option<PathBuf>,
+    /// Where triggers are recorded for the configurator; not recorded when unset
+    history_file: Option<PathBuf>,
+}
+
+/// Files the service keeps outside the config. Each is left alone when
+/// unset, as in tests, which must not touch the user's own.
+#[derive(Debug, Clone, Default)]
+struct ServiceFiles {
+    /// Runtime state, kept in memory only when unset
+    state: Option<PathBuf>,
+    /// Notification history, not recorded when unset
+    history: Option<PathBuf>,
+    /// Quiet requests from the configurator, not picked up when unset
+    quiet: Option<PathBuf>,
+}
+
+impl ServiceFiles {
+    /// The files in their usual places in the config and data directories
+    fn default_locations() -> Self {
+        let quiet = quiet::quiet_file_path()
+            .map_err(|e| tracing::warn!("Quiet requests are unavailable: {}", e))
+            .ok();
+        Self {
+            state: RuntimeState::state_file_path().ok(),
+            history: Some(history::history_file_path()),
+            quiet,
+        }
+    }
+}
+
+/// An automation's saved progress, as the message cache its watcher starts from
+async fn restore_progress(
+    runtime_state: &RwLock<RuntimeState>,
+    automation_id: &str,
+) -> HashMap<String, LastMessageCache> {
+    let now = std::time::Instant::now();
+    let now_ms = quiet::now_ms();
+    let state = runtime_state.read().await;
+    let Some(automation) = state.automations.get(automation_id) else {
+        return HashMap::new();
+    };
+
+    automation
+        .chats
+        .iter()
+        .map(|(chat_id, progress)| {
+            let started = progress.window_started_ms.and_then(|started_ms| {
+                now.checked_sub(std::time::Duration::from_millis(
+                    now_ms.saturating_sub(started_ms),
+                ))
+            });
+            (
+                chat_id.clone(),
+                LastMessageCache {
+                    message_id: progress.message_id.clone(),
+                    sort_key: progress.sort_key.clone(),
+                    notification_start_time: started,
+                },
+            )
+        })
+        .collect()
+}
+
+/// How long progress in a chat an automation stopped watching is kept, in
+/// milliseconds
+fn keep_removed_chats_ms(app_state: &SharedAppState) -> u64 {
+    app_state
+        .with_config(|config| config.notifications.keep_removed_chats_secs)
+        .unwrap_or_default()
+        .saturating_mul(1000)
+}
+
+/// Record a watcher's message cache as its automation's progress. Chats the
+/// automation no longer watches keep the progress they had when they were
+/// dropped for `keep_removed_ms`, so adding one back soon after resumes
+/// from there instead of starting fresh.
+async fn record_progress(
+    runtime_state: &RwLock<RuntimeState>,
+    automation: &NotificationAutomation,
+    last_messages: &HashMap<String, LastMessageCache>,
+    keep_removed_ms: u64,
+) {
+    let now = std::time::Instant::now();
+    let now_ms = quiet::now_ms();
+    let mut chats: HashMap<String, ChatProgress> = last_messages
+        .iter()
+        .filter(|(chat_id, _)| automation.chat_ids.contains(chat_id))
+        .map(|(chat_id, cached)| {
+            let window_started_ms = cached.notification_start_time.map(|started| {
+                now_ms.saturating_sub(now.saturating_duration_since(started).as_millis() as u64)
+            });
+            (
+                chat_id.clone(),
+                ChatProgress {
+                    message_id: cached.message_id.clone(),
+                    sort_key: cached.sort_key.clone(),
+                    window_started_ms,
+                    updated_ms: now_ms,
+                },
+            )
+        })
+        .collect();
+
+    let mut state = runtime_state.write().await;
+    let mut known_chats = None;
+    if let Some(previous) = state.automations.remove(&automation.id) {
+        known_chats = previous.known_chats;
+        for (chat_id, progress) in previous.chats {
+            if !chats.contains_key(&chat_id)
+                && now_ms.saturating_sub(progress.updated_ms) < keep_removed_ms
+            {
+                chats.insert(chat_id, progress);
+            }
+        }
+    }
+    state.automations.insert(
+        automation.id.clone(),
+        AutomationState { chats, known_chats },
+    );
+}
+
+/// Write the runtime state to its file, if it has one
+async fn save_runtime_state(shared: &SharedHandles) {
+    let Some(path) = &shared.state_file else {
+        return;
+    };
+    let mut runtime_state = shared.runtime_state.write().await;
+    runtime_state.token_rejected_ms = API_HEALTH.token_rejected_ms();
+    if let Err(e) = runtime_state.save_to(path) {
+        eprintln!("✗ Failed to save runtime state to {:?}: {}", path, e);
+        tracing::error!("Failed to save runtime state to {:?}: {}", path, e);
+    }
+}
+
+/// How often the service looks for new quiet requests
+const QUIET_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Keep `requests` in step with the quiet request file, ignoring requests
+/// from before the service started. Those were meant for alerts that are
+/// gone, and could silence a message that arrived in the meantime.
+async fn watch_quiet_requests(requests: Arc<RwLock<QuietRequests>>, path: PathBuf) {
+    let started = quiet::now_ms();
+    let mut last_modified = None;
+    loop {
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        if modified != last_modified {
+            last_modified = modified;
+            let mut fresh = quiet::load_from(&path);
+            fresh.retain(|_, requested| *requested >= started);
+            *requests.write().await = fresh;
+        }
+        tokio::time::sleep(QUIET_POLL_INTERVAL).await;
+    }
+}
+
 #[derive(Debug)]
 struct AutomationTask {
     automation_id: String,
+    /// What the task was started with; see [`task_settings`]
+    settings: serde_json::Value,
     handle: JoinHandle<()>,
 }
 
+/// Everything a running automation task was handed when it started, so a
+/// reload can tell which tasks need restarting. Snoozes are left out: tasks
+/// look those up in the live config.
+fn task_settings(
+    automation: &NotificationAutomation,
+    dry_run: bool,
+    jitter: bool,
+) -> serde_json::Value {
+    let mut automation = automation.clone();
+    automation.snoozed_until = None;
+    serde_json::json!({
+        "automation": automation,
+        "dry_run": dry_run,
+        "jitter": jitter,
+    })
+}
+
 #[allow(dead_code)]
 pub struct NotificationService {
     app_state: SharedAppState,
     automation_tasks: Arc<RwLock<Vec<AutomationTask>>>,
     last_messages: Arc<RwLock<HashMap<String, LastMessageCache>>>,
     reload_rx: Arc<RwLock<tokio::sync::mpsc::Receiver<Config>>>,
+    shared: SharedHandles,
 }
 
 impl Drop for NotificationService {
@@ -185,14 +1781,52 @@ impl Drop for NotificationService {
 
 impl NotificationService {
     pub fn new(app_state: SharedAppState, reload_rx: tokio::sync::mpsc::Receiver<Config>) -> Self {
+        Self::with_files(app_state, reload_rx, ServiceFiles::default_locations())
+    }
+
+    /// Start the service with its runtime state, history and quiet requests
+    /// in `files`
+    fn with_files(
+        app_state: SharedAppState,
+        reload_rx: tokio::sync::mpsc::Receiver<Config>,
+        files: ServiceFiles,
+    ) -> Self {
+        // Look for an audio device up front so a missing one is reported at startup
+        audio_available();
+
+        let mut runtime_state = files
+            .state
+            .as_deref()
+            .map(|path| RuntimeState::load_from(path, quiet::now_ms()))
+            .unwrap_or_default();
+        // Flapping snoozes from the last run outlast a restart too
+        let _ = app_state.with_config_mut(|config| {
+            runtime_state.apply_flapping_snoozes(config, quiet::now_ms())
+        });
+
         let last_messages = Arc::new(RwLock::new(HashMap::new()));
         let reload_rx = Arc::new(RwLock::new(reload_rx));
+        let shared = SharedHandles {
+            stats: Arc::new(RwLock::new(HashMap::new())),
+            poll_cache: Arc::new(RwLock::new(HashMap::new())),
+            limiter: Arc::new(RateLimiter::new(None)),
+            startup_alerts: Arc::new(RwLock::new(HashMap::new())),
+            quiet_requests: Arc::new(RwLock::new(HashMap::new())),
+            runtime_state: Arc::new(RwLock::new(runtime_state)),
+            state_file: files.state,
+            history_file: files.history,
+        };
+        if let Some(path) = files.quiet {
+            tokio::spawn(watch_quiet_requests(shared.quiet_requests.clone(), path));
+        }
+        tokio::spawn(scheduled_focus::run(app_state.clone()));
 
         let service = Self {
             app_state: app_state.clone(),
             automation_tasks: Arc::new(RwLock::new(Vec::new())),
             last_messages: last_messages.clone(),
             reload_rx: reload_rx.clone(),
+            shared: shared.clone(),
         };
 
         // Start automation loops based on config
@@ -201,20 +1835,93 @@ impl NotificationService {
             let automation_tasks = service.automation_tasks.clone();
             let last_messages = last_messages.clone();
             let reload_rx = reload_rx.clone();
+            let shared = shared.clone();
 
             async move {
-                Self::run_service(app_state, automation_tasks, last_messages, reload_rx).await;
+                Self::run_service(app_state, automation_tasks, last_messages, reload_rx, shared)
+                .await;
             }
         });
 
         service
     }
 
+    /// Serve the Prometheus metrics endpoint in the background, if configured
+    pub fn serve_metrics(&self, config: &crate::config::MetricsConfig) {
+        if let Some(port) = config.port {
+            tokio::spawn(metrics::serve(
+                config.address.clone(),
+                port,
+                self.shared.stats.clone(),
+            ));
+        }
+    }
+
+    /// Listen for commands from the configurator in the background, if enabled.
+    /// `reload_now` is notified when a reload is requested.
+    pub fn serve_control(
+        &self,
+        config: &crate::config::ControlConfig,
+        reload_now: Arc<tokio::sync::Notify>,
+    ) {
+        if config.enabled {
+            tokio::spawn(control::serve(
+                config.port,
+                control::ControlContext {
+                    app_state: self.app_state.clone(),
+                    stats: self.shared.stats.clone(),
+                    runtime_state: self.shared.runtime_state.clone(),
+                    reload_now,
+                },
+            ));
+        }
+    }
+
+    /// Save each automation's progress so the next run picks up from it
+    pub async fn save_state(&self) {
+        save_runtime_state(&self.shared).await;
+    }
+
+    /// Print a table of what each automation did during this session
+    pub async fn print_summary(&self) {
+        let stats = self.shared.stats.read().await;
+
+        if stats.is_empty() {
+            println!("📊 No automations ran during this session.");
+            return;
+        }
+
+        let mut rows: Vec<&AutomationStats> = stats.values().collect();
+        rows.sort_by(|a, b| a.name.cmp(&b.name));
+
+        println!("📊 Session summary:");
+        println!(
+            "  {:<24} {:>9} {:>6} {:>6} {:>6}",
+            "Automation", "Triggered", "Sounds", "Focus", "Errors"
+        );
+        for row in rows {
+            let name: String = row.name.chars().take(24).collect();
+            println!(
+                "  {:<24} {:>9} {:>6} {:>6} {:>6}",
+                name, row.triggered, row.sounds_played, row.focus_actions, row.errors
+            );
+            tracing::info!(
+                "Session summary for '{}': triggered={}, sounds={}, focus={}, errors={}",
+                row.name,
+                row.triggered,
+                row.sounds_played,
+                row.focus_actions,
+                row.errors
+            );
+        }
+    }
+
     async fn run_service(
         app_state: SharedAppState,
         automation_tasks: Arc<RwLock<Vec<AutomationTask>>>,
         last_messages: Arc<RwLock<HashMap<String, LastMessageCache>>>,
         reload_rx: Arc<RwLock<tokio::sync::mpsc::Receiver<Config>>>,
+        shared: SharedHandles,
     ) {
         tracing::info!("Notification service run loop started");
         // Listen for config reload signals (including initial config)
@@ -231,6 +1938,7 @@ impl NotificationService {
                         &app_state,
                         &automation_tasks,
                         &last_messages,
+                        &shared,
                         config,
                     )
                     .await;
@@ -249,49 +1957,118 @@ impl NotificationService {
         app_state: &SharedAppState,
         automation_tasks: &Arc<RwLock<Vec<AutomationTask>>>,
         last_messages: &Arc<RwLock<HashMap<String, LastMessageCache>>>,
-        new_config: Config,
+        shared: &SharedHandles,
+        mut new_config: Config,
     ) {
+        // The config file doesn't hold snoozes for flapping; carry them over
+        shared
+            .runtime_state
+            .write()
+            .await
+            .apply_flapping_snoozes(&mut new_config, quiet::now_ms());
+
+        // A new token or URL deserves a try straight away
+        let api_changed = app_state
+            .with_config(|config| {
+                config.api.url != new_config.api.url || config.api.token != new_config.api.token
+            })
+            .unwrap_or(true);
+
         // Update app state with new config
         if let Err(e) = app_state.update_config(new_config.clone()) {
             eprintln!("Error updating app state: {}", e);
             return;
         }
+        if api_changed {
+            API_HEALTH.clear_token_rejection();
+        }
 
-        let old_tasks = automation_tasks.read().await;
-        let old_automation_ids: Vec<String> =
-            old_tasks.iter().map(|t| t.automation_id.clone()).collect();
+        shared
+            .limiter
+            .set_limit(new_config.notifications.max_requests_per_minute)
+            .await;
+
+        // Forget tasks that already ended (gave up after crashing, or exited
+        // on a config they couldn't use) so they count as new below and get
+        // started again instead of being left for dead
+        let mut old_tasks = automation_tasks.write().await;
+        old_tasks.retain(|task| !task.handle.is_finished());
+        let old_settings: HashMap<String, serde_json::Value> = old_tasks
+            .iter()
+            .map(|t| (t.automation_id.clone(), t.settings.clone()))
+            .collect();
         drop(old_tasks);
 
-        // Build map of new automations
-        let new_automations: HashMap<String, &NotificationAutomation> = new_config
+        // Build map of new automations, with their profile settings applied
+        let mut new_automations: HashMap<String, NotificationAutomation> = new_config
             .notifications
             .automations
             .iter()
             .filter(|a| a.enabled)
-            .map(|a| (a.id.clone(), a))
+            .map(|a| (a.id.clone(), a.resolve_profile(&new_config.notification_profiles)))
             .collect();
 
+        // Turn name patterns into concrete chats, picking up chats created since the last reload
+        for automation in new_automations.values_mut() {
+            let Some(pattern) = automation.chat_match.clone() else {
+                continue;
+            };
+            match resolve_chat_match(app_state, &shared.limiter, &pattern).await {
+                Ok(chat_ids) => {
+                    println!(
+                        "  🔎 '{}' matched {} chat(s) for automation '{}'",
+                        pattern,
+                        chat_ids.len(),
+                        automation.name
+                    );
+                    for chat_id in chat_ids {
+                        if !automation.chat_ids.contains(&chat_id) {
+                            automation.chat_ids.push(chat_id);
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!(
+                        "  ✗ Could not match chats for automation '{}': {}",
+                        automation.name, e
+                    );
+                    tracing::warn!(
+                        "Failed to resolve chat_match '{}' for automation '{}': {}",
+                        pattern,
+                        automation.name,
+                        e
+                    );
+                }
+            }
+        }
+
         let new_automation_ids: Vec<String> = new_automations.keys().cloned().collect();
+        let dry_run = new_config.notifications.dry_run;
+        let jitter = new_config.notifications.jitter;
 
         // Determine what changed
-        let to_stop: Vec<String> = old_automation_ids
-            .iter()
+        let to_stop: Vec<String> = old_settings
+            .keys()
             .filter(|id| !new_automation_ids.contains(id))
             .cloned()
             .collect();
 
         let to_start: Vec<String> = new_automation_ids
             .iter()
-            .filter(|id| !old_automation_ids.contains(id))
+            .filter(|id| !old_settings.contains_key(*id))
             .cloned()
             .collect();
 
-        // For simplicity, restart ALL existing automations since they might have changed
-        // This ensures config changes like changing loop conditions are applied
-        let to_restart: Vec<String> = new_automation_ids
+        // Restart only the automations whose settings changed; the rest keep
+        // running undisturbed, timers and all
+        let to_restart: Vec<String> = new_automations
             .iter()
-            .filter(|id| old_automation_ids.contains(id))
-            .cloned()
+            .filter(|(id, automation)| {
+                old_settings
+                    .get(*id)
+                    .is_some_and(|old| *old != task_settings(automation, dry_run, jitter))
+            })
+            .map(|(id, _)| id.clone())
             .collect();
 
         // Stop removed/disabled automations
@@ -332,22 +2109,16 @@ impl NotificationService {
             for automation_id in &to_restart {
                 if let Some(automation) = new_automations.get(automation_id) {
                     tracing::info!("Starting automation: {} (ID: {})", automation.name, automation.id);
-                    let handle = match automation.automation_type {
-                        AutomationType::Loop => {
-                            Self::start_loop_automation_static(
-                                app_state.clone(),
-                                (*automation).clone(),
-                            )
-                        }
-                        AutomationType::Immediate => {
-                            Self::start_immediate_automation_static(
-                                app_state.clone(),
-                                (*automation).clone(),
-                            )
-                        }
-                    };
+                    let handle = Self::supervise_automation(
+                        app_state.clone(),
+                        automation.clone(),
+                        dry_run,
+                        jitter,
+                        shared.clone(),
+                    );
                     tasks.push(AutomationTask {
                         automation_id: automation_id.clone(),
+                        settings: task_settings(automation, dry_run, jitter),
                         handle,
                     });
                 }
@@ -362,46 +2133,148 @@ impl NotificationService {
             for automation_id in &to_start {
                 if let Some(automation) = new_automations.get(automation_id) {
                     tracing::info!("Starting automation: {} (ID: {})", automation.name, automation.id);
-                    let handle = match automation.automation_type {
-                        AutomationType::Loop => {
-                            Self::start_loop_automation_static(
-                                app_state.clone(),
-                                (*automation).clone(),
-                            )
-                        }
-                        AutomationType::Immediate => {
-                            Self::start_immediate_automation_static(
-                                app_state.clone(),
-                                (*automation).clone(),
-                            )
-                        }
-                    };
+                    let handle = Self::supervise_automation(
+                        app_state.clone(),
+                        automation.clone(),
+                        dry_run,
+                        jitter,
+                        shared.clone(),
+                    );
                     tasks.push(AutomationTask {
                         automation_id: automation_id.clone(),
+                        settings: task_settings(automation, dry_run, jitter),
                         handle,
                     });
                 }
             }
         }
 
-        // Clean up message cache for chats no longer being tracked
-        let all_tracked_chat_ids: Vec<String> = new_config
-            .notifications
-            .automations
-            .iter()
-            .filter(|a| a.enabled)
+        // Clean up message cache for chats no longer being tracked, keeping
+        // the chats found through name patterns
+        let all_tracked_chat_ids: Vec<String> = new_automations
+            .values()
             .flat_map(|a| a.chat_ids.clone())
             .collect();
 
         let mut cache = last_messages.write().await;
         cache.retain(|chat_id, _| all_tracked_chat_ids.contains(chat_id));
+        drop(cache);
+
+        let mut polls = shared.poll_cache.write().await;
+        polls.retain(|chat_id, _| all_tracked_chat_ids.contains(chat_id));
+        drop(polls);
+
+        shared
+            .runtime_state
+            .write()
+            .await
+            .retain_automations(&new_automation_ids);
+        save_runtime_state(shared).await;
+    }
+
+    fn start_automation_static(
+        app_state: SharedAppState,
+        automation: NotificationAutomation,
+        dry_run: bool,
+        jitter: bool,
+        shared: SharedHandles,
+    ) -> JoinHandle<()> {
+        match automation.automation_type {
+            AutomationType::Loop | AutomationType::Combined => Self::start_loop_automation_static(
+                app_state, automation, dry_run, jitter, shared,
+            ),
+            AutomationType::Immediate => Self::start_immediate_automation_static(
+                app_state, automation, dry_run, jitter, shared,
+            ),
+            AutomationType::NewChat => Self::start_new_chat_automation_static(
+                app_state, automation, dry_run, jitter, shared,
+            ),
+        }
+    }
+
+    /// Run an automation and respawn it with backoff if its task panics.
+    /// Aborting the returned handle stops the automation too.
+    fn supervise_automation(
+        app_state: SharedAppState,
+        automation: NotificationAutomation,
+        dry_run: bool,
+        jitter: bool,
+        shared: SharedHandles,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut consecutive_crashes = 0;
+
+            loop {
+                let started_at = std::time::Instant::now();
+                let mut task = AbortOnDrop(Self::start_automation_static(
+                    app_state.clone(),
+                    automation.clone(),
+                    dry_run,
+                    jitter,
+                    shared.clone(),
+                ));
+
+                let error = match (&mut task.0).await {
+                    Ok(()) => break, // Finished on its own (e.g. invalid config)
+                    Err(e) if e.is_panic() => e,
+                    Err(_) => break, // Cancelled
+                };
+
+                record_stat(&shared.stats, &automation, |s| s.errors += 1).await;
+
+                if started_at.elapsed() >= STABLE_RUN {
+                    consecutive_crashes = 0;
+                }
+                consecutive_crashes += 1;
+
+                if consecutive_crashes > MAX_RESTARTS {
+                    tracing::error!(
+                        "Automation '{}' crashed {} times in a row, giving up: {}",
+                        automation.name, consecutive_crashes, error
+                    );
+                    eprintln!(
+                        "✗ Automation '{}' keeps crashing, disabled until the next config reload",
+                        automation.name
+                    );
+                    break;
+                }
+
+                let delay = (RESTART_BASE_DELAY * 2u32.pow(consecutive_crashes - 1))
+                    .min(MAX_RESTART_DELAY);
+                tracing::error!(
+                    "Automation '{}' crashed ({}), restarting in {:?} (attempt {}/{})",
+                    automation.name, error, delay, consecutive_crashes, MAX_RESTARTS
+                );
+                eprintln!(
+                    "✗ Automation '{}' crashed, restarting in {:?}",
+                    automation.name, delay
+                );
+                tokio::time::sleep(delay).await;
+            }
+        })
     }
 
     fn start_immediate_automation_static(
         app_state: SharedAppState,
         automation: NotificationAutomation,
+        dry_run: bool,
+        jitter: bool,
+        shared: SharedHandles,
     ) -> JoinHandle<()> {
         tokio::spawn(async move {
+            let SharedHandles {
+                stats,
+                poll_cache,
+                limiter,
+                startup_alerts,
+                runtime_state,
+                history_file,
+                ..
+            } = shared;
+
+            // Make sure the automation shows up in the summary even if it never fires
+            record_stat(&stats, &automation, |_| {}).await;
+
             println!(
                 "Starting immediate automation: {} (ID: {}) for {} chat(s)",
                 automation.name,
@@ -409,27 +2282,34 @@ impl NotificationService {
                 automation.chat_ids.len()
             );
 
-            // Track last seen message per chat for this automation
-            let mut last_messages: HashMap<String, LastMessageCache> = HashMap::new();
+            let poll_interval = IMMEDIATE_POLL_INTERVAL;
+            if jitter {
+                tokio::time::sleep(initial_poll_delay(poll_interval)).await;
+            }
+
+            // Track last seen message per chat for this automation, from
+            // where the last run left off
+            let mut last_messages = restore_progress(&runtime_state, &automation.id).await;
 
             loop {
+                // Hold off while Beeper rejects the token
+                if API_HEALTH.polling_paused(std::time::Instant::now()) {
+                    tokio::time::sleep(poll_interval).await;
+                    continue;
+                }
+
                 // Check each chat in this automation for new messages
                 for chat_id in &automation.chat_ids {
                     // Fetch latest message for this chat
-                    let result = app_state.with_client(|client| {
-                        tokio::task::block_in_place(|| {
-                            tokio::runtime::Handle::current()
-                                .block_on(async { client.list_messages(chat_id, None, None).await })
-                        })
-                    });
+                    let result = fetch_latest_message(&app_state, &poll_cache, &limiter, chat_id).await;
 
                     match result {
-                        Ok(Ok(messages_response)) => {
-                            if let Some(latest_message) = messages_response.items.first() {
+                        Ok(latest) => {
+                            if let Some(latest_message) = &latest {
                                 // Check if this is a new message
                                 let is_new_message = match last_messages.get(chat_id) {
                                     Some(cached) => {
-                                        cached.sort_key < latest_message.sort_key
+                                        sort_key_is_newer(&cached.sort_key, &latest_message.sort_key)
                                     }
                                     None => {
                                         // First time seeing this chat, initialize
@@ -445,16 +2325,19 @@ impl NotificationService {
                                             "Immediate automation '{}': Initialized tracking for chat {}",
                                             automation.name, chat_id
                                         );
-                                        false // Don't treat first message as new
+                                        // Don't treat first message as new, unless asked to
+                                        automation.notify_on_startup
+                                            && claim_startup_alert(
+                                                &startup_alerts,
+                                                &automation.id,
+                                                chat_id,
+                                                &latest_message.id,
+                                            )
+                                            .await
                                     }
                                 };
 
                                 if is_new_message {
-                                    println!(
-                                        "Immediate automation '{}': New message detected in chat {}",
-                                        automation.name, chat_id
-                                    );
-
                                     // Update cache
                                     last_messages.insert(
                                         chat_id.clone(),
@@ -465,93 +2348,324 @@ impl NotificationService {
                                         },
                                     );
 
-                                    // Trigger focus action (only if user is active)
-                                    if automation.focus_chat {
-                                        if is_user_active() {
-                                            tracing::info!("User is active, proceeding with focus chat action for automation '{}'", automation.name);
-                                            let result = app_state.with_client(|client| {
-                                                tokio::task::block_in_place(|| {
-                                                    tokio::runtime::Handle::current().block_on(async {
-                                                        use beeper_desktop_api::FocusAppInput;
-
-                                                        let focus_input = FocusAppInput {
-                                                            chat_id: Some(chat_id.clone()),
-                                                            message_id: None,
-                                                            draft: None,
-                                                        };
-
-                                                        client.focus_app(Some(focus_input)).await
-                                                    })
-                                                })
-                                            });
-
-                                            match result {
-                                                Ok(Ok(response)) => {
-                                                    if response.success {
-                                                        tracing::info!("Successfully focused chat {} for automation '{}'", chat_id, automation.name);
-                                                    }
-                                                }
-                                                Ok(Err(e)) => {
-                                                    tracing::error!("Error focusing chat {}: {}", chat_id, e);
-                                                    eprintln!(
-                                                        "Error focusing chat {}: {}",
-                                                        chat_id, e
-                                                    );
-                                                }
-                                                Err(e) => {
-                                                    tracing::error!("Error accessing client for focus: {}", e);
-                                                    eprintln!(
-                                                        "Error accessing client for focus: {}",
-                                                        e
-                                                    );
-                                                }
-                                            }
-                                        } else {
-                                            tracing::info!("User is idle, skipping focus chat action for automation '{}'", automation.name);
-                                        }
+                                    // Skip messages I sent myself
+                                    if automation.ignore_own_messages
+                                        && latest_message.is_sender == Some(true)
+                                    {
+                                        tracing::debug!(
+                                            "Immediate automation '{}': Ignoring own message in chat {}",
+                                            automation.name, chat_id
+                                        );
+                                        continue;
+                                    }
+
+                                    if !passes_mention_filter(&automation, latest_message) {
+                                        tracing::debug!(
+                                            "Immediate automation '{}': Message doesn't mention me in chat {}",
+                                            automation.name, chat_id
+                                        );
+                                        continue;
+                                    }
+
+                                    // Skip old history synced in with a newer sort key
+                                    if is_stale(
+                                        latest_message.sent_at,
+                                        automation.max_message_age_secs,
+                                        chrono::Utc::now(),
+                                    ) {
+                                        tracing::info!(
+                                            "Immediate automation '{}': Ignoring old message in chat {}",
+                                            automation.name, chat_id
+                                        );
+                                        continue;
                                     }
 
-                                    // Trigger notification sound if configured
-                                    if let Some(sound_path) = &automation.notification_sound {
-                                        if !sound_path.is_empty() {
-                                            println!(
-                                                "▶ Playing notification sound for '{}': {}",
-                                                automation.name, sound_path
-                                            );
-                                            play_sound(sound_path);
-                                        }
+                                    if is_paused(&app_state) {
+                                        tracing::info!(
+                                            "Immediate automation '{}': Paused, skipping actions for chat {}",
+                                            automation.name, chat_id
+                                        );
+                                        continue;
                                     }
 
-                                    // Trigger ntfy notification if configured
-                                    if let Some(ntfy_config) = &automation.ntfy_config {
-                                        let sender = latest_message.sender_name.as_deref().unwrap_or("Unknown");
-                                        send_ntfy_notification(
-                                            ntfy_config,
-                                            &automation.name,
-                                            sender,
-                                            chat_id,
+                                    if !is_in_schedule(&automation) {
+                                        tracing::info!(
+                                            "Immediate automation '{}': Outside schedule, skipping actions for chat {}",
+                                            automation.name, chat_id
                                         );
+                                        continue;
                                     }
+
+                                    if is_snoozed(&app_state, &automation) {
+                                        tracing::info!(
+                                            "Immediate automation '{}': Snoozed, skipping actions for chat {}",
+                                            automation.name, chat_id
+                                        );
+                                        continue;
+                                    }
+
+                                    println!(
+                                        "Immediate automation '{}': New message detected in chat {}",
+                                        automation.name, chat_id
+                                    );
+                                    let context = immediate_trigger_context(
+                                        &app_state,
+                                        &limiter,
+                                        chat_id,
+                                        latest_message,
+                                    )
+                                    .await;
+                                    record_trigger(
+                                        &app_state,
+                                        &stats,
+                                        &runtime_state,
+                                        &automation,
+                                        chat_id,
+                                    )
+                                    .await;
+
+                                    let actions_taken = fire_actions(
+                                        &automation,
+                                        &ActionContext {
+                                            app_state: &app_state,
+                                            limiter: &limiter,
+                                            stats: &stats,
+                                            trigger: &context,
+                                            message_id: Some(&latest_message.id),
+                                            label: "Immediate automation",
+                                            dry_run,
+                                        },
+                                    )
+                                    .await;
+                                    record_history(
+                                        history_file.as_deref(),
+                                        &automation,
+                                        &context,
+                                        actions_taken,
+                                        dry_run,
+                                    );
                                 }
                             }
                         }
-                        Ok(Err(e)) => {
+                        Err(e) => {
+                            record_stat(&stats, &automation, |s| s.errors += 1).await;
                             eprintln!(
                                 "Error fetching messages for automation '{}', chat {}: {}",
                                 automation.name, chat_id, e
                             );
                         }
+                    }
+                }
+
+                record_progress(
+                    &runtime_state,
+                    &automation,
+                    &last_messages,
+                    keep_removed_chats_ms(&app_state),
+                )
+                .await;
+
+                // Wait before next check
+                sleep_until_next_poll(
+                    &app_state,
+                    &poll_cache,
+                    &limiter,
+                    &automation,
+                    &mut last_messages,
+                    next_poll_delay(poll_interval, jitter),
+                )
+                .await;
+            }
+        })
+    }
+
+    /// Watch the chat list and alert for each chat that shows up in it.
+    /// The first listing reads `MAX_CHAT_PAGES` pages so older chats aren't
+    /// mistaken for new ones later; after that only the first page is read,
+    /// since a new chat sorts to the top.
+    fn start_new_chat_automation_static(
+        app_state: SharedAppState,
+        automation: NotificationAutomation,
+        dry_run: bool,
+        jitter: bool,
+        shared: SharedHandles,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let SharedHandles {
+                stats,
+                poll_cache,
+                limiter,
+                runtime_state,
+                history_file,
+                ..
+            } = shared;
+
+            // Make sure the automation shows up in the summary even if it never fires
+            record_stat(&stats, &automation, |_| {}).await;
+
+            println!(
+                "Starting new chat automation: {} (ID: {})",
+                automation.name, automation.id
+            );
+
+            let poll_interval = IMMEDIATE_POLL_INTERVAL;
+            if jitter {
+                tokio::time::sleep(initial_poll_delay(poll_interval)).await;
+            }
+
+            // Chats already seen, from where the last run left off
+            let mut known_chats = runtime_state
+                .read()
+                .await
+                .automations
+                .get(&automation.id)
+                .and_then(|state| state.known_chats.clone());
+
+            loop {
+                // Hold off while Beeper rejects the token
+                if API_HEALTH.polling_paused(std::time::Instant::now()) {
+                    tokio::time::sleep(poll_interval).await;
+                    continue;
+                }
+
+                let new_chats = match &mut known_chats {
+                    None => match list_chat_ids(&app_state, &limiter).await {
+                        Ok((chat_ids, _)) => {
+                            println!(
+                                "New chat automation '{}': Tracking {} existing chat(s)",
+                                automation.name,
+                                chat_ids.len()
+                            );
+                            known_chats = Some(chat_ids);
+                            Some(Vec::new())
+                        }
                         Err(e) => {
+                            record_stat(&stats, &automation, |s| s.errors += 1).await;
                             eprintln!(
-                                "Error accessing client for automation '{}', chat {}: {}",
-                                automation.name, chat_id, e
+                                "Error listing chats for automation '{}': {}",
+                                automation.name, e
+                            );
+                            None
+                        }
+                    },
+                    Some(known) => match fetch_chat_page(&app_state, &limiter, None).await {
+                        Ok((items, _, _)) => Some(take_new_chats(known, items, |chat| &chat.id)),
+                        Err(e) => {
+                            record_stat(&stats, &automation, |s| s.errors += 1).await;
+                            eprintln!(
+                                "Error listing chats for automation '{}': {}",
+                                automation.name, e
+                            );
+                            None
+                        }
+                    },
+                };
+
+                if let Some(new_chats) = new_chats {
+                    runtime_state
+                        .write()
+                        .await
+                        .automations
+                        .entry(automation.id.clone())
+                        .or_default()
+                        .known_chats = known_chats.clone();
+
+                    for chat in new_chats {
+                        let result =
+                            fetch_latest_message(&app_state, &poll_cache, &limiter, &chat.id).await;
+                        let latest = result.unwrap_or_else(|e| {
+                            tracing::warn!(
+                                "New chat automation '{}': Could not fetch messages for chat {}: {}",
+                                automation.name, chat.id, e
+                            );
+                            None
+                        });
+
+                        // Skip chats I started myself
+                        if automation.ignore_own_messages
+                            && latest.as_ref().is_some_and(|m| m.is_sender == Some(true))
+                        {
+                            tracing::debug!(
+                                "New chat automation '{}': Ignoring chat {} I started",
+                                automation.name,
+                                chat.id
+                            );
+                            continue;
+                        }
+
+                        if is_paused(&app_state) {
+                            tracing::info!(
+                                "New chat automation '{}': Paused, skipping actions for chat {}",
+                                automation.name,
+                                chat.id
+                            );
+                            continue;
+                        }
+
+                        if !is_in_schedule(&automation) {
+                            tracing::info!(
+                                "New chat automation '{}': Outside schedule, skipping actions for chat {}",
+                                automation.name,
+                                chat.id
+                            );
+                            continue;
+                        }
+
+                        if is_snoozed(&app_state, &automation) {
+                            tracing::info!(
+                                "New chat automation '{}': Snoozed, skipping actions for chat {}",
+                                automation.name,
+                                chat.id
                             );
+                            continue;
                         }
+
+                        println!(
+                            "New chat automation '{}': New chat {} ({})",
+                            automation.name, chat.title, chat.id
+                        );
+                        let context = match &latest {
+                            Some(message) => TriggerContext::new(
+                                &chat.id,
+                                Some(&chat.title),
+                                message,
+                                Some(chat.unread_count),
+                            ),
+                            None => TriggerContext {
+                                chat_id: chat.id.clone(),
+                                chat_name: chat.title.clone(),
+                                sender: "Unknown".to_string(),
+                                message_text: String::new(),
+                                unread_count: Some(chat.unread_count),
+                            },
+                        };
+                        record_trigger(&app_state, &stats, &runtime_state, &automation, &chat.id)
+                            .await;
+
+                        let actions_taken = fire_actions(
+                            &automation,
+                            &ActionContext {
+                                app_state: &app_state,
+                                limiter: &limiter,
+                                stats: &stats,
+                                trigger: &context,
+                                message_id: latest.as_ref().map(|m| m.id.as_str()),
+                                label: "New chat automation",
+                                dry_run,
+                            },
+                        )
+                        .await;
+                        record_history(
+                            history_file.as_deref(),
+                            &automation,
+                            &context,
+                            actions_taken,
+                            dry_run,
+                        );
                     }
                 }
 
-                // Wait 3 seconds before next check
-                tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+                tokio::time::sleep(next_poll_delay(poll_interval, jitter)).await;
             }
         })
     }
@@ -559,8 +2673,25 @@ impl NotificationService {
     fn start_loop_automation_static(
         app_state: SharedAppState,
         automation: NotificationAutomation,
+        dry_run: bool,
+        jitter: bool,
+        shared: SharedHandles,
     ) -> JoinHandle<()> {
         tokio::spawn(async move {
+            let SharedHandles {
+                stats,
+                poll_cache,
+                limiter,
+                startup_alerts,
+                quiet_requests,
+                runtime_state,
+                history_file,
+                ..
+            } = shared;
+
+            // Make sure the automation shows up in the summary even if it never fires
+            record_stat(&stats, &automation, |_| {}).await;
+
             use crate::notifications::models::LoopUntil;
             use std::collections::HashMap;
 
@@ -578,86 +2709,146 @@ impl NotificationService {
             };
 
             let check_interval = std::time::Duration::from_millis(loop_config.check_interval);
+            // Combined automations poll like immediate ones so new messages
+            // alert right away, and remind at the loop's check interval
+            let combined = automation.automation_type == AutomationType::Combined;
+            let poll_interval = if combined {
+                IMMEDIATE_POLL_INTERVAL
+            } else {
+                check_interval
+            };
+            if jitter {
+                tokio::time::sleep(initial_poll_delay(poll_interval)).await;
+            }
 
-            // Track last seen message and notification start time per chat
-            let mut last_messages: HashMap<String, LastMessageCache> = HashMap::new();
+            // Track last seen message and notification start time per chat,
+            // from where the last run left off
+            let mut last_messages = restore_progress(&runtime_state, &automation.id).await;
+            // When each chat's loop condition was last checked (combined only)
+            let mut last_checked: HashMap<String, std::time::Instant> = HashMap::new();
+            // Chats silenced by a quiet request until their next message, and
+            // the request last applied to each chat
+            let mut quieted: std::collections::HashSet<String> = Default::default();
+            let mut applied_quiet: HashMap<String, u64> = HashMap::new();
 
             loop {
-                // Check each chat in this automation
-                for chat_id in &automation.chat_ids {
-                    // Fetch latest message to check if it's new
-                    let message_result = app_state.with_client(|client| {
-                        tokio::task::block_in_place(|| {
-                            tokio::runtime::Handle::current()
-                                .block_on(async { client.list_messages(chat_id, None, None).await })
-                        })
-                    });
+                // Hold off while Beeper rejects the token
+                if API_HEALTH.polling_paused(std::time::Instant::now()) {
+                    tokio::time::sleep(poll_interval).await;
+                    continue;
+                }
 
-                    // Also fetch chat status for unread count
-                    let chat_result = app_state.with_client(|client| {
-                        tokio::task::block_in_place(|| {
-                            tokio::runtime::Handle::current()
-                                .block_on(async { client.list_chats(None, None).await })
-                        })
-                    });
+                // Look up every chat's unread count once per iteration
+                let chats = find_chats(
+                    &automation.chat_ids,
+                    |chat: &beeper_desktop_api::Chat| chat.id.as_str(),
+                    |cursor| fetch_chat_page(&app_state, &limiter, cursor),
+                )
+                .await;
+
+                // Poll every chat concurrently, then handle the results one
+                // chat at a time so the per-chat tracking stays consistent
+                let latest_messages =
+                    fetch_latest_messages(&app_state, &poll_cache, &limiter, &automation.chat_ids)
+                        .await;
 
-                    match (message_result, chat_result) {
-                        (Ok(Ok(messages_response)), Ok(Ok(chats_response))) => {
-                            if let Some(latest_message) = messages_response.items.first() {
+                // Check each chat in this automation
+                for (chat_id, message_result) in automation.chat_ids.iter().zip(latest_messages) {
+                    match (message_result, &chats) {
+                        (Ok(latest), Ok(chats)) => {
+                            if let Some(latest_message) = &latest {
                                 let current_sort_key = &latest_message.sort_key;
 
                                 // Check if this is a new message
-                                let is_new_message = match last_messages.get(chat_id) {
-                                    Some(cached) => &cached.sort_key < current_sort_key,
+                                let is_new_message = match last_messages.get_mut(chat_id) {
+                                    // For ForATime, a new message (re)starts the notification timer
+                                    Some(cached) => cached.observe(
+                                        &latest_message.id,
+                                        current_sort_key,
+                                        loop_config.until == LoopUntil::ForATime,
+                                        std::time::Instant::now(),
+                                    ),
                                     None => {
-                                        // First time seeing this chat, initialize
+                                        // First time seeing this chat, initialize. With
+                                        // notify_on_startup a ForATime window opens right away.
+                                        let start_now = automation.notify_on_startup
+                                            && loop_config.until == LoopUntil::ForATime
+                                            && latest_message.is_sender != Some(true)
+                                            && claim_startup_alert(
+                                                &startup_alerts,
+                                                &automation.id,
+                                                chat_id,
+                                                &latest_message.id,
+                                            )
+                                            .await;
                                         last_messages.insert(
                                             chat_id.clone(),
                                             LastMessageCache {
                                                 message_id: latest_message.id.clone(),
                                                 sort_key: current_sort_key.clone(),
-                                                notification_start_time: None,
+                                                notification_start_time: start_now
+                                                    .then(std::time::Instant::now),
                                             },
                                         );
                                         println!(
-                                            "Loop automation '{}': Initialized tracking for chat {}",
-                                            automation.name, chat_id
+                                            "Loop automation '{}': Initialized tracking for chat {}{}",
+                                            automation.name,
+                                            chat_id,
+                                            if start_now { ", started notification timer" } else { "" }
                                         );
                                         false // Don't treat first message as new
                                     }
                                 };
 
-                                if is_new_message {
-                                    // For ForATime, start the notification timer on new message
-                                    let start_time = if loop_config.until == LoopUntil::ForATime {
-                                        println!(
-                                            "Loop automation '{}': New message detected, started notification timer for chat {}",
-                                            automation.name, chat_id
-                                        );
-                                        Some(std::time::Instant::now())
-                                    } else {
-                                        None
-                                    };
+                                if is_new_message && loop_config.until == LoopUntil::ForATime {
+                                    println!(
+                                        "Loop automation '{}': New message detected, started notification timer for chat {}",
+                                        automation.name, chat_id
+                                    );
+                                }
 
-                                    // Update cache with new message
-                                    last_messages.insert(
-                                        chat_id.clone(),
-                                        LastMessageCache {
-                                            message_id: latest_message.id.clone(),
-                                            sort_key: current_sort_key.clone(),
-                                            notification_start_time: start_time,
-                                        },
+                                // A quiet request silences the chat until a newer message arrives
+                                let requested = quiet_requests.read().await.get(chat_id).copied();
+                                if let Some(requested) = requested
+                                    && applied_quiet.insert(chat_id.clone(), requested)
+                                        != Some(requested)
+                                {
+                                    quieted.insert(chat_id.clone());
+                                    if let Some(cached) = last_messages.get_mut(chat_id) {
+                                        cached.notification_start_time = None;
+                                    }
+                                    println!(
+                                        "🔕 Loop automation '{}': Quieted chat {} until its next message",
+                                        automation.name, chat_id
                                     );
+                                } else if is_new_message {
+                                    quieted.remove(chat_id);
                                 }
 
                                 // Find chat to check unread status
-                                if let Some(chat) =
-                                    chats_response.items.iter().find(|c| &c.id == chat_id)
-                                {
-                                    let should_notify = match loop_config.until {
+                                if let Some(chat) = chats.get(chat_id) {
+                                    let now = std::time::Instant::now();
+                                    let alert_now = combined
+                                        && is_new_message
+                                        && latest_message.is_sender != Some(true);
+                                    let check_due = !combined
+                                        || alert_now
+                                        || reminder_due(
+                                            last_checked.get(chat_id).copied(),
+                                            check_interval,
+                                            now,
+                                        );
+                                    if combined && check_due {
+                                        last_checked.insert(chat_id.clone(), now);
+                                    }
+
+                                    let should_notify = alert_now || (check_due && match loop_config.until {
                                         LoopUntil::MessageSeen => {
-                                            // Keep notifying while there are unread messages
-                                            let notify = chat.unread_count > 0;
+                                            // Keep notifying while enough messages are unread
+                                            let notify = meets_unread_threshold(
+                                                chat.unread_count,
+                                                loop_config.min_unread,
+                                            );
                                             tracing::debug!(
                                                 "Loop automation '{}': MessageSeen check for chat {} - unread: {}, notify: {}",
                                                 automation.name, chat_id, chat.unread_count, notify
@@ -686,147 +2877,932 @@ impl NotificationService {
                                         }
                                         LoopUntil::ForATime => {
                                             // Check if timer has started and not expired for this specific chat
-                                            if let Some(cached) = last_messages.get(chat_id) {
-                                                if let Some(start_time) =
-                                                    cached.notification_start_time
-                                                {
-                                                    if let Some(time_limit) = loop_config.time {
-                                                        if start_time.elapsed().as_millis()
-                                                            >= time_limit as u128
-                                                        {
-                                                            tracing::debug!(
-                                                                "Loop automation '{}': Time limit reached for chat {}, stopping notifications",
-                                                                automation.name, chat_id
-                                                            );
-                                                            // Reset timer by updating cache
-                                                            last_messages.insert(
-                                                                chat_id.clone(),
-                                                                LastMessageCache {
-                                                                    message_id: cached
-                                                                        .message_id
-                                                                        .clone(),
-                                                                    sort_key: cached
-                                                                        .sort_key
-                                                                        .clone(),
-                                                                    notification_start_time: None,
-                                                                },
-                                                            );
-                                                            false
-                                                        } else {
-                                                            true // Keep notifying until time runs out
-                                                        }
-                                                    } else {
-                                                        false // No time limit set, shouldn't happen
-                                                    }
-                                                } else {
-                                                    false // No new message yet, don't notify
-                                                }
-                                            } else {
-                                                false // Chat not in cache yet
+                                            let window = last_messages
+                                                .get_mut(chat_id)
+                                                .map(|cached| {
+                                                    cached.check_window(
+                                                        loop_config.time,
+                                                        std::time::Instant::now(),
+                                                    )
+                                                })
+                                                .unwrap_or(NotificationWindow::Idle);
+
+                                            if window == NotificationWindow::Ended {
+                                                println!(
+                                                    "⏹ Loop automation '{}': Notification window ended for chat {}",
+                                                    automation.name, chat_id
+                                                );
+                                                tracing::info!(
+                                                    "Loop automation '{}': Time limit reached for chat {}, stopping notifications until a new message",
+                                                    automation.name, chat_id
+                                                );
                                             }
+
+                                            window == NotificationWindow::Active
+                                        }
+                                        LoopUntil::Once => {
+                                            let notify =
+                                                fires_once(is_new_message, chat.unread_count > 0);
+                                            tracing::debug!(
+                                                "Loop automation '{}': Once check for chat {} - new: {}, unread: {}, notify: {}",
+                                                automation.name, chat_id, is_new_message, chat.unread_count, notify
+                                            );
+                                            notify
                                         }
-                                    };
+                                    });
+
+                                    if alert_now {
+                                        tracing::debug!(
+                                            "Loop automation '{}': New message in chat {}, alerting right away",
+                                            automation.name, chat_id
+                                        );
+                                    }
 
-                                    if should_notify {
+                                    if should_notify
+                                        && is_stale(
+                                            latest_message.sent_at,
+                                            automation.max_message_age_secs,
+                                            chrono::Utc::now(),
+                                        )
+                                    {
+                                        tracing::info!(
+                                            "Loop automation '{}': Latest message is too old, skipping actions for chat {}",
+                                            automation.name, chat_id
+                                        );
+                                    } else if should_notify
+                                        && !passes_mention_filter(&automation, latest_message)
+                                    {
+                                        tracing::debug!(
+                                            "Loop automation '{}': Latest message doesn't mention me, skipping actions for chat {}",
+                                            automation.name, chat_id
+                                        );
+                                    } else if should_notify && quieted.contains(chat_id) {
+                                        tracing::debug!(
+                                            "Loop automation '{}': Chat {} is quieted until its next message",
+                                            automation.name, chat_id
+                                        );
+                                    } else if should_notify && is_paused(&app_state) {
+                                        tracing::info!(
+                                            "Loop automation '{}': Paused, skipping actions for chat {}",
+                                            automation.name, chat_id
+                                        );
+                                    } else if should_notify && !is_in_schedule(&automation) {
+                                        tracing::info!(
+                                            "Loop automation '{}': Outside schedule, skipping actions for chat {}",
+                                            automation.name, chat_id
+                                        );
+                                    } else if should_notify && is_snoozed(&app_state, &automation) {
+                                        tracing::info!(
+                                            "Loop automation '{}': Snoozed, skipping actions for chat {}",
+                                            automation.name, chat_id
+                                        );
+                                    } else if should_notify {
                                         tracing::info!(
                                             "Loop automation '{}': Triggering actions for chat {} (unread: {})",
                                             automation.name, chat_id, chat.unread_count
                                         );
+                                        record_trigger(
+                                            &app_state,
+                                            &stats,
+                                            &runtime_state,
+                                            &automation,
+                                            chat_id,
+                                        )
+                                        .await;
+                                        let context = TriggerContext::new(
+                                            chat_id,
+                                            Some(&chat.title),
+                                            latest_message,
+                                            Some(chat.unread_count),
+                                        );
 
-                                        // Trigger focus action (only if user is active)
-                                        if automation.focus_chat {
-                                            if is_user_active() {
-                                                tracing::info!("User is active, proceeding with focus chat action for automation '{}'", automation.name);
-                                                let result = app_state.with_client(|client| {
-                                                    tokio::task::block_in_place(|| {
-                                                        tokio::runtime::Handle::current().block_on(
-                                                            async {
-                                                                use beeper_desktop_api::FocusAppInput;
-
-                                                                let focus_input = FocusAppInput {
-                                                                    chat_id: Some(chat_id.clone()),
-                                                                    message_id: None,
-                                                                    draft: None,
-                                                                };
-
-                                                                client
-                                                                    .focus_app(Some(focus_input))
-                                                                    .await
-                                                            },
-                                                        )
-                                                    })
-                                                });
-
-                                                match result {
-                                                    Ok(Ok(response)) => {
-                                                        if response.success {
-                                                            tracing::info!("Successfully focused chat {} for automation '{}'", chat_id, automation.name);
-                                                        }
-                                                    }
-                                                    Ok(Err(e)) => {
-                                                        tracing::error!("Error focusing chat {}: {}", chat_id, e);
-                                                        eprintln!(
-                                                            "Error focusing chat {}: {}",
-                                                            chat_id, e
-                                                        );
-                                                    }
-                                                    Err(e) => {
-                                                        tracing::error!("Error accessing client for focus: {}", e);
-                                                        eprintln!(
-                                                            "Error accessing client for focus: {}",
-                                                            e
-                                                        );
-                                                    }
-                                                }
-                                            } else {
-                                                tracing::info!("User is idle, skipping focus chat action for automation '{}'", automation.name);
-                                            }
-                                        }
-
-                                        // Trigger notification sound if configured
-                                        if let Some(sound_path) = &automation.notification_sound {
-                                            if !sound_path.is_empty() {
-                                                println!(
-                                                    "▶ Playing notification sound: {}",
-                                                    sound_path
-                                                );
-                                                play_sound(sound_path);
-                                            }
-                                        }
-
-                                        // Trigger ntfy notification if configured
-                                        if let Some(ntfy_config) = &automation.ntfy_config {
-                                            let sender = latest_message.sender_name.as_deref().unwrap_or("Unknown");
-                                            let chat_name = chat.title.as_str();
-                                            send_ntfy_notification(
-                                                ntfy_config,
-                                                &automation.name,
-                                                sender,
-                                                chat_name,
-                                            );
-                                        }
+                                        let actions_taken = fire_actions(
+                                            &automation,
+                                            &ActionContext {
+                                                app_state: &app_state,
+                                                limiter: &limiter,
+                                                stats: &stats,
+                                                trigger: &context,
+                                                message_id: Some(&latest_message.id),
+                                                label: "Loop automation",
+                                                dry_run,
+                                            },
+                                        )
+                                        .await;
+                                        record_history(
+                                            history_file.as_deref(),
+                                            &automation,
+                                            &context,
+                                            actions_taken,
+                                            dry_run,
+                                        );
                                     }
                                 }
                             }
                         }
-                        (Ok(Err(e)), _) | (_, Ok(Err(e))) => {
+                        (Err(e), _) => {
+                            record_stat(&stats, &automation, |s| s.errors += 1).await;
                             eprintln!(
-                                "Error fetching data for automation {}: {}",
+                                "Error fetching messages for automation {}: {}",
                                 automation.name, e
                             );
                         }
-                        (Err(e), _) | (_, Err(e)) => {
+                        (_, Err(e)) => {
+                            record_stat(&stats, &automation, |s| s.errors += 1).await;
                             eprintln!(
-                                "Error accessing client for automation {}: {}",
+                                "Error fetching chats for automation {}: {}",
                                 automation.name, e
                             );
                         }
                     }
                 }
 
+                record_progress(
+                    &runtime_state,
+                    &automation,
+                    &last_messages,
+                    keep_removed_chats_ms(&app_state),
+                )
+                .await;
+
                 // Wait for the configured check interval
-                tokio::time::sleep(check_interval).await;
+                sleep_until_next_poll(
+                    &app_state,
+                    &poll_cache,
+                    &limiter,
+                    &automation,
+                    &mut last_messages,
+                    next_poll_delay(poll_interval, jitter),
+                )
+                .await;
             }
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flapping_counts_alerts_in_the_last_minute() {
+        let mut stats = AutomationStats::default();
+        let start = std::time::Instant::now();
+        let second = std::time::Duration::from_secs(1);
+
+        for i in 0..3 {
+            assert_eq!(stats.record_trigger(start + i * second), i as usize + 1);
+        }
+        assert!(stats.is_flapping(2, start + 2 * second));
+        assert!(!stats.is_flapping(3, start + 2 * second));
+        assert!(!stats.is_flapping(0, start + 2 * second), "0 turns it off");
+
+        // Alerts drop out of the window as it moves on
+        assert!(!stats.is_flapping(2, start + FLAPPING_WINDOW));
+        assert_eq!(stats.record_trigger(start + FLAPPING_WINDOW + second), 2);
+        assert_eq!(stats.triggered, 4);
+    }
+
+    #[test]
+    fn test_reload_restarts_only_changed_tasks() {
+        let mut automation = NotificationAutomation::new("a".into(), "A".into(), vec![]);
+        let settings = task_settings(&automation, false, true);
+
+        automation.snoozed_until = Some(std::time::SystemTime::now());
+        assert_eq!(task_settings(&automation, false, true), settings);
+
+        assert_ne!(task_settings(&automation, true, true), settings);
+        automation.chat_ids.push("chat".into());
+        assert_ne!(task_settings(&automation, false, true), settings);
+    }
+
+    #[test]
+    fn test_trigger_context_render() {
+        let message = PolledMessage {
+            id: "m1".to_string(),
+            sort_key: "1".to_string(),
+            sender_name: None,
+            text: Some("hello".to_string()),
+            is_sender: Some(false),
+            sent_at: None,
+            mentions_me: None,
+        };
+
+        let context = TriggerContext::new("!chat", None, &message, Some(3));
+        assert_eq!(
+            context.render("{sender} in {chat_name}: {message} ({unread_count}) [{automation_name}]", "Work"),
+            "Unknown in !chat: hello (3) [Work]"
+        );
+    }
+
+    #[test]
+    fn test_render_template() {
+        let vars = [("sender", "Ada"), ("message", "use {sender} here"), ("unread", "2")];
+
+        assert_eq!(
+            render_template("{sender}: {message} ({unread})", &vars),
+            "Ada: use {sender} here (2)"
+        );
+        // Placeholders that aren't in the template are simply unused
+        assert_eq!(render_template("New message", &vars), "New message");
+        // Unknown tokens and stray braces stay literal
+        assert_eq!(
+            render_template("{nope} {sender} {{sender}} {", &vars),
+            "{nope} Ada {Ada} {"
+        );
+    }
+
+    #[test]
+    fn test_sort_key_is_newer() {
+        // Plain string comparison gets this wrong: "9" > "10"
+        assert!(sort_key_is_newer("9", "10"));
+        assert!(!sort_key_is_newer("10", "9"));
+        assert!(!sort_key_is_newer("10", "10"));
+
+        // Non-numeric keys compare by length, then lexically
+        assert!(sort_key_is_newer("a9", "a10"));
+        assert!(sort_key_is_newer("abc", "abd"));
+        assert!(!sort_key_is_newer("abd", "abc"));
+    }
+
+    #[test]
+    fn test_for_a_time_window_restarts_on_new_message() {
+        use std::time::{Duration, Instant};
+
+        let t0 = Instant::now();
+        let limit = Some(1000);
+        let mut cache = LastMessageCache {
+            message_id: "m1".to_string(),
+            sort_key: "1".to_string(),
+            notification_start_time: None,
+        };
+
+        // Nothing new yet
+        assert_eq!(cache.check_window(limit, t0), NotificationWindow::Idle);
+        assert!(!cache.observe("m1", "1", true, t0));
+
+        // New message -> notify until the window runs out
+        assert!(cache.observe("m2", "2", true, t0));
+        assert_eq!(cache.check_window(limit, t0 + Duration::from_millis(500)), NotificationWindow::Active);
+
+        // Expiry is reported exactly once
+        assert_eq!(cache.check_window(limit, t0 + Duration::from_millis(1000)), NotificationWindow::Ended);
+        assert_eq!(cache.check_window(limit, t0 + Duration::from_millis(1500)), NotificationWindow::Idle);
+
+        // Another genuinely new message restarts the timer
+        let t1 = t0 + Duration::from_secs(5);
+        assert!(cache.observe("m3", "3", true, t1));
+        assert_eq!(cache.check_window(limit, t1 + Duration::from_millis(10)), NotificationWindow::Active);
+        assert_eq!(cache.message_id, "m3");
+    }
+
+    #[test]
+    fn test_once_fires_a_single_time_per_new_message() {
+        let now = std::time::Instant::now();
+        let mut cache = LastMessageCache {
+            message_id: "m1".to_string(),
+            sort_key: "1".to_string(),
+            notification_start_time: None,
+        };
+
+        // Each poll sees the chat's latest message; the chat stays unread throughout
+        let polls = ["1", "2", "2", "2", "3", "3"];
+        let fired: Vec<&str> = polls
+            .iter()
+            .filter(|sort_key| {
+                let is_new = cache.observe(&format!("m{}", sort_key), sort_key, false, now);
+                fires_once(is_new, true)
+            })
+            .copied()
+            .collect();
+        assert_eq!(fired, vec!["2", "3"]);
+
+        // A message read before the check saw it doesn't fire at all
+        let is_new = cache.observe("m4", "4", false, now);
+        assert!(is_new);
+        assert!(!fires_once(is_new, false));
+    }
+
+    #[test]
+    fn test_combined_reminder_due() {
+        let now = std::time::Instant::now();
+        let interval = std::time::Duration::from_secs(60);
+
+        // A chat that was never checked is checked on the first poll
+        assert!(reminder_due(None, interval, now));
+        // Polls in between only look for new messages
+        assert!(!reminder_due(
+            Some(now),
+            interval,
+            now + IMMEDIATE_POLL_INTERVAL
+        ));
+        assert!(reminder_due(Some(now), interval, now + interval));
+    }
+
+    #[test]
+    fn test_stale_message_with_new_sort_key() {
+        let now = chrono::Utc::now();
+        let mut cache = LastMessageCache {
+            message_id: "m1".to_string(),
+            sort_key: "100".to_string(),
+            notification_start_time: None,
+        };
+
+        // Backfilled history: the sort key is newer, but it was sent hours ago
+        let sent_at = Some(now - chrono::Duration::hours(3));
+        assert!(cache.observe("m0", "101", false, std::time::Instant::now()));
+        assert!(is_stale(sent_at, Some(600), now));
+
+        // Without a limit, or for a recent message, nothing is skipped
+        assert!(!is_stale(sent_at, None, now));
+        assert!(!is_stale(Some(now - chrono::Duration::seconds(30)), Some(600), now));
+        assert!(!is_stale(None, Some(600), now));
+    }
+
+    #[test]
+    fn test_mention_filter() {
+        let mut automation =
+            NotificationAutomation::new("a".to_string(), "Team".to_string(), vec![]);
+        let mut message = PolledMessage {
+            id: "m1".to_string(),
+            sort_key: "1".to_string(),
+            sender_name: None,
+            text: Some("lunch?".to_string()),
+            is_sender: Some(false),
+            sent_at: None,
+            mentions_me: None,
+        };
+        assert!(passes_mention_filter(&automation, &message));
+
+        // Fallback: whole-word match on the configured names
+        automation.mentions_only = true;
+        automation.mention_names = vec!["@erdem".to_string(), "Erdem G".to_string()];
+        assert!(!passes_mention_filter(&automation, &message));
+        message.text = Some("@Erdem can you look?".to_string());
+        assert!(passes_mention_filter(&automation, &message));
+        message.text = Some("thanks erdem g!".to_string());
+        assert!(passes_mention_filter(&automation, &message));
+        message.text = Some("ask erdemir".to_string());
+        assert!(!passes_mention_filter(&automation, &message));
+
+        // Mention data from the API wins over the text
+        message.mentions_me = Some(false);
+        message.text = Some("@erdem".to_string());
+        assert!(!passes_mention_filter(&automation, &message));
+        message.mentions_me = Some(true);
+        message.text = None;
+        assert!(passes_mention_filter(&automation, &message));
+    }
+
+    #[test]
+    fn test_webhook_payload() {
+        let automation = NotificationAutomation::new(
+            "work".to_string(),
+            "Work".to_string(),
+            vec!["chat1".to_string()],
+        );
+        let context = TriggerContext {
+            chat_id: "chat1".to_string(),
+            chat_name: "Team".to_string(),
+            sender: "Alice".to_string(),
+            message_text: "Deploy is done".to_string(),
+            unread_count: Some(2),
+        };
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2025-06-02T09:55:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        let payload = serde_json::to_value(WebhookPayload::new(&automation, &context, timestamp));
+        assert_eq!(
+            payload.unwrap(),
+            serde_json::json!({
+                "automation": "Work",
+                "chat_id": "chat1",
+                "chat_name": "Team",
+                "sender": "Alice",
+                "message": "Deploy is done",
+                "unread": 2,
+                "timestamp": "2025-06-02T09:55:00+00:00",
+            })
+        );
+    }
+
+    #[test]
+    fn test_ntfy_fallback_when_sound_fails() {
+        use crate::notifications::models::NtfyConfig;
+
+        let off = NtfyConfig {
+            url: "https://ntfy.sh/me".to_string(),
+            ..Default::default()
+        };
+        // Switched off means off unless the fallback was asked for
+        assert!(!ntfy_for_alert(Some(&off), true).unwrap().enabled);
+
+        let fallback = NtfyConfig {
+            fallback_when_no_audio: true,
+            ..off
+        };
+        assert!(!ntfy_for_alert(Some(&fallback), false).unwrap().enabled);
+        assert!(ntfy_for_alert(Some(&fallback), true).unwrap().enabled);
+
+        // Not set up: nothing to fall back to
+        assert!(!ntfy_for_alert(Some(&NtfyConfig::default()), true).unwrap().enabled);
+        assert!(ntfy_for_alert(None, true).is_none());
+
+        // The desktop notification falls back under the same opt-in
+        let mut automation = NotificationAutomation::new("a".into(), "A".into(), vec![]);
+        assert!(!desktop_for_alert(&automation, true));
+        automation.ntfy_config = Some(fallback);
+        assert!(!desktop_for_alert(&automation, false));
+        assert!(desktop_for_alert(&automation, true));
+        automation.desktop_notification = true;
+        assert!(desktop_for_alert(&automation, false));
+    }
+
+    #[test]
+    fn test_unread_threshold() {
+        // Default behaves like the old `unread_count > 0` check
+        assert!(!meets_unread_threshold(0, None));
+        assert!(meets_unread_threshold(1, None));
+        assert!(!meets_unread_threshold(0, Some(0)));
+
+        assert!(!meets_unread_threshold(2, Some(3)));
+        assert!(meets_unread_threshold(3, Some(3)));
+        assert!(meets_unread_threshold(7, Some(3)));
+    }
+
+    #[test]
+    fn test_poll_delay_jitter_bounds() {
+        let interval = std::time::Duration::from_secs(3);
+        assert_eq!(next_poll_delay(interval, false), interval);
+
+        for _ in 0..100 {
+            assert!(initial_poll_delay(interval) < interval);
+            let delay = next_poll_delay(interval, true);
+            assert!(delay >= interval.mul_f64(0.9) && delay < interval.mul_f64(1.1));
+        }
+    }
+
+    #[test]
+    fn test_wake_gap_reseeds_without_alerting() {
+        let before = std::time::SystemTime::now();
+        let delay = std::time::Duration::from_secs(3);
+
+        assert!(!is_wake_gap(before, before + delay, delay));
+        assert!(!is_wake_gap(before, before + delay * 10, delay));
+        // An hour-long suspend, and the clock being set back an hour
+        let hour = std::time::Duration::from_secs(3600);
+        assert!(is_wake_gap(before, before + hour, delay));
+        assert!(is_wake_gap(before, before - hour, delay));
+
+        // A ForATime window open when the machine went to sleep is closed,
+        // and the message that arrived meanwhile counts as seen
+        let start = std::time::Instant::now();
+        let mut cache = LastMessageCache {
+            message_id: "m1".to_string(),
+            sort_key: "1".to_string(),
+            notification_start_time: Some(start),
+        };
+        cache.reseed("m2", "2");
+        assert!(!cache.observe("m2", "2", true, start));
+        let window = cache.check_window(Some(1000), start);
+        assert_eq!(window, NotificationWindow::Idle);
+        assert!(cache.observe("m3", "3", true, start));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_late_config_starts_immediate_watcher() {
+        // The service starts before the API is configured, as on a fresh install
+        let app_state = SharedAppState::new(Config::default());
+        let (reload_tx, reload_rx) = tokio::sync::mpsc::channel(1);
+        let service = NotificationService::with_files(app_state, reload_rx, ServiceFiles::default());
+        assert!(service.automation_tasks.read().await.is_empty());
+
+        let mut config = Config::default();
+        config.api.url = "http://127.0.0.1:9".to_string();
+        config.api.token = "token".to_string().into();
+        let automation = NotificationAutomation::new(
+            "work".to_string(),
+            "Work".to_string(),
+            vec!["chat1".to_string()],
+        );
+        config.notifications.automations.push(automation);
+        reload_tx.send(config).await.unwrap();
+
+        let started = async {
+            loop {
+                let tasks = service.automation_tasks.read().await;
+                if let Some(task) = tasks.iter().find(|t| t.automation_id == "work") {
+                    assert!(!task.handle.is_finished());
+                    break;
+                }
+                drop(tasks);
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(5), started)
+            .await
+            .expect("immediate watcher never started");
+    }
+
+    #[tokio::test]
+    async fn test_find_chats_request_count() {
+        // 12 chats over 4 pages of 3; the automation watches chats 1, 4 and 5
+        let pages: Vec<Vec<String>> = (0..4)
+            .map(|page| (0..3).map(|i| format!("chat{}", page * 3 + i)).collect())
+            .collect();
+        let fetch_counted = |calls: &std::cell::Cell<usize>, cursor: Option<String>| {
+            calls.set(calls.get() + 1);
+            let page: usize = cursor.map_or(0, |c| c.parse().unwrap());
+            let has_more = page + 1 < pages.len();
+            let next = has_more.then(|| (page + 1).to_string());
+            std::future::ready(Ok((pages[page].clone(), next, has_more)))
+        };
+        fn id_of(chat: &String) -> &str {
+            chat
+        }
+
+        let calls = std::cell::Cell::new(0);
+        let watched: Vec<String> = ["chat1", "chat4", "chat5"].map(String::from).to_vec();
+        let found = find_chats(&watched, id_of, |cursor| fetch_counted(&calls, cursor))
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 3);
+        assert_eq!(calls.get(), 2, "stops once all chats are found");
+
+        let calls = std::cell::Cell::new(0);
+        let first_page: Vec<String> = ["chat0", "chat2"].map(String::from).to_vec();
+        find_chats(&first_page, id_of, |cursor| fetch_counted(&calls, cursor))
+            .await
+            .unwrap();
+        assert_eq!(calls.get(), 1);
+
+        let calls = std::cell::Cell::new(0);
+        let missing = vec!["gone".to_string()];
+        let found = find_chats(&missing, id_of, |cursor| fetch_counted(&calls, cursor))
+            .await
+            .unwrap();
+        assert!(found.is_empty());
+        assert_eq!(calls.get(), pages.len(), "stops at the last page");
+    }
+
+    fn mock_handles() -> SharedHandles {
+        SharedHandles {
+            stats: Default::default(),
+            poll_cache: Default::default(),
+            limiter: Arc::new(RateLimiter::new(None)),
+            startup_alerts: Default::default(),
+            quiet_requests: Default::default(),
+            runtime_state: Default::default(),
+            state_file: None,
+            history_file: None,
+        }
+    }
+
+    async fn triggered(shared: &SharedHandles, automation_id: &str) -> u64 {
+        shared
+            .stats
+            .read()
+            .await
+            .get(automation_id)
+            .map_or(0, |stats| stats.triggered)
+    }
+
+    /// Let the next poll see fresh data. Paused Tokio time doesn't move the
+    /// real clock the poll and chat list caches expire by.
+    async fn expire_caches(app_state: &SharedAppState, shared: &SharedHandles) {
+        shared.poll_cache.write().await.clear();
+        app_state.invalidate_chats();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_immediate_watcher_alerts_on_new_messages() {
+        use crate::beeper_api::mock::MockApi;
+
+        let api = Arc::new(MockApi::default());
+        api.add_chat("chat1", "Friend");
+        api.push_message("chat1", "m1", false);
+        let app_state = SharedAppState::with_api(Config::default(), api.clone());
+        let shared = mock_handles();
+        let automation = NotificationAutomation::new(
+            "work".to_string(),
+            "Work".to_string(),
+            vec!["chat1".to_string()],
+        );
+        let handle = NotificationService::start_immediate_automation_static(
+            app_state.clone(),
+            automation,
+            false,
+            false,
+            shared.clone(),
+        );
+
+        // The message already there when the watcher starts only seeds tracking
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        assert_eq!(triggered(&shared, "work").await, 0);
+
+        api.push_message("chat1", "m2", false);
+        expire_caches(&app_state, &shared).await;
+        tokio::time::sleep(IMMEDIATE_POLL_INTERVAL).await;
+        assert_eq!(triggered(&shared, "work").await, 1);
+
+        // My own reply is new too, but doesn't alert
+        api.push_message("chat1", "m3", true);
+        expire_caches(&app_state, &shared).await;
+        tokio::time::sleep(IMMEDIATE_POLL_INTERVAL).await;
+        assert_eq!(triggered(&shared, "work").await, 1);
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_immediate_context_has_chat_name_and_unread() {
+        use crate::beeper_api::mock::MockApi;
+
+        let api = Arc::new(MockApi::default());
+        api.add_chat("chat1", "Friend");
+        api.push_message("chat1", "m1", false);
+        api.push_message("chat1", "m2", false);
+        let app_state = SharedAppState::with_api(Config::default(), api.clone());
+        let limiter = RateLimiter::new(None);
+        let message = fetch_latest_message(&app_state, &Default::default(), &limiter, "chat1")
+            .await
+            .unwrap()
+            .unwrap();
+
+        let context = immediate_trigger_context(&app_state, &limiter, "chat1", &message).await;
+        assert_eq!(context.render("{chat_name}: {unread}", "Work"), "Friend: 2");
+
+        // A chat missing from the list still alerts, under its id
+        let context = immediate_trigger_context(&app_state, &limiter, "gone", &message).await;
+        assert_eq!(context.render("{chat_name}", "Work"), "gone");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_watcher_resumes_from_saved_progress() {
+        use crate::beeper_api::mock::MockApi;
+
+        let api = Arc::new(MockApi::default());
+        api.add_chat("chat1", "Friend");
+        api.push_message("chat1", "m1", false);
+        let app_state = SharedAppState::with_api(Config::default(), api.clone());
+        let shared = mock_handles();
+        let automation = NotificationAutomation::new(
+            "work".to_string(),
+            "Work".to_string(),
+            vec!["chat1".to_string()],
+        );
+
+        // The last run got as far as m1; m2 arrived while it was stopped
+        let mut last_messages = HashMap::new();
+        last_messages.insert(
+            "chat1".to_string(),
+            LastMessageCache {
+                message_id: "m1".to_string(),
+                sort_key: "00000001".to_string(),
+                notification_start_time: None,
+            },
+        );
+        record_progress(&shared.runtime_state, &automation, &last_messages, 0).await;
+        api.push_message("chat1", "m2", false);
+
+        let handle = NotificationService::start_immediate_automation_static(
+            app_state.clone(),
+            automation,
+            false,
+            false,
+            shared.clone(),
+        );
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        assert_eq!(triggered(&shared, "work").await, 1);
+
+        let state = shared.runtime_state.read().await;
+        assert_eq!(state.automations["work"].chats["chat1"].message_id, "m2");
+        drop(state);
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_removed_chat_progress_is_kept_for_grace_period() {
+        let shared = mock_handles();
+        let mut automation = NotificationAutomation::new(
+            "work".to_string(),
+            "Work".to_string(),
+            vec!["chat1".to_string(), "chat2".to_string()],
+        );
+        let mut last_messages = HashMap::new();
+        for chat_id in ["chat1", "chat2"] {
+            last_messages.insert(
+                chat_id.to_string(),
+                LastMessageCache {
+                    message_id: "m1".to_string(),
+                    sort_key: "00000001".to_string(),
+                    notification_start_time: None,
+                },
+            );
+        }
+        let hour = 60 * 60 * 1000;
+        record_progress(&shared.runtime_state, &automation, &last_messages, hour).await;
+
+        // chat1 is removed; the restarted watcher still restores it
+        automation.chat_ids = vec!["chat2".to_string()];
+        let restored = restore_progress(&shared.runtime_state, "work").await;
+        record_progress(&shared.runtime_state, &automation, &restored, hour).await;
+
+        // Re-added within the hour, it resumes from m1
+        let restored = restore_progress(&shared.runtime_state, "work").await;
+        assert_eq!(restored["chat1"].message_id, "m1");
+
+        // Without a grace period it's forgotten at once
+        record_progress(&shared.runtime_state, &automation, &restored, 0).await;
+        let restored = restore_progress(&shared.runtime_state, "work").await;
+        assert!(!restored.contains_key("chat1"));
+        assert!(restored.contains_key("chat2"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_new_chat_watcher_alerts_on_new_chats() {
+        use crate::beeper_api::mock::MockApi;
+
+        let api = Arc::new(MockApi::default());
+        api.add_chat("chat1", "Friend");
+        let app_state = SharedAppState::with_api(Config::default(), api.clone());
+        let shared = mock_handles();
+        let mut automation =
+            NotificationAutomation::new("new".to_string(), "New".to_string(), vec![]);
+        automation.automation_type = AutomationType::NewChat;
+        let handle = NotificationService::start_automation_static(
+            app_state.clone(),
+            automation.clone(),
+            false,
+            false,
+            shared.clone(),
+        );
+
+        // Chats there when the watcher starts only seed the known set
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        assert_eq!(triggered(&shared, "new").await, 0);
+
+        api.add_chat("chat2", "Stranger");
+        api.push_message("chat2", "m1", false);
+        expire_caches(&app_state, &shared).await;
+        tokio::time::sleep(IMMEDIATE_POLL_INTERVAL).await;
+        assert_eq!(triggered(&shared, "new").await, 1);
+
+        // A chat I started doesn't alert
+        api.add_chat("chat3", "Shop");
+        api.push_message("chat3", "m2", true);
+        expire_caches(&app_state, &shared).await;
+        tokio::time::sleep(IMMEDIATE_POLL_INTERVAL).await;
+        assert_eq!(triggered(&shared, "new").await, 1);
+        handle.abort();
+
+        // The known set is kept, so a restart doesn't alert for chat2 again
+        let state = shared.runtime_state.read().await;
+        let known_chats = state.automations["new"]
+            .known_chats
+            .clone()
+            .unwrap_or_default();
+        assert_eq!(known_chats.len(), 3);
+        drop(state);
+        let handle = NotificationService::start_automation_static(
+            app_state.clone(),
+            automation,
+            false,
+            false,
+            shared.clone(),
+        );
+        expire_caches(&app_state, &shared).await;
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        assert_eq!(triggered(&shared, "new").await, 1);
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_fire_test_alert_leaves_chat_unread() {
+        use crate::beeper_api::{BeeperApi, mock::MockApi};
+
+        let api = Arc::new(MockApi::default());
+        api.add_chat("chat1", "Friend");
+        api.push_message("chat1", "m1", false);
+        let app_state = SharedAppState::with_api(Config::default(), api.clone());
+        let mut automation = NotificationAutomation::new(
+            "work".to_string(),
+            "Work".to_string(),
+            vec!["chat1".to_string()],
+        );
+        automation.desktop_notification = true;
+        automation.mark_read = true;
+
+        // A test alert has no real message, so there's nothing to mark read
+        let actions = fire_test_alert(&app_state, &automation).await;
+        let expected: &[&str] = if desktop::SUPPORTED { &["desktop"] } else { &[] };
+        assert_eq!(actions, expected);
+        let chats = api.list_chats(None).await.unwrap();
+        assert_eq!(chats.items[0].unread_count, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_loop_watcher_stops_once_read() {
+        use crate::beeper_api::mock::MockApi;
+        use crate::notifications::models::{LoopConfig, LoopUntil};
+
+        let api = Arc::new(MockApi::default());
+        api.add_chat("chat1", "Friend");
+        api.push_message("chat1", "m1", false);
+        let app_state = SharedAppState::with_api(Config::default(), api.clone());
+        let shared = mock_handles();
+        let mut automation = NotificationAutomation::new(
+            "nag".to_string(),
+            "Nag".to_string(),
+            vec!["chat1".to_string()],
+        );
+        automation.automation_type = AutomationType::Loop;
+        automation.loop_config = Some(LoopConfig {
+            until: LoopUntil::MessageSeen,
+            time: None,
+            check_interval: 1000,
+            min_unread: None,
+        });
+        let handle = NotificationService::start_loop_automation_static(
+            app_state.clone(),
+            automation,
+            false,
+            false,
+            shared.clone(),
+        );
+        let second = std::time::Duration::from_secs(1);
+
+        // Checks at 0s and 1s both find the chat unread
+        tokio::time::sleep(second * 3 / 2).await;
+        assert_eq!(triggered(&shared, "nag").await, 2);
+
+        // Reading the chat stops the reminders
+        api.mark_chat_read("chat1");
+        expire_caches(&app_state, &shared).await;
+        tokio::time::sleep(second * 2).await;
+        assert_eq!(triggered(&shared, "nag").await, 2);
+
+        // A new message starts them again
+        api.push_message("chat1", "m2", false);
+        expire_caches(&app_state, &shared).await;
+        tokio::time::sleep(second).await;
+        assert_eq!(triggered(&shared, "nag").await, 3);
+
+        handle.abort();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_quiet_request_silences_loop_until_next_message() {
+        use crate::beeper_api::mock::MockApi;
+        use crate::notifications::models::{LoopConfig, LoopUntil};
+
+        let api = Arc::new(MockApi::default());
+        api.add_chat("chat1", "Friend");
+        api.push_message("chat1", "m1", false);
+        let app_state = SharedAppState::with_api(Config::default(), api.clone());
+        let shared = mock_handles();
+        let mut automation = NotificationAutomation::new(
+            "nag".to_string(),
+            "Nag".to_string(),
+            vec!["chat1".to_string()],
+        );
+        automation.automation_type = AutomationType::Loop;
+        automation.loop_config = Some(LoopConfig {
+            until: LoopUntil::MessageSeen,
+            time: None,
+            check_interval: 1000,
+            min_unread: None,
+        });
+        let handle = NotificationService::start_loop_automation_static(
+            app_state.clone(),
+            automation,
+            false,
+            false,
+            shared.clone(),
+        );
+        let second = std::time::Duration::from_secs(1);
+
+        tokio::time::sleep(second / 2).await;
+        assert_eq!(triggered(&shared, "nag").await, 1);
+
+        // The chat is still unread, but quieted
+        shared
+            .quiet_requests
+            .write()
+            .await
+            .insert("chat1".to_string(), quiet::now_ms());
+        tokio::time::sleep(second * 2).await;
+        assert_eq!(triggered(&shared, "nag").await, 1);
+
+        // The next message lifts it
+        api.push_message("chat1", "m2", false);
+        expire_caches(&app_state, &shared).await;
+        tokio::time::sleep(second).await;
+        assert_eq!(triggered(&shared, "nag").await, 2);
+
+        handle.abort();
+    }
+}
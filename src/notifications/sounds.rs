@@ -0,0 +1,54 @@
+// Alert sounds bundled into the binary, so an automation can make a noise
+// without the user finding sound files first
+
+/// Prefix that marks a `notification_sound` as a bundled sound, as in `builtin:chime`
+pub const BUILTIN_PREFIX: &str = "builtin:";
+
+/// Bundled sounds by name, as WAV data
+pub const BUILTIN_SOUNDS: [(&str, &[u8]); 3] = [
+    ("chime", include_bytes!("../../assets/sounds/chime.wav")),
+    ("ping", include_bytes!("../../assets/sounds/ping.wav")),
+    ("alarm", include_bytes!("../../assets/sounds/alarm.wav")),
+];
+
+/// Whether a sound setting refers to a bundled sound rather than a file
+pub fn is_builtin(sound: &str) -> bool {
+    sound.starts_with(BUILTIN_PREFIX)
+}
+
+/// The data of the bundled sound a setting names. `None` for file paths and
+/// for names that aren't bundled.
+pub fn builtin(sound: &str) -> Option<&'static [u8]> {
+    let name = sound.strip_prefix(BUILTIN_PREFIX)?.trim();
+    BUILTIN_SOUNDS
+        .iter()
+        .find(|(builtin, _)| builtin.eq_ignore_ascii_case(name))
+        .map(|(_, data)| *data)
+}
+
+/// The setting for each bundled sound, e.g. `builtin:chime`
+pub fn builtin_settings() -> impl Iterator<Item = String> {
+    BUILTIN_SOUNDS
+        .iter()
+        .map(|(name, _)| format!("{}{}", BUILTIN_PREFIX, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_lookup() {
+        for setting in builtin_settings() {
+            let data = builtin(&setting).unwrap();
+            assert_eq!(&data[..4], b"RIFF");
+            assert_eq!(&data[8..12], b"WAVE");
+        }
+
+        assert!(builtin("builtin:Chime").is_some());
+        assert!(builtin("builtin:siren").is_none());
+        assert!(builtin("chime").is_none());
+        assert!(is_builtin("builtin:siren"));
+        assert!(!is_builtin("sounds/chime.wav"));
+    }
+}
@@ -0,0 +1,202 @@
+// Runtime state: how far each automation has got in each chat, kept apart
+// from config.toml so the config only ever holds what the user set.
+//
+// The service loads `state.json` from the config directory when it starts,
+// and writes it back on every config reload and at shutdown. Watchers pick
+// up from the saved progress, so a message that arrived while the service
+// was stopped or restarting still alerts, and a running `for_a_time` window
+// carries on where it was.
+
+use crate::config::{Config, ConfigError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Progress older than this is dropped when loading; after a long break the
+/// service starts fresh rather than alerting for everything it missed
+const KEEP_PROGRESS_MS: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeState {
+    /// Progress per automation id
+    #[serde(default)]
+    pub automations: HashMap<String, AutomationState>,
+    /// When the service found Beeper rejecting the API token, in Unix
+    /// milliseconds, if no request has worked since
+    #[serde(default)]
+    pub token_rejected_ms: Option<u64>,
+    /// When each automation snoozed for flapping wakes up, in Unix
+    /// milliseconds. The config file doesn't hold these snoozes, so they are
+    /// applied again to every config the service loads.
+    #[serde(default)]
+    pub flapping_snoozes: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AutomationState {
+    /// Progress per chat id
+    #[serde(default)]
+    pub chats: HashMap<String, ChatProgress>,
+    /// Chat ids a new chat automation has already seen in the chat list.
+    /// `None` until its first listing, which is taken in without alerting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub known_chats: Option<BTreeSet<String>>,
+}
+
+/// Where an automation is up to in one chat
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatProgress {
+    /// Latest message the automation has seen
+    pub message_id: String,
+    pub sort_key: String,
+    /// When the running `for_a_time` window opened, in Unix milliseconds
+    #[serde(default)]
+    pub window_started_ms: Option<u64>,
+    /// When this was recorded, in Unix milliseconds
+    pub updated_ms: u64,
+}
+
+impl RuntimeState {
+    /// Get runtime state file path
+    pub fn state_file_path() -> Result<PathBuf, ConfigError> {
+        Ok(Config::config_dir()?.join("state.json"))
+    }
+
+    /// Load the state saved in `path`, without progress older than a day. A
+    /// missing file means a fresh start; an unreadable one is reported and
+    /// treated the same.
+    pub fn load_from(path: &Path, now_ms: u64) -> Self {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                tracing::warn!("Could not read runtime state {:?}: {}", path, e);
+                return Self::default();
+            }
+        };
+
+        let mut state: Self = match serde_json::from_str(&content) {
+            Ok(state) => state,
+            Err(e) => {
+                eprintln!("⚠ Ignoring unreadable runtime state {:?}: {}", path, e);
+                return Self::default();
+            }
+        };
+        for automation in state.automations.values_mut() {
+            automation.chats.retain(|_, progress| {
+                now_ms.saturating_sub(progress.updated_ms) < KEEP_PROGRESS_MS
+            });
+        }
+        state.automations.retain(|_, automation| {
+            !automation.chats.is_empty() || automation.known_chats.is_some()
+        });
+        state
+            .flapping_snoozes
+            .retain(|_, until_ms| *until_ms > now_ms);
+        state
+    }
+
+    /// Write the state to `path`, replacing the file in one step so a crash
+    /// mid-write can't leave half a file behind
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&temp_path, path)
+    }
+
+    /// Forget automations that are no longer running
+    pub fn retain_automations(&mut self, automation_ids: &[String]) {
+        self.automations.retain(|id, _| automation_ids.contains(id));
+        self.flapping_snoozes
+            .retain(|id, _| automation_ids.contains(id));
+    }
+
+    /// Snooze the automations in `config` that are still snoozed for
+    /// flapping, unless they are already snoozed for longer
+    pub fn apply_flapping_snoozes(&mut self, config: &mut Config, now_ms: u64) {
+        self.flapping_snoozes
+            .retain(|_, until_ms| *until_ms > now_ms);
+        for automation in &mut config.notifications.automations {
+            let Some(until_ms) = self.flapping_snoozes.get(&automation.id) else {
+                continue;
+            };
+            let until = UNIX_EPOCH + Duration::from_millis(*until_ms);
+            if automation
+                .snoozed_until
+                .is_none_or(|current| current < until)
+            {
+                automation.snoozed_until = Some(until);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_drops_old_progress() {
+        let dir = std::env::temp_dir().join(format!("beeper-state-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("state.json");
+        let day = KEEP_PROGRESS_MS;
+        assert_eq!(RuntimeState::load_from(&path, day), RuntimeState::default());
+
+        let progress = |message_id: &str, updated_ms| ChatProgress {
+            message_id: message_id.to_string(),
+            sort_key: "1".to_string(),
+            window_started_ms: None,
+            updated_ms,
+        };
+        let mut state = RuntimeState::default();
+        let work = state.automations.entry("work".to_string()).or_default();
+        work.chats.insert("recent".to_string(), progress("m2", day));
+        work.chats.insert("old".to_string(), progress("m1", 0));
+        state.save_to(&path).unwrap();
+
+        let loaded = RuntimeState::load_from(&path, day + 1);
+        let chats = &loaded.automations["work"].chats;
+        assert_eq!(chats.len(), 1);
+        assert_eq!(chats["recent"].message_id, "m2");
+
+        // Nothing recent left at all
+        assert!(
+            RuntimeState::load_from(&path, 3 * day)
+                .automations
+                .is_empty()
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_flapping_snoozes_outlast_a_reload() {
+        use crate::notifications::NotificationAutomation;
+
+        let mut state = RuntimeState::default();
+        state.flapping_snoozes.insert("nag".to_string(), 2_000);
+        state.flapping_snoozes.insert("old".to_string(), 500);
+
+        let mut config = Config::default();
+        config.notifications.automations = vec![NotificationAutomation::new(
+            "nag".to_string(),
+            "Nag".to_string(),
+            vec![],
+        )];
+        state.apply_flapping_snoozes(&mut config, 1_000);
+        assert_eq!(
+            config.notifications.automations[0].snoozed_until,
+            Some(UNIX_EPOCH + Duration::from_millis(2_000))
+        );
+        assert!(!state.flapping_snoozes.contains_key("old"));
+
+        // Once it runs out, a reload leaves the automation alone
+        config.notifications.automations[0].snoozed_until = None;
+        state.apply_flapping_snoozes(&mut config, 2_000);
+        assert_eq!(config.notifications.automations[0].snoozed_until, None);
+    }
+}
@@ -0,0 +1,391 @@
+// Setting the service up to run in the background, from the configurator:
+// a Scheduled Task at logon on Windows, as install.ps1 creates, a systemd
+// user unit on Linux and a launchd agent on macOS. The last two run as the
+// current user, so they need no root rights.
+
+use crate::config::Config;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Whether the background service is set up, and whether it is running
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ServiceInstall {
+    NotInstalled,
+    Stopped,
+    Running,
+    /// No supported service manager on this platform
+    Unsupported,
+}
+
+impl std::fmt::Display for ServiceInstall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServiceInstall::NotInstalled => write!(f, "not installed"),
+            ServiceInstall::Stopped => write!(f, "installed, stopped"),
+            ServiceInstall::Running => write!(f, "running"),
+            ServiceInstall::Unsupported => write!(f, "not supported here"),
+        }
+    }
+}
+
+/// What to do when the service manager refuses the current user
+#[cfg(windows)]
+const PERMISSION_HINT: &str = "Not allowed to register the service. Run the configurator as Administrator, or install with install.ps1";
+#[cfg(not(windows))]
+const PERMISSION_HINT: &str = "Not allowed to set up the service. Install it with the install script instead (see the README)";
+
+/// The service binary, which the installers put next to the configurator
+fn service_binary() -> Result<PathBuf, String> {
+    let name = if cfg!(windows) {
+        "auto-beeper-windows-service.exe"
+    } else {
+        "auto-beeper-service"
+    };
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Could not find the configurator's location: {}", e))?;
+    let path = exe.with_file_name(name);
+    if path.exists() {
+        Ok(path)
+    } else {
+        Err(format!("{} not found next to the configurator", name))
+    }
+}
+
+/// The config directory to hand the installed service, when `--config-dir` or
+/// `BEEPER_CONFIG_DIR` moved it; the service manager starts the service with
+/// neither. Made absolute, as the service runs from another directory.
+pub fn config_dir_arg() -> Result<Option<PathBuf>, String> {
+    if !Config::config_dir_overridden() {
+        return Ok(None);
+    }
+    let dir = Config::config_dir().map_err(|e| e.to_string())?;
+    std::path::absolute(&dir)
+        .map(|dir| Some(dir.components().collect()))
+        .map_err(|e| format!("Could not resolve the config directory {:?}: {}", dir, e))
+}
+
+/// Run a command, returning its output, or its error output when it fails
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Could not run {}: {}", program, e))?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(if stderr.is_empty() {
+        format!("{} failed ({})", program, output.status)
+    } else {
+        stderr
+    })
+}
+
+/// Whether an error says the user isn't allowed to do this
+fn is_permission_error(error: &str) -> bool {
+    let error = error.to_lowercase();
+    [
+        "access is denied",
+        "access denied",
+        "permission denied",
+        "not permitted",
+        "authentication required",
+    ]
+    .iter()
+    .any(|phrase| error.contains(phrase))
+}
+
+/// The error with guidance in front when it is about permissions
+fn explain(error: String) -> String {
+    if is_permission_error(&error) {
+        format!("{} ({})", PERMISSION_HINT, error)
+    } else {
+        error
+    }
+}
+
+/// Whether the background service is installed and running
+pub fn status() -> ServiceInstall {
+    platform::status()
+}
+
+/// Install the background service if needed, start it, and have it start
+/// at login from now on. Returns what was done.
+pub fn install_and_start() -> Result<String, String> {
+    platform::install_and_start().map_err(explain)
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{ServiceInstall, config_dir_arg, run, service_binary};
+    use std::path::{Path, PathBuf};
+
+    const UNIT_NAME: &str = "auto-beeper.service";
+
+    fn unit_path() -> Result<PathBuf, String> {
+        dirs::config_dir()
+            .map(|dir| dir.join("systemd").join("user").join(UNIT_NAME))
+            .ok_or_else(|| "Could not find the user config directory".to_string())
+    }
+
+    /// A user unit like the install script's system unit
+    pub(super) fn systemd_unit(service_path: &Path, config_dir: Option<&Path>) -> String {
+        let config_dir = config_dir
+            .map(|dir| format!(" --config-dir \"{}\"", dir.display()))
+            .unwrap_or_default();
+        format!(
+            "[Unit]
+Description=Beeper Automations Service
+After=network.target
+
+[Service]
+Type=notify
+ExecStart=\"{}\"{}
+Restart=on-failure
+RestartSec=10
+WatchdogSec=60
+
+[Install]
+WantedBy=default.target
+",
+            service_path.display(),
+            config_dir
+        )
+    }
+
+    /// `systemctl` for the user's units, or the system's
+    fn systemctl(user: bool, args: &[&str]) -> Result<String, String> {
+        let mut full_args = Vec::with_capacity(args.len() + 1);
+        if user {
+            full_args.push("--user");
+        }
+        full_args.extend_from_slice(args);
+        run("systemctl", &full_args)
+    }
+
+    pub(super) fn status() -> ServiceInstall {
+        // The install script's system unit counts as well as our user unit
+        let mut installed = false;
+        for user in [false, true] {
+            if systemctl(user, &["is-active", "--quiet", UNIT_NAME]).is_ok() {
+                return ServiceInstall::Running;
+            }
+            installed |= systemctl(user, &["cat", UNIT_NAME]).is_ok();
+        }
+        if installed {
+            ServiceInstall::Stopped
+        } else {
+            ServiceInstall::NotInstalled
+        }
+    }
+
+    pub(super) fn install_and_start() -> Result<String, String> {
+        // Starting a system unit takes root, which a terminal UI can't ask for
+        if systemctl(false, &["cat", UNIT_NAME]).is_ok() {
+            if systemctl(false, &["is-active", "--quiet", UNIT_NAME]).is_ok() {
+                return Ok("✓ The system service is already running".to_string());
+            }
+            return Err(format!(
+                "The service is installed system-wide; start it with: sudo systemctl start {}",
+                UNIT_NAME
+            ));
+        }
+
+        let unit_path = unit_path()?;
+        if let Some(parent) = unit_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Could not create {:?}: {}", parent, e))?;
+        }
+        let unit = systemd_unit(&service_binary()?, config_dir_arg()?.as_deref());
+        std::fs::write(&unit_path, unit)
+            .map_err(|e| format!("Could not write {:?}: {}", unit_path, e))?;
+
+        systemctl(true, &["daemon-reload"])?;
+        systemctl(true, &["enable", "--now", UNIT_NAME])?;
+        Ok(format!(
+            "✓ Service installed as a systemd user unit and started ({})",
+            UNIT_NAME
+        ))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{ServiceInstall, config_dir_arg, run, service_binary};
+    use std::path::{Path, PathBuf};
+
+    const AGENT_LABEL: &str = "com.beeper.automations";
+
+    fn agent_path() -> Result<PathBuf, String> {
+        dirs::home_dir()
+            .map(|home| {
+                home.join("Library")
+                    .join("LaunchAgents")
+                    .join(format!("{}.plist", AGENT_LABEL))
+            })
+            .ok_or_else(|| "Could not find the home directory".to_string())
+    }
+
+    /// The same agent as the install script's
+    fn launchd_plist(service_path: &Path, config_dir: Option<&Path>, logs_dir: &Path) -> String {
+        let config_dir = config_dir
+            .map(|dir| {
+                format!(
+                    "\n        <string>--config-dir</string>\n        <string>{}</string>",
+                    dir.display()
+                )
+            })
+            .unwrap_or_default();
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{service}</string>{config_dir}
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <true/>
+    <key>StandardOutPath</key>
+    <string>{logs}/beeper-automations.log</string>
+    <key>StandardErrorPath</key>
+    <string>{logs}/beeper-automations.error.log</string>
+</dict>
+</plist>
+"#,
+            label = AGENT_LABEL,
+            service = service_path.display(),
+            config_dir = config_dir,
+            logs = logs_dir.display()
+        )
+    }
+
+    pub(super) fn status() -> ServiceInstall {
+        match run("launchctl", &["list", AGENT_LABEL]) {
+            Ok(info) if info.contains("\"PID\"") => ServiceInstall::Running,
+            Ok(_) => ServiceInstall::Stopped,
+            Err(_) if agent_path().is_ok_and(|path| path.exists()) => ServiceInstall::Stopped,
+            Err(_) => ServiceInstall::NotInstalled,
+        }
+    }
+
+    pub(super) fn install_and_start() -> Result<String, String> {
+        let agent_path = agent_path()?;
+        let library = agent_path
+            .parent()
+            .and_then(Path::parent)
+            .ok_or("Could not find the Library directory")?;
+        let logs_dir = library.join("Logs");
+        for dir in [agent_path.parent().unwrap_or(library), logs_dir.as_path()] {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Could not create {:?}: {}", dir, e))?;
+        }
+        let plist = launchd_plist(&service_binary()?, config_dir_arg()?.as_deref(), &logs_dir);
+        std::fs::write(&agent_path, plist)
+            .map_err(|e| format!("Could not write {:?}: {}", agent_path, e))?;
+
+        // Reload so a changed agent takes effect; unloading one that isn't loaded fails harmlessly
+        let path = agent_path.to_string_lossy();
+        run("launchctl", &["unload", &path]).ok();
+        run("launchctl", &["load", "-w", &path])?;
+        Ok(format!(
+            "✓ Service installed as a launchd agent and started ({})",
+            AGENT_LABEL
+        ))
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{ServiceInstall, config_dir_arg, run, service_binary};
+
+    /// Same task name as install.ps1, so either can replace the other's task
+    const TASK_NAME: &str = "BeeperAutomations";
+
+    pub(super) fn status() -> ServiceInstall {
+        match run("schtasks", &["/Query", "/TN", TASK_NAME, "/FO", "LIST"]) {
+            Ok(info) if info.contains("Running") => ServiceInstall::Running,
+            Ok(_) => ServiceInstall::Stopped,
+            Err(_) => ServiceInstall::NotInstalled,
+        }
+    }
+
+    pub(super) fn install_and_start() -> Result<String, String> {
+        let mut task_command = format!("\"{}\"", service_binary()?.display());
+        if let Some(dir) = config_dir_arg()? {
+            task_command.push_str(&format!(" --config-dir \"{}\"", dir.display()));
+        }
+        run(
+            "schtasks",
+            &[
+                "/Create",
+                "/TN",
+                TASK_NAME,
+                "/TR",
+                &task_command,
+                "/SC",
+                "ONLOGON",
+                "/RL",
+                "HIGHEST",
+                "/F",
+            ],
+        )?;
+        run("schtasks", &["/Run", "/TN", TASK_NAME])?;
+        Ok(format!(
+            "✓ Service registered as the '{}' logon task and started",
+            TASK_NAME
+        ))
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::ServiceInstall;
+
+    pub(super) fn status() -> ServiceInstall {
+        ServiceInstall::Unsupported
+    }
+
+    pub(super) fn install_and_start() -> Result<String, String> {
+        Err("Installing the service isn't supported on this platform".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permission_errors_get_guidance() {
+        assert!(
+            explain("schtasks: ERROR: Access is denied.".to_string()).starts_with(PERMISSION_HINT)
+        );
+        assert!(
+            explain("Failed to enable unit: Interactive authentication required.".to_string())
+                .starts_with(PERMISSION_HINT)
+        );
+        assert_eq!(explain("Unit not found".to_string()), "Unit not found");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_systemd_unit_quotes_binary_path() {
+        let service = std::path::Path::new("/opt/beeper tools/auto-beeper-service");
+        let unit = platform::systemd_unit(service, None);
+        assert!(unit.contains("ExecStart=\"/opt/beeper tools/auto-beeper-service\"\n"));
+        assert!(unit.contains("Type=notify"));
+        assert!(unit.contains("WantedBy=default.target"));
+
+        // A moved config directory goes along to the service
+        let unit = platform::systemd_unit(service, Some(std::path::Path::new("/srv/beeper conf")));
+        assert!(unit.contains(
+            "ExecStart=\"/opt/beeper tools/auto-beeper-service\" --config-dir \"/srv/beeper conf\"\n"
+        ));
+    }
+}
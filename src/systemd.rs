@@ -0,0 +1,40 @@
+// systemd integration for running the service as a Linux daemon
+//
+// Everything here is a no-op when the service isn't started by systemd
+// (NOTIFY_SOCKET / WATCHDOG_USEC unset), so it's safe to call unconditionally.
+
+use sd_notify::NotifyState;
+use std::time::Duration;
+
+/// Tell systemd the service finished starting up (`Type=notify` units)
+pub fn notify_ready() {
+    if let Err(e) = sd_notify::notify(false, &[NotifyState::Ready]) {
+        tracing::error!("Failed to notify systemd readiness: {}", e);
+    }
+}
+
+/// Tell systemd the service is shutting down
+pub fn notify_stopping() {
+    let _ = sd_notify::notify(false, &[NotifyState::Stopping]);
+}
+
+/// Ping the systemd watchdog at half the configured interval, if enabled
+pub fn spawn_watchdog() {
+    let mut usec = 0;
+    if !sd_notify::watchdog_enabled(false, &mut usec) {
+        return;
+    }
+
+    let interval = Duration::from_micros(usec / 2);
+    tracing::info!("systemd watchdog enabled, pinging every {:?}", interval);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = sd_notify::notify(false, &[NotifyState::Watchdog]) {
+                tracing::error!("Failed to ping systemd watchdog: {}", e);
+            }
+        }
+    });
+}
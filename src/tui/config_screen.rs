@@ -1,4 +1,5 @@
-use crate::config::Config;
+use crate::api_check::{ApiStatus, validate_api};
+use crate::config::{Config, normalize_api_url};
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
 use ratatui::{
@@ -10,6 +11,10 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph},
 };
 
+/// Smallest terminal that fits the header, both input fields and the footer
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 18;
+
 /// Application state for the TUI
 #[derive(Debug, Clone, Copy)]
 enum InputField {
@@ -23,12 +28,15 @@ pub struct ConfigScreen {
     url_input: String,
     token_input: String,
     message: String,
+    /// Pending inline credential check, while the screen waits on it
+    validation: Option<std::sync::mpsc::Receiver<ApiStatus>>,
+    spinner_frame: usize,
 }
 
 impl ConfigScreen {
     pub fn new(config: Config) -> Self {
         let url_input = config.api.url.clone();
-        let token_input = config.api.token.clone();
+        let token_input = config.api.token.reveal().to_string();
 
         Self {
             config,
@@ -36,27 +44,117 @@ impl ConfigScreen {
             url_input,
             token_input,
             message: String::new(),
+            validation: None,
+            spinner_frame: 0,
         }
     }
 
+    /// Open the screen with a message already shown, e.g. why validation failed
+    pub fn with_message(mut self, message: String) -> Self {
+        self.message = message;
+        self
+    }
+
     pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<Config> {
         use crossterm::event::{self, Event};
 
         loop {
             terminal.draw(|f| self.ui(f))?;
 
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press && self.handle_key(key) => {
+                    break;
+                }
+                Event::Paste(text) => self.paste(&text),
+                _ => {}
+            }
+        }
+
+        self.save(terminal)
+    }
+
+    /// Like `run`, but checks the credentials in place before accepting them.
+    /// Returns the saved configuration once they work, or `None` if cancelled;
+    /// nothing is saved until the check passes.
+    pub fn run_validated<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+    ) -> Result<Option<Config>> {
+        use crossterm::event::{self, Event};
+        use std::sync::mpsc::TryRecvError;
+
+        loop {
+            if let Some(validation) = &self.validation {
+                match validation.try_recv() {
+                    Ok(status) if status.is_valid() => {
+                        self.validation = None;
+                        return self.save(terminal).map(Some);
+                    }
+                    Ok(status) => {
+                        self.validation = None;
+                        self.message = format!("✗ {}", status);
+                    }
+                    Err(TryRecvError::Disconnected) => {
+                        self.validation = None;
+                        self.message = "✗ The API check stopped unexpectedly".to_string();
+                    }
+                    Err(TryRecvError::Empty) => self.spinner_frame += 1,
+                }
+            }
+
+            terminal.draw(|f| self.ui(f))?;
+
+            // Poll with a timeout so the spinner keeps moving during a check
+            if !event::poll(std::time::Duration::from_millis(100))? {
+                continue;
+            }
+
+            // Input is ignored while a check is running
+            if self.validation.is_some() {
+                continue;
+            }
+
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    if key.code == KeyCode::Esc {
+                        return Ok(None);
+                    }
                     if self.handle_key(key) {
-                        break;
+                        self.start_validation();
                     }
                 }
+                Event::Paste(text) => self.paste(&text),
+                _ => {}
             }
         }
+    }
+
+    /// Insert pasted text into the active field in one go. Line breaks and
+    /// surrounding whitespace are dropped, since neither field can hold them.
+    fn paste(&mut self, text: &str) {
+        let text: String = text.trim().chars().filter(|c| !c.is_control()).collect();
+        match self.active_field {
+            InputField::Url => self.url_input.push_str(&text),
+            InputField::Token => self.token_input.push_str(&text),
+        }
+        self.message.clear();
+    }
 
+    fn start_validation(&mut self) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let url = self.url_input.clone();
+        let token = self.token_input.clone();
+        tokio::spawn(async move {
+            let _ = tx.send(validate_api(&url, &token).await);
+        });
+        self.validation = Some(rx);
+        self.message.clear();
+    }
+
+    fn save<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<Config> {
         // Update config with new values
         self.config.api.url = self.url_input.clone();
-        self.config.api.token = self.token_input.clone();
+        self.config.api.token = self.token_input.clone().into();
 
         // Save configuration
         self.config.save()?;
@@ -102,11 +200,20 @@ impl ConfigScreen {
                 false
             }
             KeyCode::Enter => {
-                if !self.url_input.is_empty() && !self.token_input.is_empty() {
-                    true
-                } else {
+                if self.url_input.is_empty() || self.token_input.is_empty() {
                     self.message = "Please fill in both URL and token".to_string();
-                    false
+                    return false;
+                }
+                match normalize_api_url(&self.url_input) {
+                    Ok(url) => {
+                        self.url_input = url;
+                        true
+                    }
+                    Err(e) => {
+                        self.active_field = InputField::Url;
+                        self.message = format!("✗ {}", e);
+                        false
+                    }
                 }
             }
             KeyCode::Esc => {
@@ -118,6 +225,9 @@ impl ConfigScreen {
     }
 
     fn ui(&self, f: &mut Frame) {
+        if super::render_too_small(f, MIN_WIDTH, MIN_HEIGHT) {
+            return;
+        }
         let size = f.area();
 
         // Main vertical layout
@@ -178,16 +288,25 @@ impl ConfigScreen {
         );
 
         // Message or help text area
-        let message_text = if !self.message.is_empty() {
+        let message_text = if self.validation.is_some() {
+            format!(
+                "{} Validating API credentials...",
+                super::loading_screen::spinner_frame(self.spinner_frame)
+            )
+        } else if !self.message.is_empty() {
             self.message.clone()
         } else {
             "Tab: Switch field | Enter: Save | Esc: Cancel".to_string()
         };
 
-        let message_style = if self.message.contains("saved") {
+        let message_style = if self.validation.is_some() {
+            Style::default().fg(Color::Cyan)
+        } else if self.message.contains("saved") {
             Style::default().fg(Color::Green)
         } else if self.message.contains("cancelled") || self.message.contains("fill") {
             Style::default().fg(Color::Yellow)
+        } else if self.message.starts_with('✗') {
+            Style::default().fg(Color::Red)
         } else {
             Style::default().fg(Color::Gray)
         };
@@ -0,0 +1,211 @@
+use crate::notifications::history::{self, HistoryEntry};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    Frame, Terminal,
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+/// Number of most recent history entries shown
+const HISTORY_LIMIT: usize = 200;
+
+/// How often the screen rereads the history file to pick up new triggers
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+pub struct HistoryScreen {
+    /// Newest first
+    entries: Vec<HistoryEntry>,
+    selected_index: usize,
+    last_refresh: std::time::Instant,
+}
+
+impl Default for HistoryScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HistoryScreen {
+    pub fn new() -> Self {
+        let mut screen = Self {
+            entries: Vec::new(),
+            selected_index: 0,
+            last_refresh: std::time::Instant::now(),
+        };
+        screen.refresh();
+        screen
+    }
+
+    fn refresh(&mut self) {
+        let newest_before = self.entries.first().cloned();
+
+        self.entries = history::read_recent(HISTORY_LIMIT);
+        self.entries.reverse();
+        self.last_refresh = std::time::Instant::now();
+
+        // Keep the same entry selected as new ones arrive at the top
+        if self.selected_index > 0
+            && let Some(newest_before) = newest_before
+            && let Some(shift) = self.entries.iter().position(|e| *e == newest_before)
+        {
+            self.selected_index += shift;
+        }
+        self.selected_index = self.selected_index.min(self.entries.len().saturating_sub(1));
+    }
+
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        use crossterm::event::{self, Event};
+
+        loop {
+            if self.last_refresh.elapsed() >= REFRESH_INTERVAL {
+                self.refresh();
+            }
+
+            terminal.draw(|f| self.ui(f))?;
+
+            // Poll with a timeout so new entries show up without a key press
+            if !event::poll(std::time::Duration::from_millis(250))? {
+                continue;
+            }
+
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && self.handle_key(key)
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => true,
+            KeyCode::Up => {
+                self.selected_index = self.selected_index.saturating_sub(1);
+                false
+            }
+            KeyCode::Down => {
+                if self.selected_index + 1 < self.entries.len() {
+                    self.selected_index += 1;
+                }
+                false
+            }
+            KeyCode::PageUp => {
+                self.selected_index = self.selected_index.saturating_sub(10);
+                false
+            }
+            KeyCode::PageDown => {
+                self.selected_index =
+                    (self.selected_index + 10).min(self.entries.len().saturating_sub(1));
+                false
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                self.refresh();
+                false
+            }
+            _ => false,
+        }
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        let size = f.area();
+
+        // Main vertical layout
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(3),
+                    Constraint::Min(10),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
+            .split(size);
+
+        // Header
+        let header = Paragraph::new(vec![
+            Line::from(vec![Span::styled(
+                "Notification History",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )]),
+            Line::from(Span::styled(
+                history::history_file_path().display().to_string(),
+                Style::default().fg(Color::Gray),
+            )),
+        ]);
+        f.render_widget(header, chunks[0]);
+
+        // Entry list, scrolled to keep the selection visible
+        let visible_height = chunks[1].height.saturating_sub(2) as usize;
+        let scroll_offset = self
+            .selected_index
+            .saturating_sub(visible_height.saturating_sub(1));
+
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .enumerate()
+            .skip(scroll_offset)
+            .take(visible_height)
+            .map(|(idx, entry)| {
+                let style = if idx == self.selected_index {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else if entry.dry_run {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+
+                let actions = if entry.actions.is_empty() {
+                    "no actions".to_string()
+                } else {
+                    entry.actions.join(", ")
+                };
+
+                let label = format!(
+                    "  {}  {}  {} ({})  → {}{}",
+                    entry.timestamp,
+                    entry.automation,
+                    entry.chat,
+                    entry.sender,
+                    actions,
+                    if entry.dry_run { " [dry-run]" } else { "" }
+                );
+
+                ListItem::new(Span::styled(label, style))
+            })
+            .collect();
+
+        let list = if items.is_empty() {
+            List::new(vec![ListItem::new(Span::styled(
+                "Nothing has fired yet. Triggers appear here while the service runs.",
+                Style::default().fg(Color::DarkGray),
+            ))])
+        } else {
+            List::new(items)
+        };
+
+        let list = list.block(
+            Block::default()
+                .title(format!("Recent Triggers ({})", self.entries.len()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+        f.render_widget(list, chunks[1]);
+
+        // Footer with help text
+        let footer = Paragraph::new("↑↓/PgUp/PgDn: Scroll | R: Refresh | Q/Esc: Back")
+            .style(Style::default().fg(Color::Gray));
+        f.render_widget(footer, chunks[2]);
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -12,9 +13,61 @@ use ratatui::{
     widgets::Paragraph,
 };
 use std::io;
+use std::time::Duration;
+
+/// A spinner animation: the glyphs to cycle through and how long each is shown
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spinner {
+    pub frames: &'static [&'static str],
+    pub interval: Duration,
+}
+
+impl Spinner {
+    pub const BRAILLE: Spinner = Spinner {
+        frames: &["⠋", "⠙", "⠹", "⠸"],
+        interval: Duration::from_millis(150),
+    };
+    pub const LINE: Spinner = Spinner {
+        frames: &["|", "/", "-", "\\"],
+        interval: Duration::from_millis(100),
+    };
+    pub const DOTS: Spinner = Spinner {
+        frames: &["   ", ".  ", ".. ", "..."],
+        interval: Duration::from_millis(300),
+    };
+
+    /// The glyph for an animation frame
+    pub fn frame(&self, frame: usize) -> &'static str {
+        if self.frames.is_empty() {
+            return "";
+        }
+        self.frames[frame % self.frames.len()]
+    }
+}
+
+impl Default for Spinner {
+    fn default() -> Self {
+        Self::BRAILLE
+    }
+}
+
+/// Get the default spinner's glyph for an animation frame
+pub fn spinner_frame(frame: usize) -> &'static str {
+    Spinner::BRAILLE.frame(frame)
+}
+
+/// How a loading screen ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadOutcome<T> {
+    /// The future completed with this output
+    Finished(T),
+    /// The user pressed Esc; the future was aborted before completing
+    Cancelled,
+}
 
 pub struct LoadingScreen {
     message: String,
+    spinner: Spinner,
     spinner_frame: usize,
 }
 
@@ -22,17 +75,18 @@ impl LoadingScreen {
     pub fn new(message: String) -> Self {
         Self {
             message,
+            spinner: Spinner::default(),
             spinner_frame: 0,
         }
     }
 
+    pub fn with_spinner(mut self, spinner: Spinner) -> Self {
+        self.spinner = spinner;
+        self
+    }
+
     fn get_spinner(&self) -> &'static str {
-        match self.spinner_frame % 4 {
-            0 => "⠋",
-            1 => "⠙",
-            2 => "⠹",
-            _ => "⠸",
-        }
+        self.spinner.frame(self.spinner_frame)
     }
 
     fn ui(&mut self, f: &mut Frame) {
@@ -47,15 +101,22 @@ impl LoadingScreen {
             ])
             .split(size);
 
-        let text = vec![Line::from(vec![
-            Span::styled(
-                format!("{} ", self.get_spinner()),
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(self.message.clone(), Style::default().fg(Color::White)),
-        ])];
+        let text = vec![
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", self.get_spinner()),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(self.message.clone(), Style::default().fg(Color::White)),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Esc: Cancel",
+                Style::default().fg(Color::Gray),
+            )),
+        ];
 
         let loading = Paragraph::new(text).alignment(Alignment::Center);
 
@@ -65,7 +126,22 @@ impl LoadingScreen {
     }
 }
 
-pub async fn show_loading_screen<F, T>(message: &str, future: F) -> Result<T>
+/// Show a spinner while `future` runs. Esc aborts the future and returns
+/// [`LoadOutcome::Cancelled`] straight away.
+pub async fn show_loading_screen<F, T>(message: &str, future: F) -> Result<LoadOutcome<T>>
+where
+    F: std::future::Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    show_loading_screen_with(message, Spinner::default(), future).await
+}
+
+/// Like [`show_loading_screen`], animating `spinner` instead of the default
+pub async fn show_loading_screen_with<F, T>(
+    message: &str,
+    spinner: Spinner,
+    future: F,
+) -> Result<LoadOutcome<T>>
 where
     F: std::future::Future<Output = T> + Send + 'static,
     T: Send + 'static,
@@ -76,12 +152,13 @@ where
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut loading = LoadingScreen::new(message.to_string());
+    let mut loading = LoadingScreen::new(message.to_string()).with_spinner(spinner);
 
     // Spawn the async task
     let task = tokio::spawn(future);
 
-    // Animate loading screen while waiting
+    // Animate loading screen while waiting, watching for Esc between frames
+    let mut cancelled = false;
     loop {
         terminal.draw(|f| loading.ui(f))?;
 
@@ -89,13 +166,25 @@ where
             break;
         }
 
-        std::thread::sleep(std::time::Duration::from_millis(150));
+        if event::poll(spinner.interval)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && key.code == KeyCode::Esc
+        {
+            task.abort();
+            cancelled = true;
+            break;
+        }
     }
 
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
+    if cancelled {
+        return Ok(LoadOutcome::Cancelled);
+    }
+
     // Get the result
-    Ok(task.await?)
+    Ok(LoadOutcome::Finished(task.await?))
 }
@@ -0,0 +1,258 @@
+use crate::logging;
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    Frame, Terminal,
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use std::path::PathBuf;
+
+/// Number of most recent log lines shown
+const LOG_TAIL_LINES: usize = 500;
+
+/// How often the screen rereads the log while following its end
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Color for a log line, based on the level the file layer writes after the timestamp
+fn level_style(line: &str) -> Style {
+    if line.contains("[ERROR]") {
+        Style::default().fg(Color::Red)
+    } else if line.contains("[WARN]") {
+        Style::default().fg(Color::Yellow)
+    } else if line.contains("[DEBUG]") || line.contains("[TRACE]") {
+        Style::default().fg(Color::DarkGray)
+    } else {
+        Style::default().fg(Color::White)
+    }
+}
+
+pub struct LogScreen {
+    /// Oldest first
+    lines: Vec<String>,
+    /// Index of the first visible line
+    scroll: usize,
+    /// Keep the view pinned to the newest lines as the log grows
+    follow: bool,
+    /// Destination path while the export prompt is open
+    export_input: Option<String>,
+    message: String,
+    last_refresh: std::time::Instant,
+    visible_height: usize,
+}
+
+impl Default for LogScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogScreen {
+    pub fn new() -> Self {
+        let mut screen = Self {
+            lines: Vec::new(),
+            scroll: 0,
+            follow: true,
+            export_input: None,
+            message: String::new(),
+            last_refresh: std::time::Instant::now(),
+            visible_height: 0,
+        };
+        screen.refresh();
+        screen
+    }
+
+    fn refresh(&mut self) {
+        self.lines = logging::read_log_tail(LOG_TAIL_LINES);
+        self.last_refresh = std::time::Instant::now();
+        if self.follow {
+            self.scroll = self.max_scroll();
+        }
+        self.scroll = self.scroll.min(self.max_scroll());
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.lines.len().saturating_sub(self.visible_height.max(1))
+    }
+
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        use crossterm::event::{self, Event};
+
+        loop {
+            if self.follow && self.last_refresh.elapsed() >= REFRESH_INTERVAL {
+                self.refresh();
+            }
+
+            let height = terminal.size()?.height as usize;
+            // Header, footer and the list borders take 8 rows
+            self.visible_height = height.saturating_sub(8);
+            if self.follow {
+                self.scroll = self.max_scroll();
+            }
+
+            terminal.draw(|f| self.ui(f))?;
+
+            // Poll with a timeout so new lines show up without a key press
+            if !event::poll(std::time::Duration::from_millis(250))? {
+                continue;
+            }
+
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && self.handle_key(key)
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if let Some(input) = &mut self.export_input {
+            match key.code {
+                KeyCode::Esc => {
+                    self.export_input = None;
+                    self.message = "Export cancelled".to_string();
+                }
+                KeyCode::Enter => {
+                    let path = PathBuf::from(input.trim());
+                    self.export_input = None;
+                    self.export(path);
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return false;
+        }
+
+        let page = self.visible_height.max(1);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return true,
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = (self.scroll + 1).min(self.max_scroll()),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(page),
+            KeyCode::PageDown => self.scroll = (self.scroll + page).min(self.max_scroll()),
+            KeyCode::Home => self.scroll = 0,
+            KeyCode::End => self.scroll = self.max_scroll(),
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                self.refresh();
+                self.message.clear();
+            }
+            KeyCode::Char('e') | KeyCode::Char('E') => {
+                let default_path = dirs::home_dir()
+                    .unwrap_or_else(|| PathBuf::from("."))
+                    .join("beeper-automations-service.log");
+                self.export_input = Some(default_path.to_string_lossy().to_string());
+                self.message.clear();
+            }
+            _ => {}
+        }
+
+        // Scrolling to the bottom resumes following new lines
+        self.follow = self.scroll >= self.max_scroll();
+        false
+    }
+
+    fn export(&mut self, path: PathBuf) {
+        if path.as_os_str().is_empty() {
+            self.message = "✗ Please enter a file path".to_string();
+            return;
+        }
+
+        self.message = match logging::export_log(&path) {
+            Ok(_) => format!("✓ Log exported to {}", path.display()),
+            Err(e) => format!("✗ Export failed: {}", e),
+        };
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        let size = f.area();
+
+        // Main vertical layout
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(3),
+                    Constraint::Min(5),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
+            .split(size);
+
+        // Header
+        let header = Paragraph::new(vec![
+            Line::from(vec![Span::styled(
+                "Service Logs",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )]),
+            Line::from(Span::styled(
+                logging::log_file_path().display().to_string(),
+                Style::default().fg(Color::Gray),
+            )),
+        ]);
+        f.render_widget(header, chunks[0]);
+
+        // Log lines
+        let lines: Vec<Line> = if self.lines.is_empty() {
+            vec![Line::from(Span::styled(
+                "No log yet. The service writes this file while it runs in the background.",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            self.lines
+                .iter()
+                .skip(self.scroll)
+                .take(self.visible_height.max(1))
+                .map(|line| Line::from(Span::styled(line.as_str(), level_style(line))))
+                .collect()
+        };
+
+        let title = if self.follow {
+            format!("Last {} lines (following)", self.lines.len())
+        } else {
+            format!(
+                "Last {} lines ({}-{})",
+                self.lines.len(),
+                self.scroll + 1,
+                (self.scroll + self.visible_height).min(self.lines.len())
+            )
+        };
+
+        let body = Paragraph::new(lines).block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+        f.render_widget(body, chunks[1]);
+
+        // Footer: export prompt, last message or help text
+        let (footer_text, footer_style) = if let Some(input) = &self.export_input {
+            (
+                format!("Export to: {}_  (Enter: Save | Esc: Cancel)", input),
+                Style::default().fg(Color::Cyan),
+            )
+        } else if self.message.starts_with('✓') {
+            (self.message.clone(), Style::default().fg(Color::Green))
+        } else if self.message.starts_with('✗') {
+            (self.message.clone(), Style::default().fg(Color::Red))
+        } else if !self.message.is_empty() {
+            (self.message.clone(), Style::default().fg(Color::Yellow))
+        } else {
+            (
+                "↑↓/PgUp/PgDn/Home/End: Scroll | R: Refresh | E: Export | Q/Esc: Back".to_string(),
+                Style::default().fg(Color::Gray),
+            )
+        };
+        f.render_widget(Paragraph::new(footer_text).style(footer_style), chunks[2]);
+    }
+}
@@ -1,4 +1,7 @@
+use crate::app_state::SharedAppState;
 use crate::config::Config;
+use crate::notifications::control::{self, ControlCommand, ServiceStatus};
+use crate::service_install::{self, ServiceInstall};
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
 use ratatui::{
@@ -9,33 +12,124 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph},
 };
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Smallest terminal that fits the header, a few menu entries and the footer
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 17;
+
+/// How long the connection check waits for Beeper before calling it unreachable
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Whether Beeper Desktop answered the last connection check
+#[derive(Debug, Clone, PartialEq)]
+enum Connection {
+    Checking,
+    Connected,
+    Unreachable,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MenuOption {
     Module(usize),
+    TogglePause,
+    ScheduledFocus,
+    NotificationHistory,
+    ServiceLogs,
+    InstallService,
+    ExportConfiguration,
+    ImportConfiguration,
     ChangeConfiguration,
     Exit,
 }
 
 pub struct MainScreen {
-    _config: Config,
+    config: Config,
     selected_index: usize,
     modules: Vec<String>,
     message: String,
+    /// What the running service reported when the menu was opened
+    service: Result<ServiceStatus, String>,
+    /// Whether the service is set up to run in the background
+    install: ServiceInstall,
+    app_state: SharedAppState,
+    connection: Connection,
+    probe_rx: Option<mpsc::Receiver<Result<(), String>>>, // In-flight connection check
+    /// Asking whether to exit with changes that couldn't be saved
+    confirming_exit: bool,
 }
 
 impl MainScreen {
-    pub fn new(config: Config) -> Self {
+    pub fn new(config: Config, app_state: SharedAppState) -> Self {
         let modules = vec![
             "Notification Manager".to_string(),
             "Auto Response".to_string(),
         ];
 
-        Self {
-            _config: config,
+        let service = control::status(&config.control);
+        let mut screen = Self {
+            config,
             selected_index: 0,
             modules,
             message: String::new(),
+            service,
+            install: service_install::status(),
+            app_state,
+            connection: Connection::Checking,
+            probe_rx: None,
+            confirming_exit: false,
+        };
+        screen.spawn_probe();
+        screen
+    }
+
+    /// Check in the background whether Beeper answers a chat list request
+    fn spawn_probe(&mut self) {
+        self.connection = Connection::Checking;
+        let (tx, rx) = mpsc::channel();
+        let app_state = self.app_state.clone();
+
+        tokio::spawn(async move {
+            let probe = app_state.with_client_async(|client| async move {
+                client.list_chats(None).await.map(|_| ())
+            });
+            let result = match tokio::time::timeout(PROBE_TIMEOUT, probe).await {
+                Ok(Ok(result)) => result,
+                Ok(Err(e)) => Err(e.to_string()),
+                Err(_) => Err(format!("no answer within {}s", PROBE_TIMEOUT.as_secs())),
+            };
+            let _ = tx.send(result);
+        });
+
+        self.probe_rx = Some(rx);
+    }
+
+    /// Pick up the result of a finished connection check, if any
+    fn poll_probe(&mut self) {
+        let result = match &self.probe_rx {
+            Some(rx) => match rx.try_recv() {
+                Ok(result) => result,
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    Err("connection check stopped before finishing".to_string())
+                }
+            },
+            None => return,
+        };
+        self.probe_rx = None;
+
+        match result {
+            Ok(()) => {
+                self.connection = Connection::Connected;
+                if self.message.starts_with("Checking connection") {
+                    self.message = "✓ Beeper is reachable".to_string();
+                }
+            }
+            Err(e) => {
+                self.connection = Connection::Unreachable;
+                self.message = format!("✗ Beeper unreachable: {}", e);
+            }
         }
     }
 
@@ -43,8 +137,14 @@ impl MainScreen {
         use crossterm::event::{self, Event};
 
         loop {
+            self.poll_probe();
             terminal.draw(|f| self.ui(f))?;
 
+            // Poll with a timeout so the connection status shows up without a key press
+            if !event::poll(Duration::from_millis(100))? {
+                continue;
+            }
+
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
                     if let Some(choice) = self.handle_key(key) {
@@ -56,50 +156,151 @@ impl MainScreen {
     }
 
     fn handle_key(&mut self, key: KeyEvent) -> Option<MenuOption> {
+        if self.confirming_exit {
+            return self.handle_exit_confirmation(key);
+        }
+
         match key.code {
             KeyCode::Up => {
                 if self.selected_index > 0 {
                     self.selected_index -= 1;
                 } else {
-                    self.selected_index = self.total_items() - 1;
+                    self.selected_index = self.total_items().saturating_sub(1);
                 }
                 self.message.clear();
                 None
             }
             KeyCode::Down => {
-                self.selected_index = (self.selected_index + 1) % self.total_items();
+                // checked_rem guards against an empty menu
+                self.selected_index = (self.selected_index + 1)
+                    .checked_rem(self.total_items())
+                    .unwrap_or(0);
                 self.message.clear();
                 None
             }
+            KeyCode::Enter if self.get_selected_option() == MenuOption::Exit => self.request_exit(),
+            KeyCode::Enter if self.get_selected_option() == MenuOption::InstallService => {
+                self.install_service();
+                None
+            }
             KeyCode::Enter => {
                 let choice = self.get_selected_option();
                 self.message = match choice {
                     MenuOption::Module(idx) => format!("Selected: {}", self.modules[idx]),
+                    MenuOption::TogglePause if self.config.notifications.paused => {
+                        "Resuming automations...".to_string()
+                    }
+                    MenuOption::TogglePause => "Pausing automations...".to_string(),
+                    MenuOption::ScheduledFocus => "Opening scheduled focus...".to_string(),
+                    MenuOption::NotificationHistory => "Opening history...".to_string(),
+                    MenuOption::ServiceLogs => "Opening logs...".to_string(),
+                    MenuOption::InstallService => "Installing service...".to_string(),
+                    MenuOption::ExportConfiguration => "Opening export...".to_string(),
+                    MenuOption::ImportConfiguration => "Opening import...".to_string(),
                     MenuOption::ChangeConfiguration => "Opening configuration...".to_string(),
                     MenuOption::Exit => "Exiting...".to_string(),
                 };
                 Some(choice)
             }
-            KeyCode::Esc | KeyCode::Char('q') => Some(MenuOption::Exit),
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                // Reload the running service now instead of waiting for the file watcher
+                self.message = match control::send(&self.config.control, &ControlCommand::Reload) {
+                    Ok(_) => "✓ Service is reloading its configuration".to_string(),
+                    Err(e) => format!("✗ Could not reload the service: {}", e),
+                };
+                None
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                if self.probe_rx.is_none() {
+                    self.message = "Checking connection to Beeper...".to_string();
+                    self.spawn_probe();
+                }
+                None
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.request_exit(),
             _ => None,
         }
     }
 
+    /// Install and start the background service, then show how it went
+    fn install_service(&mut self) {
+        self.message = match service_install::install_and_start() {
+            Ok(done) => done,
+            Err(e) => format!("✗ {}", e),
+        };
+        self.install = service_install::status();
+        self.service = control::status(&self.config.control);
+    }
+
+    /// Exit, unless the config has changes that couldn't be saved; then ask first
+    fn request_exit(&mut self) -> Option<MenuOption> {
+        if self.app_state.has_unsaved_changes() {
+            self.confirming_exit = true;
+            self.message =
+                "⚠ You have unsaved changes, exit anyway? (y/n, s: try saving again)".to_string();
+            return None;
+        }
+        self.message = "Exiting...".to_string();
+        Some(MenuOption::Exit)
+    }
+
+    fn handle_exit_confirmation(&mut self, key: KeyEvent) -> Option<MenuOption> {
+        self.confirming_exit = false;
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => Some(MenuOption::Exit),
+            KeyCode::Char('s') | KeyCode::Char('S') => match self.app_state.save_config() {
+                Ok(()) => Some(MenuOption::Exit),
+                Err(e) => {
+                    self.message = format!("✗ Still can't save the configuration: {}", e);
+                    None
+                }
+            },
+            _ => {
+                self.message.clear();
+                None
+            }
+        }
+    }
+
     fn total_items(&self) -> usize {
-        self.modules.len() + 2 // modules + "Change Configuration" + "Exit"
+        // modules + "Pause/Resume" + "Scheduled Focus" + "History" + "Logs" + "Install Service"
+        // + "Export" + "Import" + "Change Configuration" + "Exit"
+        self.modules.len() + 9
+    }
+
+    /// Highlight `option`, e.g. the entry chosen the last time the menu was shown
+    pub fn select(&mut self, option: MenuOption) {
+        if let Some(index) = (0..self.total_items()).find(|&i| self.option_at(i) == option) {
+            self.selected_index = index;
+        }
     }
 
     fn get_selected_option(&self) -> MenuOption {
-        if self.selected_index < self.modules.len() {
-            MenuOption::Module(self.selected_index)
-        } else if self.selected_index == self.modules.len() {
-            MenuOption::ChangeConfiguration
-        } else {
-            MenuOption::Exit
+        self.option_at(self.selected_index)
+    }
+
+    fn option_at(&self, index: usize) -> MenuOption {
+        if index < self.modules.len() {
+            return MenuOption::Module(index);
+        }
+
+        match index - self.modules.len() {
+            0 => MenuOption::TogglePause,
+            1 => MenuOption::ScheduledFocus,
+            2 => MenuOption::NotificationHistory,
+            3 => MenuOption::ServiceLogs,
+            4 => MenuOption::InstallService,
+            5 => MenuOption::ExportConfiguration,
+            6 => MenuOption::ImportConfiguration,
+            7 => MenuOption::ChangeConfiguration,
+            _ => MenuOption::Exit,
         }
     }
 
     fn ui(&self, f: &mut Frame) {
+        if super::render_too_small(f, MIN_WIDTH, MIN_HEIGHT) {
+            return;
+        }
         let size = f.area();
 
         // Main vertical layout
@@ -115,19 +316,79 @@ impl MainScreen {
             )
             .split(size);
 
-        // Header
-        let header = Paragraph::new(vec![
+        // Header, with a banner while automations are paused
+        let mut header_status = vec![match self.connection {
+            Connection::Checking => {
+                Span::styled("◌ Checking Beeper...", Style::default().fg(Color::Gray))
+            }
+            Connection::Connected => Span::styled("● Connected", Style::default().fg(Color::Green)),
+            Connection::Unreachable => {
+                Span::styled("● Beeper unreachable", Style::default().fg(Color::Red))
+            }
+        }];
+        if self.config.notifications.paused {
+            header_status.push(Span::raw("  "));
+            header_status.push(Span::styled(
+                " ⏸ PAUSED - automations will not alert ",
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+        header_status.push(Span::raw("  "));
+        header_status.push(match &self.service {
+            Ok(status) => Span::styled(
+                format!(
+                    "● Service running, {} alert(s) this session",
+                    status.automations.iter().map(|a| a.triggered).sum::<u64>()
+                ),
+                Style::default().fg(Color::Green),
+            ),
+            Err(_) => Span::styled(
+                "○ Service not running",
+                Style::default().fg(Color::DarkGray),
+            ),
+        });
+        let flapping: Vec<&str> = match &self.service {
+            Ok(status) => status
+                .automations
+                .iter()
+                .filter(|a| a.flapping)
+                .map(|a| a.name.as_str())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        if !flapping.is_empty() {
+            header_status.push(Span::raw("  "));
+            header_status.push(Span::styled(
+                format!("⚠ Flapping: {}", flapping.join(", ")),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
+
+        let mut header_lines = vec![
             Line::from(vec![Span::styled(
                 "Beeper Automations",
                 Style::default()
                     .fg(Color::Cyan)
                     .add_modifier(Modifier::BOLD),
             )]),
-            Line::from(vec![Span::styled(
-                "Main Menu",
-                Style::default().fg(Color::Gray),
-            )]),
-        ]);
+            Line::from(header_status),
+        ];
+        // Stays up for as long as the config can't be written
+        if let Some(problem) = self.app_state.config_write_problem() {
+            header_lines.push(Line::from(Span::styled(
+                format!(" ✗ Changes won't be saved. {} ", problem),
+                Style::default()
+                    .fg(Color::White)
+                    .bg(Color::Red)
+                    .add_modifier(Modifier::BOLD),
+            )));
+        }
+        let header = Paragraph::new(header_lines);
         f.render_widget(header, chunks[0]);
 
         // Menu area
@@ -138,11 +399,19 @@ impl MainScreen {
         let footer_text = if !self.message.is_empty() {
             self.message.clone()
         } else {
-            "↑↓: Navigate | Enter: Select | Q/Esc: Exit".to_string()
+            "↑↓: Navigate | Enter: Select | C: Check Connection | R: Reload Service | Q/Esc: Exit"
+                .to_string()
         };
 
-        let footer_style = if self.message.contains("Selected")
+        let footer_style = if self.message.starts_with('✗') {
+            Style::default().fg(Color::Red)
+        } else if self.message.starts_with('⚠') {
+            Style::default().fg(Color::Yellow)
+        } else if self.message.starts_with('✓')
+            || self.message.contains("Selected")
             || self.message.contains("Opening")
+            || self.message.contains("Pausing")
+            || self.message.contains("Resuming")
             || self.message.contains("Exiting")
         {
             Style::default().fg(Color::Green)
@@ -182,13 +451,42 @@ impl MainScreen {
                     } else {
                         Style::default().fg(Color::White)
                     };
-                    ListItem::new(Span::styled("  Change Connection Configuration", style))
-                })
-                .into_iter(),
+                    let label = if self.config.notifications.paused {
+                        "  Resume All Automations"
+                    } else {
+                        "  Pause All Automations"
+                    };
+                    ListItem::new(Span::styled(label, style))
+                }),
+            )
+            .chain(
+                [
+                    "  Scheduled Focus".to_string(),
+                    "  Notification History".to_string(),
+                    "  View Service Logs".to_string(),
+                    format!("  Install & Start Background Service ({})", self.install),
+                    "  Export Configuration".to_string(),
+                    "  Import Configuration".to_string(),
+                    "  Change Connection Configuration".to_string(),
+                ]
+                .into_iter()
+                .enumerate()
+                .map(|(offset, label)| {
+                    let is_selected = self.selected_index == self.modules.len() + 1 + offset;
+                    let style = if is_selected {
+                        Style::default()
+                            .fg(Color::Black)
+                            .bg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::White)
+                    };
+                    ListItem::new(Span::styled(label, style))
+                }),
             )
             .chain(
                 std::iter::once({
-                    let is_selected = self.selected_index == self.modules.len() + 1;
+                    let is_selected = self.selected_index == self.modules.len() + 8;
                     let style = if is_selected {
                         Style::default()
                             .fg(Color::Black)
@@ -1,11 +1,18 @@
 use crate::{app_state::SharedAppState, config::Config};
 use anyhow::Result;
 use crossterm::{
+    event::{DisableBracketedPaste, EnableBracketedPaste},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
+use ratatui::{
+    Frame, Terminal,
+    layout::Alignment,
+    style::{Color, Style},
+    text::Span,
+    widgets::{Paragraph, Wrap},
+};
 use std::io;
 
 pub mod modules;
@@ -17,7 +24,22 @@ pub mod main_screen;
 pub use main_screen::{MainScreen, MenuOption};
 
 pub mod loading_screen;
-pub use loading_screen::show_loading_screen;
+pub use loading_screen::{LoadOutcome, Spinner, show_loading_screen, show_loading_screen_with};
+
+pub mod transfer_screen;
+pub use transfer_screen::{TransferKind, TransferScreen};
+
+pub mod history_screen;
+pub use history_screen::HistoryScreen;
+
+pub mod offline_screen;
+pub use offline_screen::{OfflineAction, OfflineScreen};
+
+pub mod log_screen;
+pub use log_screen::LogScreen;
+
+pub mod scheduled_focus_screen;
+pub use scheduled_focus_screen::ScheduledFocusScreen;
 
 /// Initialize the terminal
 pub fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
@@ -29,17 +51,47 @@ pub fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
     Ok(terminal)
 }
 
+/// Initialize the terminal with bracketed paste on, so pasted text arrives as
+/// a single `Event::Paste` rather than one key event per character. Legacy
+/// Windows consoles don't support it and keep the per-character behaviour.
+fn setup_terminal_with_paste() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
+    let mut terminal = setup_terminal()?;
+    execute!(terminal.backend_mut(), EnableBracketedPaste).ok();
+    Ok(terminal)
+}
+
 /// Restore the terminal to its original state
 pub fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
     disable_raw_mode()?;
+    execute!(terminal.backend_mut(), DisableBracketedPaste).ok();
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
     Ok(())
 }
 
+/// Show a notice instead of the screen when the terminal is smaller than
+/// `min_width` x `min_height`. Returns true if the notice was drawn, in which
+/// case the caller should skip its own layout.
+pub fn render_too_small(f: &mut Frame, min_width: u16, min_height: u16) -> bool {
+    let size = f.area();
+    if size.width >= min_width && size.height >= min_height {
+        return false;
+    }
+
+    let notice = Paragraph::new(format!(
+        "Terminal too small ({}x{})\nResize to at least {}x{}",
+        size.width, size.height, min_width, min_height
+    ))
+    .style(Style::default().fg(Color::Yellow))
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+    f.render_widget(notice, size);
+    true
+}
+
 /// Show configuration validation screen and get user input
 pub fn show_config_screen(config: Config) -> Result<Config> {
-    let mut terminal = setup_terminal()?;
+    let mut terminal = setup_terminal_with_paste()?;
     let mut screen = ConfigScreen::new(config);
 
     let result = screen.run(&mut terminal);
@@ -48,10 +100,95 @@ pub fn show_config_screen(config: Config) -> Result<Config> {
     result
 }
 
-/// Show main menu screen and get user selection
-pub fn show_main_screen(config: Config) -> Result<Option<MenuOption>> {
+/// Show the configuration screen, checking new credentials before saving them.
+/// Returns `None` if the user backed out, leaving the saved config untouched.
+pub fn show_config_screen_validated(config: Config) -> Result<Option<Config>> {
+    let mut terminal = setup_terminal_with_paste()?;
+    let mut screen = ConfigScreen::new(config);
+
+    let result = screen.run_validated(&mut terminal);
+    restore_terminal(&mut terminal)?;
+
+    result
+}
+
+/// Show the configuration screen with a message explaining why it's needed
+pub fn show_config_screen_with_message(config: Config, message: String) -> Result<Config> {
+    let mut terminal = setup_terminal_with_paste()?;
+    let mut screen = ConfigScreen::new(config).with_message(message);
+
+    let result = screen.run(&mut terminal);
+    restore_terminal(&mut terminal)?;
+
+    result
+}
+
+/// Show main menu screen and get user selection, starting on `last_choice` when given
+pub fn show_main_screen(
+    config: Config,
+    app_state: SharedAppState,
+    last_choice: Option<MenuOption>,
+) -> Result<Option<MenuOption>> {
     let mut terminal = setup_terminal()?;
-    let mut screen = MainScreen::new(config);
+    let mut screen = MainScreen::new(config, app_state);
+    if let Some(option) = last_choice {
+        screen.select(option);
+    }
+
+    let result = screen.run(&mut terminal);
+    restore_terminal(&mut terminal)?;
+
+    result
+}
+
+/// Show the export/import screen; returns the updated config after an import
+pub fn show_transfer_screen(config: Config, kind: TransferKind) -> Result<Option<Config>> {
+    let mut terminal = setup_terminal()?;
+    let mut screen = TransferScreen::new(config, kind);
+
+    let result = screen.run(&mut terminal);
+    restore_terminal(&mut terminal)?;
+
+    result
+}
+
+/// Tell the user Beeper Desktop isn't reachable and ask what to do next
+pub fn show_offline_screen(url: String, process_running: Option<bool>) -> Result<OfflineAction> {
+    let mut terminal = setup_terminal()?;
+    let mut screen = OfflineScreen::new(url, process_running);
+
+    let result = screen.run(&mut terminal);
+    restore_terminal(&mut terminal)?;
+
+    result
+}
+
+/// Show the log of recent automation triggers
+pub fn show_history_screen() -> Result<()> {
+    let mut terminal = setup_terminal()?;
+    let mut screen = HistoryScreen::new();
+
+    let result = screen.run(&mut terminal);
+    restore_terminal(&mut terminal)?;
+
+    result
+}
+
+/// Show the service log viewer
+pub fn show_log_screen() -> Result<()> {
+    let mut terminal = setup_terminal()?;
+    let mut screen = LogScreen::new();
+
+    let result = screen.run(&mut terminal);
+    restore_terminal(&mut terminal)?;
+
+    result
+}
+
+/// Show the chats opened on a schedule, for adding and editing them
+pub fn show_scheduled_focus_screen(app_state: SharedAppState) -> Result<()> {
+    let mut terminal = setup_terminal_with_paste()?;
+    let mut screen = ScheduledFocusScreen::new(app_state);
 
     let result = screen.run(&mut terminal);
     restore_terminal(&mut terminal)?;
@@ -69,3 +206,45 @@ pub fn show_notification_screen(app_state: SharedAppState) -> Result<()> {
 
     Ok(())
 }
+
+/// `text` cut to fit `width` terminal columns, ending in "…" when it had to
+/// be cut. Wide characters such as emoji count as two columns.
+pub fn truncate_to_width(text: &str, width: usize) -> String {
+    if Span::raw(text).width() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut truncated = String::new();
+    let mut used = 0;
+    let mut buf = [0; 4];
+    for ch in text.chars() {
+        let ch_width = Span::raw(&*ch.encode_utf8(&mut buf)).width();
+        // Leave a column for the ellipsis
+        if used + ch_width >= width {
+            break;
+        }
+        truncated.push(ch);
+        used += ch_width;
+    }
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("Family", 10), "Family");
+        assert_eq!(truncate_to_width("Family", 6), "Family");
+        assert_eq!(truncate_to_width("Family chat", 6), "Famil…");
+        assert_eq!(truncate_to_width(&"x".repeat(200), 20).chars().count(), 20);
+        // Emoji take two columns and aren't split
+        assert_eq!(truncate_to_width("🎉🎉🎉 party", 6), "🎉🎉…");
+        assert_eq!(truncate_to_width("Family", 0), "");
+    }
+}
@@ -1,14 +1,54 @@
 use crate::notifications::NotificationAutomation;
+use crate::notifications::chat_match::glob_matches;
+use crate::notifications::control::{self, ControlCommand};
+use crate::notifications::service;
+use crate::tui::loading_screen::spinner_frame;
+use crate::tui::truncate_to_width;
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     Frame, Terminal,
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
+use std::collections::HashMap;
+
+/// Maximum number of pages fetched while a filter is active, so a filter with
+/// no matches doesn't page through the entire account
+const MAX_FILTER_PAGES: usize = 10;
+
+/// How long the first page of chats is reused when reopening the chat selector
+const CHAT_LIST_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Smallest terminal the list and the edit modals fit in
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 20;
+
+/// Columns a long name keeps when shortened to fit a list row
+const MIN_NAME_WIDTH: usize = 12;
+
+/// One page of chats: (id, name) pairs, the next cursor, and whether more pages exist
+type ChatPage = (Vec<(String, String)>, Option<String>, bool);
+
+/// A chat page fetch as delivered to the selector, or why it failed
+type ChatLoad = Result<ChatPage, String>;
+
+/// Live state of a watched chat, shown next to each automation in the list
+#[derive(Debug, Clone)]
+struct ChatActivity {
+    title: String,
+    unread_count: u32,
+    last_activity: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Result of a status fetch: activity per chat id, or an error message
+type ActivityResult = std::result::Result<HashMap<String, ChatActivity>, String>;
+
+/// Result of a test alert: the automation's name and the actions it took
+type TestAlertResult = (String, Vec<&'static str>);
 
 pub enum ScreenState {
     List,
@@ -17,6 +57,9 @@ pub enum ScreenState {
     SelectingChats(AutomationForm, ChatSelector),
     ConfiguringLoop(AutomationForm),
     ConfiguringNtfy(AutomationForm),
+    ConfiguringSchedule(AutomationForm),
+    /// Prompting for how many minutes to snooze the selected automation
+    Snoozing(String),
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +71,11 @@ pub struct ChatSelector {
     pub loading: bool,
     pub cursor: Option<String>, // Cursor for pagination
     pub has_more: bool,         // Whether there are more chats to fetch
+    pub filter_pages: usize,    // Pages fetched since the filter last changed
+    /// Chat ID and sound path while assigning a chat its own sound
+    pub sound_input: Option<(String, String)>,
+    /// Why the last page failed to load, shown below the list
+    pub load_error: Option<String>,
 }
 
 impl ChatSelector {
@@ -40,9 +88,17 @@ impl ChatSelector {
             loading: false,
             cursor: None,
             has_more: true,
+            filter_pages: 0,
+            sound_input: None,
+            load_error: None,
         }
     }
 
+    /// Whether the first page of chats has arrived, even if it was empty
+    fn has_first_page(&self) -> bool {
+        !self.available_chats.is_empty() || self.cursor.is_some() || !self.has_more
+    }
+
     fn filtered_chats(&self) -> Vec<(String, String)> {
         if self.filter.is_empty() {
             self.available_chats.clone()
@@ -56,6 +112,57 @@ impl ChatSelector {
     }
 }
 
+/// How far `+`/`-` on the Type field move a loop's check interval
+const CHECK_INTERVAL_STEP_MS: u64 = 500;
+
+/// A millisecond duration in the largest whole unit, e.g. "5 min" or "1500ms"
+fn describe_ms(ms: u64) -> String {
+    if ms >= 60_000 && ms.is_multiple_of(60_000) {
+        format!("{} min", ms / 60_000)
+    } else if ms >= 1000 && ms.is_multiple_of(1000) {
+        format!("{}s", ms / 1000)
+    } else {
+        format!("{}ms", ms)
+    }
+}
+
+/// An enum field whose value is picked from a popup list
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DropdownField {
+    AutomationType,
+    LoopUntil,
+}
+
+impl DropdownField {
+    fn title(self) -> &'static str {
+        match self {
+            DropdownField::AutomationType => "Type",
+            DropdownField::LoopUntil => "Loop Until",
+        }
+    }
+
+    /// Labels of the field's choices, in `ALL` order
+    fn options(self) -> Vec<String> {
+        match self {
+            DropdownField::AutomationType => crate::notifications::AutomationType::ALL
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            DropdownField::LoopUntil => crate::notifications::LoopUntil::ALL
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// An open popup list and the choice under the cursor
+#[derive(Debug, Clone, Copy)]
+struct Dropdown {
+    field: DropdownField,
+    highlighted: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct AutomationForm {
     pub id: Option<String>, // None for new, Some for editing
@@ -65,18 +172,35 @@ pub struct AutomationForm {
     pub loop_until: crate::notifications::LoopUntil,
     pub loop_time: String,      // String for input, converted to u64
     pub check_interval: String, // String for input
+    pub min_unread: String,     // Empty means the default of 1
     pub notification_sound: String,
+    pub chat_sound_overrides: HashMap<String, String>, // chat_id -> sound path
     pub focus_chat: bool,
     pub enabled: bool,
     pub ntfy_enabled: bool,
     pub ntfy_url: String,
     pub ntfy_message: String,
     pub ntfy_priority: String,
+    pub ntfy_topic: String,
+    pub ntfy_token: String,
+    pub ntfy_title: String,
+    pub ntfy_tags: String, // Comma-separated
+    pub ntfy_fallback: bool,
+    pub schedule_enabled: bool,
+    pub schedule_days: String,  // e.g. "mon-fri"
+    pub schedule_hours: String, // e.g. "09:00-17:00"
+    pub schedule_cron: String,  // Overrides days and hours when set
+    pub profile: Option<String>,
     pub selected_field: usize, // Current field being edited
+    original: Option<NotificationAutomation>, // Carries settings the form doesn't edit
+    dropdown: Option<Dropdown>,               // Open enum picker, if any
+    previewing: bool,                         // Showing the plain-English summary
+    initial: Option<serde_json::Value>,       // What the form saved as when opened
+    confirming_discard: bool,                 // Esc pressed with changes, awaiting y/n
 }
 
 impl AutomationForm {
-    fn new() -> Self {
+    fn new(default_check_interval_ms: u64) -> Self {
         Self {
             id: None,
             name: String::new(),
@@ -84,38 +208,67 @@ impl AutomationForm {
             automation_type: crate::notifications::AutomationType::Immediate,
             loop_until: crate::notifications::LoopUntil::MessageSeen,
             loop_time: String::new(),
-            check_interval: "3000".to_string(),
+            check_interval: default_check_interval_ms.to_string(),
+            min_unread: String::new(),
             notification_sound: String::new(),
+            chat_sound_overrides: HashMap::new(),
             focus_chat: false,
             enabled: true,
             ntfy_enabled: false,
             ntfy_url: String::new(),
             ntfy_message: "New message from {sender} in {chat_name}".to_string(),
             ntfy_priority: "5".to_string(),
+            ntfy_topic: String::new(),
+            ntfy_token: String::new(),
+            ntfy_title: String::new(),
+            ntfy_tags: String::new(),
+            ntfy_fallback: false,
+            schedule_enabled: false,
+            schedule_days: "mon-fri".to_string(),
+            schedule_hours: "09:00-17:00".to_string(),
+            schedule_cron: String::new(),
+            profile: None,
             selected_field: 0,
+            original: None,
+            dropdown: None,
+            previewing: false,
+            initial: None,
+            confirming_discard: false,
         }
+        .with_initial()
     }
 
-    fn from_automation(automation: &NotificationAutomation) -> Self {
-        let (loop_until, loop_time, check_interval) =
+    /// `default_check_interval_ms` fills the loop settings when the
+    /// automation doesn't have any yet
+    fn from_automation(
+        automation: &NotificationAutomation,
+        default_check_interval_ms: u64,
+    ) -> Self {
+        let (loop_until, loop_time, check_interval, min_unread) =
             if let Some(loop_config) = &automation.loop_config {
                 (
                     loop_config.until,
                     loop_config.time.map(|t| t.to_string()).unwrap_or_default(),
                     loop_config.check_interval.to_string(),
+                    loop_config
+                        .min_unread
+                        .map(|n| n.to_string())
+                        .unwrap_or_default(),
                 )
             } else {
                 (
                     crate::notifications::LoopUntil::MessageSeen,
                     String::new(),
-                    "3000".to_string(),
+                    default_check_interval_ms.to_string(),
+                    String::new(),
                 )
             };
 
-        let (ntfy_enabled, ntfy_url, ntfy_message, ntfy_priority) = if let Some(ntfy_config) = &automation.ntfy_config {
-            (ntfy_config.enabled, ntfy_config.url.clone(), ntfy_config.message.clone(), ntfy_config.priority.to_string())
-        } else {
-            (false, String::new(), "New message from {sender} in {chat_name}".to_string(), "5".to_string())
+        let ntfy_config = automation.ntfy_config.clone().unwrap_or_default();
+
+        let (schedule_days, schedule_hours) = match &automation.schedule {
+            Some(schedule) => (schedule.days_str(), schedule.hours_str()),
+            None => ("mon-fri".to_string(), "09:00-17:00".to_string()),
         };
 
         Self {
@@ -126,19 +279,58 @@ impl AutomationForm {
             loop_until,
             loop_time,
             check_interval,
+            min_unread,
             notification_sound: automation.notification_sound.clone().unwrap_or_default(),
+            chat_sound_overrides: automation.chat_sound_overrides.clone(),
             focus_chat: automation.focus_chat,
             enabled: automation.enabled,
-            ntfy_enabled,
-            ntfy_url,
-            ntfy_message,
-            ntfy_priority,
+            ntfy_enabled: ntfy_config.enabled,
+            ntfy_url: ntfy_config.url,
+            ntfy_message: ntfy_config.message,
+            ntfy_priority: ntfy_config.priority.to_string(),
+            ntfy_topic: ntfy_config.topic.unwrap_or_default(),
+            ntfy_token: ntfy_config
+                .token
+                .map(|token| token.reveal().to_string())
+                .unwrap_or_default(),
+            ntfy_title: ntfy_config.title.unwrap_or_default(),
+            ntfy_tags: ntfy_config.tags.join(", "),
+            ntfy_fallback: ntfy_config.fallback_when_no_audio,
+            schedule_enabled: automation.schedule.is_some() || automation.cron.is_some(),
+            schedule_days,
+            schedule_hours,
+            schedule_cron: automation.cron.clone().unwrap_or_default(),
+            profile: automation.profile.clone(),
             selected_field: 0,
+            original: Some(automation.clone()),
+            dropdown: None,
+            previewing: false,
+            initial: None,
+            confirming_discard: false,
         }
+        .with_initial()
+    }
+
+    /// The automation the form would save, without the id a new one is given
+    fn snapshot(&self) -> Option<serde_json::Value> {
+        let mut automation = self.to_automation();
+        automation.id = self.id.clone().unwrap_or_default();
+        serde_json::to_value(&automation).ok()
+    }
+
+    /// Remember what the form holds now, for [`Self::is_dirty`]
+    fn with_initial(mut self) -> Self {
+        self.initial = self.snapshot();
+        self
+    }
+
+    /// Whether the form was changed since it was opened
+    fn is_dirty(&self) -> bool {
+        self.snapshot() != self.initial
     }
 
     fn to_automation(&self) -> NotificationAutomation {
-        let loop_config = if self.automation_type == crate::notifications::AutomationType::Loop {
+        let loop_config = if self.automation_type.uses_loop_config() {
             Some(crate::notifications::LoopConfig {
                 until: self.loop_until,
                 time: if !self.loop_time.is_empty() {
@@ -147,6 +339,7 @@ impl AutomationForm {
                     None
                 },
                 check_interval: self.check_interval.parse().unwrap_or(3000),
+                min_unread: self.min_unread.parse().ok(),
             })
         } else {
             None
@@ -158,11 +351,33 @@ impl AutomationForm {
                 url: self.ntfy_url.clone(),
                 message: self.ntfy_message.clone(),
                 priority: self.ntfy_priority.parse().unwrap_or(5),
+                topic: non_empty(&self.ntfy_topic),
+                token: non_empty(&self.ntfy_token).map(Into::into),
+                title: non_empty(&self.ntfy_title),
+                tags: self
+                    .ntfy_tags
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(String::from)
+                    .collect(),
+                fallback_when_no_audio: self.ntfy_fallback,
             })
         } else {
             None
         };
 
+        let (schedule, cron) = if self.schedule_enabled {
+            (self.schedule().ok(), non_empty(&self.schedule_cron))
+        } else {
+            (None, None)
+        };
+
+        // Start from the original so settings without a form field survive editing
+        let base = self.original.clone().unwrap_or_else(|| {
+            NotificationAutomation::new(String::new(), String::new(), Vec::new())
+        });
+
         NotificationAutomation {
             id: self
                 .id
@@ -176,27 +391,248 @@ impl AutomationForm {
             } else {
                 None
             },
+            chat_sound_overrides: self.chat_sound_overrides.clone(),
             focus_chat: self.focus_chat,
             loop_config,
             enabled: self.enabled,
             ntfy_config,
+            schedule,
+            cron,
+            profile: self.profile.clone(),
+            ..base
+        }
+    }
+
+    /// Plain-English description of what the automation will do as
+    /// currently filled in
+    fn summary(&self) -> String {
+        use crate::notifications::{AutomationType, LoopUntil};
+
+        let original = self.original.as_ref();
+        let mut parts = Vec::new();
+
+        let chats = match original.and_then(|a| a.chat_match.as_deref()) {
+            Some(pattern) => format!(
+                "{} chat(s) plus chats matching '{}'",
+                self.chat_ids.len(),
+                pattern
+            ),
+            None => format!("{} chat(s)", self.chat_ids.len()),
+        };
+
+        let behaviour = match self.automation_type {
+            AutomationType::Immediate => {
+                format!("Immediate: alerts once per new message in {}", chats)
+            }
+            AutomationType::NewChat => {
+                "New chat: alerts once for each chat that appears in the chat list".to_string()
+            }
+            AutomationType::Loop | AutomationType::Combined => {
+                let interval = describe_ms(self.check_interval.parse().unwrap_or(3000));
+                let until = match self.loop_until {
+                    LoopUntil::MessageSeen => match self.min_unread.parse::<u32>() {
+                        Ok(n) if n > 1 => {
+                            format!("once a chat has {} unread messages, until it is read", n)
+                        }
+                        _ => "until the chat is read".to_string(),
+                    },
+                    LoopUntil::Answer => "until you reply".to_string(),
+                    LoopUntil::ForATime => match self.loop_time.parse() {
+                        Ok(ms) => format!("for {} after each new message", describe_ms(ms)),
+                        Err(_) => "for a time that isn't set yet".to_string(),
+                    },
+                    LoopUntil::Once => "once per new message while it is unread".to_string(),
+                };
+                if self.automation_type == AutomationType::Combined {
+                    format!(
+                        "Combined: alerts on each new message, then every {} {} in {}",
+                        interval, until, chats
+                    )
+                } else {
+                    format!("Loop: alerts every {} {} in {}", interval, until, chats)
+                }
+            }
+        };
+        parts.push(behaviour);
+
+        if self.notification_sound.is_empty() {
+            parts.push("plays no sound".to_string());
+        } else {
+            parts.push(format!("plays {}", self.notification_sound));
+        }
+        if !self.chat_sound_overrides.is_empty() {
+            parts.push(format!(
+                "{} chat(s) have their own sound",
+                self.chat_sound_overrides.len()
+            ));
+        }
+        if self.focus_chat {
+            parts.push("focuses the chat".to_string());
+        }
+        if self.ntfy_enabled {
+            parts.push("pushes to ntfy".to_string());
+        }
+        if let Some(profile) = &self.profile {
+            parts.push(format!("fills unset settings from profile '{}'", profile));
+        }
+        if self.schedule_enabled {
+            parts.push(match non_empty(&self.schedule_cron) {
+                Some(cron) => format!("only alerts when cron '{}' matches", cron),
+                None => format!(
+                    "only alerts on {} {}",
+                    self.schedule_days, self.schedule_hours
+                ),
+            });
+        }
+
+        // Settings the form doesn't edit, carried over from the saved automation
+        let ignore_own = original.is_none_or(|a| a.ignore_own_messages);
+        if ignore_own {
+            parts.push("ignores own messages".to_string());
+        }
+        if let Some(automation) = original {
+            if automation.mark_read {
+                parts.push("marks the chat read".to_string());
+            }
+            if automation.desktop_notification {
+                parts.push("shows a desktop notification".to_string());
+            }
+            if !automation.sound_rules.is_empty() {
+                parts.push(format!(
+                    "picks the sound by message text ({} rule(s))",
+                    automation.sound_rules.len()
+                ));
+            }
+            if let Some(secs) = automation.max_message_age_secs {
+                parts.push(format!(
+                    "skips messages older than {}",
+                    describe_ms(secs * 1000)
+                ));
+            }
+        }
+
+        let summary = format!("{}.", parts.join(", "));
+        if self.enabled {
+            summary
+        } else {
+            format!("Disabled. When enabled: {}", summary)
+        }
+    }
+
+    fn schedule(&self) -> std::result::Result<crate::notifications::Schedule, String> {
+        crate::notifications::Schedule::parse(&self.schedule_days, &self.schedule_hours)
+    }
+
+    /// Check the days and hours, and the cron expression if there is one
+    fn check_schedule(&self) -> std::result::Result<(), String> {
+        self.schedule()?;
+        if let Some(cron) = non_empty(&self.schedule_cron) {
+            cron.parse::<crate::notifications::CronSchedule>()
+                .map_err(|e| format!("cron: {}", e))?;
         }
+        Ok(())
+    }
+
+    /// Move the check interval up or down one step, keeping it at least one step
+    fn step_check_interval(&mut self, up: bool) {
+        let current: u64 = self.check_interval.parse().unwrap_or(3000);
+        let stepped = if up {
+            current.saturating_add(CHECK_INTERVAL_STEP_MS)
+        } else {
+            current.saturating_sub(CHECK_INTERVAL_STEP_MS)
+        };
+        self.check_interval = stepped.max(CHECK_INTERVAL_STEP_MS).to_string();
     }
 
     fn field_count(&self) -> usize {
-        // Base fields: name, chat_ids, type, sound, focus_chat, enabled, ntfy
-        // Loop configuration and Ntfy configuration are in separate screens
-        7
+        // Base fields: name, chat_ids, type, sound, focus_chat, enabled, ntfy, schedule, profile
+        // Loop, Ntfy and Schedule configuration are in separate screens
+        9
+    }
+
+    /// Open the popup list for an enum field, starting on its current value
+    fn open_dropdown(&mut self, field: DropdownField) {
+        let current = match field {
+            DropdownField::AutomationType => crate::notifications::AutomationType::ALL
+                .iter()
+                .position(|t| *t == self.automation_type),
+            DropdownField::LoopUntil => crate::notifications::LoopUntil::ALL
+                .iter()
+                .position(|u| *u == self.loop_until),
+        };
+        self.dropdown = Some(Dropdown {
+            field,
+            highlighted: current.unwrap_or(0),
+        });
+    }
+
+    /// Handle a key while a dropdown is open. Returns the field that was set
+    /// when a choice is made.
+    fn handle_dropdown_key(&mut self, code: KeyCode) -> Option<DropdownField> {
+        let dropdown = self.dropdown.as_mut()?;
+        let count = dropdown.field.options().len();
+
+        match code {
+            KeyCode::Up | KeyCode::BackTab => {
+                dropdown.highlighted = (dropdown.highlighted + count - 1) % count;
+            }
+            KeyCode::Down | KeyCode::Tab => {
+                dropdown.highlighted = (dropdown.highlighted + 1) % count;
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let Dropdown { field, highlighted } = self.dropdown.take()?;
+                match field {
+                    DropdownField::AutomationType => {
+                        self.automation_type =
+                            crate::notifications::AutomationType::ALL[highlighted];
+                    }
+                    DropdownField::LoopUntil => {
+                        self.loop_until = crate::notifications::LoopUntil::ALL[highlighted];
+                    }
+                }
+                return Some(field);
+            }
+            KeyCode::Esc => self.dropdown = None,
+            _ => {}
+        }
+        None
     }
 
     fn loop_field_count(&self) -> usize {
-        // Loop fields: loop_until, check_interval, and optionally loop_time
-        if self.loop_until == crate::notifications::LoopUntil::ForATime {
-            3 // loop_until, loop_time, check_interval
+        // Loop fields: loop_until, check_interval, and optionally loop_time or min_unread
+        if self.loop_extra_field().is_some() {
+            3 // loop_until, loop_time/min_unread, check_interval
         } else {
             2 // loop_until, check_interval
         }
     }
+
+    /// The field shown between Loop Until and Check Interval, if the
+    /// selected condition has one: its label and input
+    fn loop_extra_field(&self) -> Option<(&'static str, &String)> {
+        match self.loop_until {
+            crate::notifications::LoopUntil::ForATime => {
+                Some(("Loop Time (ms) *required*", &self.loop_time))
+            }
+            crate::notifications::LoopUntil::MessageSeen => {
+                Some(("Min Unread Messages (default 1)", &self.min_unread))
+            }
+            _ => None,
+        }
+    }
+
+    /// The numeric input behind a loop configuration field index
+    fn loop_text_field_mut(&mut self, field: usize) -> Option<&mut String> {
+        let has_extra = self.loop_extra_field().is_some();
+        match (field, has_extra) {
+            (1, true) if self.loop_until == crate::notifications::LoopUntil::ForATime => {
+                Some(&mut self.loop_time)
+            }
+            (1, true) => Some(&mut self.min_unread),
+            (2, true) | (1, false) => Some(&mut self.check_interval),
+            _ => None,
+        }
+    }
 }
 
 pub struct NotificationScreen {
@@ -205,6 +641,15 @@ pub struct NotificationScreen {
     selected_index: usize,
     message: String,
     state: ScreenState,
+    chat_load_rx: Option<std::sync::mpsc::Receiver<ChatLoad>>, // In-flight chat page fetch
+    chat_cache: Option<ChatSelector>, // Last selector state, reused when reopening
+    activity_rx: Option<std::sync::mpsc::Receiver<ActivityResult>>, // In-flight status fetch
+    chat_activity: Option<HashMap<String, ChatActivity>>, // Last fetched status, by chat id
+    test_rx: Option<std::sync::mpsc::Receiver<TestAlertResult>>, // In-flight test alert
+    spinner_frame: usize,
+    /// Last deleted automation and the row it was on, until U puts it back
+    /// or another action makes undoing it ambiguous
+    last_deleted: Option<(usize, NotificationAutomation)>,
 }
 
 impl NotificationScreen {
@@ -214,79 +659,397 @@ impl NotificationScreen {
             .map(|c| c.notifications.automations.clone())
             .unwrap_or_default();
 
+        // Come back to the automation selected when the screen was last closed
+        let selected_index = app_state
+            .last_automation_id()
+            .and_then(|id| automations.iter().position(|a| a.id == id))
+            .unwrap_or(0);
+
         Self {
             app_state,
             automations,
-            selected_index: 0,
+            selected_index,
             message: String::new(),
             state: ScreenState::List,
+            chat_load_rx: None,
+            chat_cache: None,
+            activity_rx: None,
+            chat_activity: None,
+            test_rx: None,
+            spinner_frame: 0,
+            last_deleted: None,
         }
     }
 
-    fn save_to_config(&self) -> Result<()> {
+    /// The configured check interval for new loop settings
+    fn default_check_interval_ms(&self) -> u64 {
         self.app_state
-            .with_config_mut(|config| {
-                config.notifications.automations = self.automations.clone();
-            })
-            .map_err(|e| anyhow::anyhow!(e))?;
+            .get_config()
+            .map(|config| config.notifications.default_check_interval_ms)
+            .unwrap_or(3000)
+    }
 
-        // Save to disk
-        if let Ok(config) = self.app_state.get_config() {
-            config.save()?;
-        }
+    fn save_to_config(&self) -> Result<()> {
+        self.app_state.with_config_mut(|config| {
+            config.notifications.automations = self.automations.clone();
+        })?;
+
+        // Save to disk; a failure is remembered so exiting asks first
+        self.app_state.save_config()?;
 
         Ok(())
     }
 
-    fn load_chats_sync(
-        &self,
+    /// Fetch one page of chats on a background thread, delivering it over a channel
+    fn spawn_chat_load(
+        app_state: crate::app_state::SharedAppState,
         cursor: Option<String>,
-    ) -> (Vec<(String, String)>, Option<String>, bool) {
-        // Get a handle to the current runtime and spawn a blocking task
+    ) -> std::sync::mpsc::Receiver<ChatLoad> {
+        let (tx, rx) = std::sync::mpsc::channel();
         let handle = tokio::runtime::Handle::current();
 
-        // Use spawn_blocking to avoid blocking the async runtime
-        std::thread::scope(|s| {
-            let thread_handle = s.spawn(|| {
-                handle.block_on(async {
-                    // Fetch one page of chats from Beeper API
-                    self.app_state
-                        .with_client(|client| {
-                            // Create a new runtime for the blocking call
-                            tokio::task::block_in_place(|| {
-                                handle.block_on(async {
-                                    match client.list_chats(cursor.as_deref(), None).await {
-                                        Ok(response) => {
-                                            let chats: Vec<(String, String)> = response
-                                                .items
-                                                .iter()
-                                                .map(|chat| (chat.id.clone(), chat.display_name()))
-                                                .collect();
-
-                                            (chats, response.oldest_cursor, response.has_more)
-                                        }
-                                        Err(_) => (Vec::new(), None, false),
-                                    }
-                                })
-                            })
+        tokio::task::spawn_blocking(move || {
+            // The first page is shared with anything else that listed chats recently
+            if cursor.is_none() {
+                let page = app_state
+                    .get_chats_cached(CHAT_LIST_TTL)
+                    .map(|chat_list| {
+                        (
+                            chat_list
+                                .chats
+                                .iter()
+                                .map(|chat| (chat.id.clone(), chat.display_name()))
+                                .collect(),
+                            chat_list.oldest_cursor.clone(),
+                            chat_list.has_more,
+                        )
+                    })
+                    .map_err(|e| e.to_string());
+                let _ = tx.send(page);
+                return;
+            }
+
+            let page = app_state
+                .with_client(|client| {
+                    handle.block_on(async {
+                        client.list_chats(cursor.as_deref()).await.map(|response| {
+                            let chats: Vec<(String, String)> = response
+                                .items
+                                .iter()
+                                .map(|chat| (chat.id.clone(), chat.display_name()))
+                                .collect();
+
+                            (chats, response.oldest_cursor, response.has_more)
                         })
-                        .unwrap_or_else(|_| (Vec::new(), None, false))
+                    })
                 })
-            });
+                .map_err(|e| e.to_string())
+                .and_then(|page| page);
+
+            // The receiver is gone if the selector was closed mid-fetch
+            let _ = tx.send(page);
+        });
+
+        rx
+    }
+
+    /// Fetch unread counts and last activity for the chats automations watch,
+    /// paging through the chat list until all of them are found. Chats whose
+    /// names match one of `patterns` are included too, which means reading
+    /// every page up to the limit.
+    fn spawn_activity_load(
+        app_state: crate::app_state::SharedAppState,
+        mut wanted: std::collections::HashSet<String>,
+        patterns: Vec<String>,
+    ) -> std::sync::mpsc::Receiver<ActivityResult> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let handle = tokio::runtime::Handle::current();
+
+        tokio::task::spawn_blocking(move || {
+            let result = app_state
+                .with_client(|client| {
+                    handle.block_on(async {
+                        let mut activity = HashMap::new();
+                        let mut cursor: Option<String> = None;
+
+                        for _ in 0..MAX_FILTER_PAGES {
+                            let response = client.list_chats(cursor.as_deref()).await?;
+
+                            for chat in &response.items {
+                                let matches_pattern = patterns
+                                    .iter()
+                                    .any(|pattern| glob_matches(pattern, &chat.title));
+                                if wanted.remove(&chat.id) || matches_pattern {
+                                    let last_activity = chat
+                                        .last_activity
+                                        .as_deref()
+                                        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                                        .map(|t| t.with_timezone(&chrono::Utc));
+                                    activity.insert(
+                                        chat.id.clone(),
+                                        ChatActivity {
+                                            title: chat.title.clone(),
+                                            unread_count: chat.unread_count,
+                                            last_activity,
+                                        },
+                                    );
+                                }
+                            }
+
+                            if (wanted.is_empty() && patterns.is_empty()) || !response.has_more {
+                                break;
+                            }
+                            cursor = response.oldest_cursor;
+                        }
+
+                        Ok(activity)
+                    })
+                })
+                .unwrap_or_else(|e| Err(e.to_string()));
+
+            // The receiver is gone if the screen was closed mid-fetch
+            let _ = tx.send(result);
+        });
+
+        rx
+    }
+
+    /// Start fetching chat status for every automation's chats
+    fn refresh_activity(&mut self) {
+        let wanted = self
+            .automations
+            .iter()
+            .flat_map(|a| a.chat_ids.iter().cloned())
+            .collect();
+        let patterns = self
+            .automations
+            .iter()
+            .filter_map(|a| a.chat_match.clone())
+            .collect();
+        self.activity_rx = Some(Self::spawn_activity_load(
+            self.app_state.clone(),
+            wanted,
+            patterns,
+        ));
+    }
+
+    /// The loaded chats an automation watches: its own chat ids plus any chat
+    /// whose name matches its pattern
+    fn watched_chats<'a>(
+        &'a self,
+        automation: &'a NotificationAutomation,
+    ) -> Vec<&'a ChatActivity> {
+        let Some(activity) = &self.chat_activity else {
+            return Vec::new();
+        };
 
-            thread_handle.join().unwrap()
-        })
+        activity
+            .iter()
+            .filter(|(id, chat)| {
+                automation.chat_ids.contains(id) || automation.matches_chat_name(&chat.title)
+            })
+            .map(|(_, chat)| chat)
+            .collect()
+    }
+
+    /// Chat part of an automation's list entry: a count, or for a name
+    /// pattern the chats it currently matches
+    fn chat_summary(&self, automation: &NotificationAutomation) -> String {
+        let Some(pattern) = &automation.chat_match else {
+            return format!("{} chats", automation.chat_ids.len());
+        };
+        if self.chat_activity.is_none() {
+            return format!("'{}'", pattern);
+        }
+
+        let mut matched: Vec<&str> = self
+            .watched_chats(automation)
+            .into_iter()
+            .filter(|chat| automation.matches_chat_name(&chat.title))
+            .map(|chat| chat.title.as_str())
+            .collect();
+        matched.sort_unstable();
+
+        let mut summary = format!("'{}' → {}", pattern, matched.len());
+        if !matched.is_empty() {
+            summary.push_str(": ");
+            summary.push_str(&matched[..matched.len().min(3)].join(", "));
+            if matched.len() > 3 {
+                summary.push_str(&format!(" +{}", matched.len() - 3));
+            }
+        }
+        if !automation.chat_ids.is_empty() {
+            summary.push_str(&format!(" + {} chats", automation.chat_ids.len()));
+        }
+        summary
+    }
+
+    /// Store a finished status fetch, if any
+    fn poll_activity_load(&mut self) {
+        let result = match &self.activity_rx {
+            Some(rx) => match rx.try_recv() {
+                Ok(result) => result,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    Err("status fetch stopped".to_string())
+                }
+            },
+            None => return,
+        };
+        self.activity_rx = None;
+
+        match result {
+            Ok(activity) => self.chat_activity = Some(activity),
+            Err(e) => self.message = format!("Could not load chat status: {}", e),
+        }
+    }
+
+    /// Status suffix for an automation's list entry, e.g. " · 3 unread · 5m ago"
+    fn activity_summary(&self, automation: &NotificationAutomation) -> String {
+        let chats = self.watched_chats(automation);
+        if chats.is_empty() {
+            return String::new();
+        }
+
+        let unread: u32 = chats.iter().map(|c| c.unread_count).sum();
+        let mut summary = format!(" · {} unread", unread);
+        if let Some(latest) = chats.iter().filter_map(|c| c.last_activity).max() {
+            summary.push_str(&format!(" · {}", format_elapsed(chrono::Utc::now() - latest)));
+        }
+        summary
+    }
+
+    /// Fire the selected automation's actions once on the runtime, delivering
+    /// the actions taken over a channel
+    fn spawn_test_alert(&mut self) {
+        let Some(automation) = self.automations.get(self.selected_index).cloned() else {
+            return;
+        };
+        self.message = format!("Testing '{}'...", automation.name);
+        let (tx, rx) = std::sync::mpsc::channel();
+        let app_state = self.app_state.clone();
+
+        tokio::spawn(async move {
+            let actions = service::fire_test_alert(&app_state, &automation).await;
+            let _ = tx.send((automation.name, actions));
+        });
+
+        self.test_rx = Some(rx);
+    }
+
+    /// Show the outcome of a finished test alert, if any. Returns true when
+    /// one finished.
+    fn poll_test_alert(&mut self) -> bool {
+        let (name, actions) = match &self.test_rx {
+            Some(rx) => match rx.try_recv() {
+                Ok(result) => result,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return false,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.test_rx = None;
+                    self.message = "✗ Test alert stopped before finishing".to_string();
+                    return true;
+                }
+            },
+            None => return false,
+        };
+        self.test_rx = None;
+
+        self.message = if actions.is_empty() {
+            format!(
+                "✗ Nothing fired for '{}'; set a sound, ntfy or desktop notification",
+                name
+            )
+        } else {
+            format!("✓ Test alert for '{}': {}", name, actions.join(", "))
+        };
+        true
+    }
+
+    /// Apply a finished chat page to the open selector, if any
+    fn poll_chat_load(&mut self) {
+        let load = match &self.chat_load_rx {
+            Some(rx) => match rx.try_recv() {
+                Ok(load) => load,
+                Err(std::sync::mpsc::TryRecvError::Empty) => return,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    Err("the chat fetch stopped unexpectedly".to_string())
+                }
+            },
+            None => return,
+        };
+        self.chat_load_rx = None;
+
+        let selector = match self.state {
+            ScreenState::SelectingChats(_, ref mut s) => s,
+            _ => return,
+        };
+
+        // A failed page leaves what was loaded so far, so scrolling or
+        // Ctrl+R tries it again
+        let (new_chats, new_cursor, has_more) = match load {
+            Ok(page) => page,
+            Err(e) => {
+                selector.loading = false;
+                selector.load_error = Some(e);
+                return;
+            }
+        };
+        selector.load_error = None;
+        selector.available_chats.extend(new_chats);
+        selector.cursor = new_cursor;
+        selector.has_more = has_more;
+        selector.loading = false;
+
+        if selector.filter.is_empty() {
+            return;
+        }
+
+        // While filtering, keep paging until the filtered view has room past
+        // the selection or the page cap is reached
+        selector.filter_pages += 1;
+        if selector.has_more
+            && selector.filter_pages < MAX_FILTER_PAGES
+            && selector.filtered_chats().len() <= selector.selected_index + 5
+        {
+            selector.loading = true;
+            self.chat_load_rx = Some(Self::spawn_chat_load(
+                self.app_state.clone(),
+                selector.cursor.clone(),
+            ));
+        }
     }
 
     pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<bool> {
         use crossterm::event::{self, Event};
 
+        if self.chat_activity.is_none() && self.activity_rx.is_none() {
+            self.refresh_activity();
+        }
+
         loop {
+            self.poll_chat_load();
+            self.poll_activity_load();
+            if self.poll_test_alert() {
+                // Sound and ntfy errors are printed straight to the terminal; draw over them
+                terminal.clear()?;
+            }
+            if self.chat_load_rx.is_some() || self.activity_rx.is_some() || self.test_rx.is_some() {
+                self.spinner_frame += 1;
+            }
+
             terminal.draw(|f| self.ui(f))?;
 
+            // Poll with a timeout so the spinner animates while a fetch is running
+            if !event::poll(std::time::Duration::from_millis(100))? {
+                continue;
+            }
+
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
                     if self.handle_key(key)? {
+                        let selected = self.automations.get(self.selected_index);
+                        self.app_state
+                            .set_last_automation_id(selected.map(|a| a.id.clone()));
                         return Ok(true);
                     }
                 }
@@ -302,55 +1065,118 @@ impl NotificationScreen {
             ScreenState::SelectingChats(_, _) => self.handle_chat_selector_key(key),
             ScreenState::ConfiguringLoop(_) => self.handle_loop_config_key(key),
             ScreenState::ConfiguringNtfy(_) => self.handle_ntfy_config_key(key),
+            ScreenState::ConfiguringSchedule(_) => self.handle_schedule_config_key(key),
+            ScreenState::Snoozing(_) => self.handle_snooze_key(key),
         }
     }
 
     fn handle_list_key(&mut self, key: KeyEvent) -> Result<bool> {
+        // Undo only reaches back one step; anything but moving around ends it
+        if !matches!(
+            key.code,
+            KeyCode::Up | KeyCode::Down | KeyCode::Char('u') | KeyCode::Char('U')
+        ) {
+            self.last_deleted = None;
+        }
+
         match key.code {
             KeyCode::Esc | KeyCode::Char('q') => Ok(true),
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                if self.activity_rx.is_none() {
+                    self.message.clear();
+                    self.refresh_activity();
+                }
+                Ok(false)
+            }
+            KeyCode::Char('t') | KeyCode::Char('T') => {
+                if self.test_rx.is_none() {
+                    self.spawn_test_alert();
+                }
+                Ok(false)
+            }
+            KeyCode::Char('s') | KeyCode::Char('S') => {
+                if !self.automations.is_empty() {
+                    self.message.clear();
+                    self.state = ScreenState::Snoozing("30".to_string());
+                }
+                Ok(false)
+            }
+            KeyCode::Char('m') | KeyCode::Char('M') => {
+                // Ask the running service to stop the current alerts
+                if let Some(automation) = self.automations.get(self.selected_index) {
+                    let mut chat_ids = automation.chat_ids.clone();
+                    // Chats picked up by chat_match, as far as the last status fetch knows
+                    if let Some(activity) = &self.chat_activity {
+                        chat_ids.extend(
+                            activity
+                                .iter()
+                                .filter(|(_, chat)| automation.matches_chat_name(&chat.title))
+                                .map(|(chat_id, _)| chat_id.clone()),
+                        );
+                    }
+                    self.message = match crate::notifications::quiet::request(&chat_ids) {
+                        Ok(()) => format!(
+                            "✓ Quieted '{}' until each chat's next message",
+                            automation.name
+                        ),
+                        Err(e) => format!("✗ Failed to quiet '{}': {}", automation.name, e),
+                    };
+                }
+                Ok(false)
+            }
             KeyCode::Char('n') | KeyCode::Char('N') => {
                 // Add new automation
-                self.state = ScreenState::AddingAutomation(AutomationForm::new());
+                let form = AutomationForm::new(self.default_check_interval_ms());
+                self.state = ScreenState::AddingAutomation(form);
                 Ok(false)
             }
             KeyCode::Char('d') | KeyCode::Char('D') => {
                 // Delete selected automation
-                if !self.automations.is_empty() {
-                    let deleted_name = self.automations[self.selected_index].name.clone();
-                    self.automations.remove(self.selected_index);
-
-                    // Adjust selected_index if needed
-                    if self.selected_index >= self.automations.len() && self.selected_index > 0 {
-                        self.selected_index -= 1;
-                    }
-
+                let index = self.selected_index;
+                if let Some(deleted) = self.remove_selected() {
                     // Save to config
                     if let Err(e) = self.save_to_config() {
                         self.message = format!("Warning: Failed to save config: {}", e);
                     } else {
-                        self.message = format!("Deleted automation: {}", deleted_name);
+                        self.message = format!("Deleted {} (press u to undo)", deleted.name);
                     }
+                    self.last_deleted = Some((index, deleted));
                 }
                 Ok(false)
             }
-            KeyCode::Up => {
-                if self.selected_index > 0 {
-                    self.selected_index -= 1;
-                } else if !self.automations.is_empty() {
-                    self.selected_index = self.automations.len() - 1;
+            KeyCode::Char('u') | KeyCode::Char('U') => {
+                if let Some(restored) = self.undo_delete() {
+                    self.message = match self.save_to_config() {
+                        Ok(()) => format!("✓ Restored {}", restored),
+                        Err(e) => format!("Warning: Failed to save config: {}", e),
+                    };
                 }
                 Ok(false)
             }
+            KeyCode::Up => {
+                let len = self.automations.len();
+                self.selected_index = match self.selected_index {
+                    _ if len == 0 => 0,
+                    0 => len - 1,
+                    index => index.min(len) - 1,
+                };
+                Ok(false)
+            }
             KeyCode::Down => {
-                if !self.automations.is_empty() {
-                    self.selected_index = (self.selected_index + 1) % self.automations.len();
-                }
+                let len = self.automations.len();
+                self.selected_index = if len == 0 {
+                    0
+                } else {
+                    (self.selected_index + 1) % len
+                };
                 Ok(false)
             }
             KeyCode::Enter => {
-                if !self.automations.is_empty() {
-                    let form =
-                        AutomationForm::from_automation(&self.automations[self.selected_index]);
+                if let Some(automation) = self.automations.get(self.selected_index) {
+                    let form = AutomationForm::from_automation(
+                        automation,
+                        self.default_check_interval_ms(),
+                    );
                     self.state = ScreenState::EditingAutomation(form);
                 }
                 Ok(false)
@@ -359,14 +1185,161 @@ impl NotificationScreen {
         }
     }
 
+    /// Remove the selected automation, keeping the selection on a row that
+    /// still exists, or on 0 once the list is empty
+    fn remove_selected(&mut self) -> Option<NotificationAutomation> {
+        if self.selected_index >= self.automations.len() {
+            return None;
+        }
+        let removed = self.automations.remove(self.selected_index);
+        self.selected_index = self
+            .selected_index
+            .min(self.automations.len().saturating_sub(1));
+        Some(removed)
+    }
+
+    /// Put the last deleted automation back on its row and select it.
+    /// Returns its name.
+    fn undo_delete(&mut self) -> Option<String> {
+        let (index, automation) = self.last_deleted.take()?;
+        let index = index.min(self.automations.len());
+        let name = automation.name.clone();
+        self.automations.insert(index, automation);
+        self.selected_index = index;
+        Some(name)
+    }
+
+    fn handle_snooze_key(&mut self, key: KeyEvent) -> Result<bool> {
+        let input = match self.state {
+            ScreenState::Snoozing(ref mut input) => input,
+            _ => return Ok(false),
+        };
+
+        match key.code {
+            KeyCode::Esc => {
+                self.state = ScreenState::List;
+            }
+            KeyCode::Enter => {
+                let Ok(minutes) = input.parse::<u64>() else {
+                    self.message = "Enter the number of minutes to snooze".to_string();
+                    return Ok(false);
+                };
+                self.state = ScreenState::List;
+
+                let Some(automation) = self.automations.get_mut(self.selected_index) else {
+                    return Ok(false);
+                };
+                let command = ControlCommand::Snooze {
+                    automation_id: automation.id.clone(),
+                    minutes,
+                };
+                // 0 ends a running snooze
+                automation.snoozed_until = (minutes > 0).then(|| {
+                    std::time::SystemTime::now() + std::time::Duration::from_secs(minutes * 60)
+                });
+                let status = match automation.snoozed_until {
+                    Some(until) => format!(
+                        "Snoozed '{}' until {}",
+                        automation.name,
+                        format_snooze_end(until)
+                    ),
+                    None => format!("Resumed '{}'", automation.name),
+                };
+
+                if let Err(e) = self.save_to_config() {
+                    self.message = format!("Warning: Failed to save config: {}", e);
+                } else {
+                    // Apply it to a running service now rather than on its next reload
+                    let control_config = self
+                        .app_state
+                        .with_config(|config| config.control.clone())
+                        .unwrap_or_default();
+                    control::send(&control_config, &command).ok();
+                    self.message = status;
+                }
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() && input.len() < 5 => {
+                input.push(c);
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// Names of the notification profiles defined in the config
+    fn profile_names(&self) -> Vec<String> {
+        self.app_state
+            .with_config(|config| {
+                config
+                    .notification_profiles
+                    .iter()
+                    .map(|p| p.name.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     fn handle_form_key(&mut self, key: KeyEvent) -> Result<bool> {
+        let profile_names = self.profile_names();
         let (form, is_editing) = match self.state {
             ScreenState::EditingAutomation(ref mut f) => (f, true),
             ScreenState::AddingAutomation(ref mut f) => (f, false),
             _ => return Ok(false),
         };
 
+        if form.dropdown.is_some() {
+            if form.handle_dropdown_key(key.code) == Some(DropdownField::AutomationType)
+                && form.automation_type.uses_loop_config()
+            {
+                // Picking Loop or Combined goes straight on to the loop settings
+                let form_clone = form.clone();
+                self.state = ScreenState::ConfiguringLoop(form_clone);
+            }
+            return Ok(false);
+        }
+
+        if form.previewing {
+            // Any key closes the preview
+            form.previewing = false;
+            return Ok(false);
+        }
+
+        if form.confirming_discard {
+            form.confirming_discard = false;
+            self.message.clear();
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.state = ScreenState::List;
+            }
+            return Ok(false);
+        }
+
+        // P types into the text fields, so there only Ctrl+P opens the preview
+        let on_text_field = matches!(form.selected_field, 0 | 3);
+        if matches!(key.code, KeyCode::Char('p') | KeyCode::Char('P'))
+            && (!on_text_field || key.modifiers.contains(KeyModifiers::CONTROL))
+        {
+            form.previewing = true;
+            return Ok(false);
+        }
+
+        // Ctrl+B on the sound field steps through the bundled sounds
+        if form.selected_field == 3
+            && key.code == KeyCode::Char('b')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            form.notification_sound = next_builtin_sound(&form.notification_sound);
+            return Ok(false);
+        }
+
         match key.code {
+            KeyCode::Esc if form.is_dirty() => {
+                form.confirming_discard = true;
+                self.message = "⚠ Discard your changes to this automation? (y/n)".to_string();
+                Ok(false)
+            }
             KeyCode::Esc => {
                 self.state = ScreenState::List;
                 self.message.clear();
@@ -377,31 +1350,41 @@ impl NotificationScreen {
                 match form.selected_field {
                     1 => {
                         // Chat selector - open selector instead of saving
+                        // Reuse the chats fetched earlier in this session, if any
                         let form_clone = form.clone();
-                        let mut selector = ChatSelector::new();
-                        selector.loading = true;
-
-                        let (chats, cursor, has_more) = self.load_chats_sync(None);
-                        selector.available_chats = chats;
-                        selector.cursor = cursor;
-                        selector.has_more = has_more;
-                        selector.loading = false;
+                        let selector = match self.chat_cache.take() {
+                            Some(cached) => cached,
+                            None => {
+                                let mut selector = ChatSelector::new();
+                                selector.loading = true;
+                                self.chat_load_rx =
+                                    Some(Self::spawn_chat_load(self.app_state.clone(), None));
+                                selector
+                            }
+                        };
 
                         self.state = ScreenState::SelectingChats(form_clone, selector);
                         return Ok(false);
                     }
-                    2 if form.automation_type == crate::notifications::AutomationType::Loop => {
-                        // Open loop configuration screen
-                        let form_clone = form.clone();
-                        self.state = ScreenState::ConfiguringLoop(form_clone);
+                    2 => {
+                        // Pick the type; choosing Loop then opens its settings
+                        form.open_dropdown(DropdownField::AutomationType);
                         return Ok(false);
                     }
                     6 if form.ntfy_enabled => {
                         // Open ntfy configuration screen
-                        let form_clone = form.clone();
+                        let mut form_clone = form.clone();
+                        form_clone.selected_field = 0;
                         self.state = ScreenState::ConfiguringNtfy(form_clone);
                         return Ok(false);
                     }
+                    7 if form.schedule_enabled => {
+                        // Open schedule configuration screen
+                        let mut form_clone = form.clone();
+                        form_clone.selected_field = 0;
+                        self.state = ScreenState::ConfiguringSchedule(form_clone);
+                        return Ok(false);
+                    }
                     _ => {}
                 }
 
@@ -411,6 +1394,27 @@ impl NotificationScreen {
                     return Ok(false);
                 }
 
+                // A disabled automation may be saved unfinished; it doesn't watch anything yet
+                let has_chat_match = form
+                    .original
+                    .as_ref()
+                    .is_some_and(|a| a.chat_match.is_some());
+                if form.enabled
+                    && form.automation_type.watches_chats()
+                    && form.chat_ids.is_empty()
+                    && !has_chat_match
+                {
+                    self.message = "Select at least one chat".to_string();
+                    return Ok(false);
+                }
+
+                if form.schedule_enabled
+                    && let Err(e) = form.check_schedule()
+                {
+                    self.message = format!("Invalid schedule: {}", e);
+                    return Ok(false);
+                }
+
                 let automation = form.to_automation();
 
                 if is_editing {
@@ -448,20 +1452,22 @@ impl NotificationScreen {
             KeyCode::Char(' ') => {
                 // Space to toggle all toggleable fields
                 match form.selected_field {
-                    2 => {
-                        // Toggle automation type
-                        form.automation_type = match form.automation_type {
-                            crate::notifications::AutomationType::Immediate => {
-                                crate::notifications::AutomationType::Loop
-                            }
-                            crate::notifications::AutomationType::Loop => {
-                                crate::notifications::AutomationType::Immediate
-                            }
-                        };
-                    }
+                    2 => form.open_dropdown(DropdownField::AutomationType),
                     4 => form.focus_chat = !form.focus_chat, // Toggle focus_chat
                     5 => form.enabled = !form.enabled,       // Toggle enabled
                     6 => form.ntfy_enabled = !form.ntfy_enabled, // Toggle ntfy
+                    7 => form.schedule_enabled = !form.schedule_enabled, // Toggle schedule
+                    8 => {
+                        // Cycle through no profile and each defined profile
+                        let current = form
+                            .profile
+                            .as_ref()
+                            .and_then(|name| profile_names.iter().position(|p| p == name));
+                        form.profile = match current {
+                            None => profile_names.first().cloned(),
+                            Some(i) => profile_names.get(i + 1).cloned(),
+                        };
+                    }
                     _ => {}
                 }
                 Ok(false)
@@ -479,6 +1485,13 @@ impl NotificationScreen {
                 }
                 Ok(false)
             }
+            KeyCode::Char(c @ ('+' | '=' | '-'))
+                if form.selected_field == 2 && form.automation_type.uses_loop_config() =>
+            {
+                // The check interval can be tuned here without opening the loop settings
+                form.step_check_interval(c != '-');
+                Ok(false)
+            }
             KeyCode::Char(c) => {
                 // Handle character input for text fields
                 match form.selected_field {
@@ -498,9 +1511,39 @@ impl NotificationScreen {
             _ => return Ok(false),
         };
 
+        if let Some((chat_id, input)) = &mut selector.sound_input {
+            match key.code {
+                KeyCode::Esc => selector.sound_input = None,
+                KeyCode::Enter => {
+                    // An empty path goes back to the automation's sound
+                    let path = input.trim().to_string();
+                    if path.is_empty() {
+                        form.chat_sound_overrides.remove(chat_id);
+                    } else {
+                        form.chat_sound_overrides.insert(chat_id.clone(), path);
+                    }
+                    selector.sound_input = None;
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return Ok(false);
+        }
+
         match key.code {
             KeyCode::Esc => {
-                // Return to form without changes
+                // Return to form without changes, discarding any in-flight fetch
+                // but keeping the chats loaded so far for the next visit. Until
+                // the first page is in there is nothing to keep, and the next
+                // visit starts the load again.
+                self.chat_load_rx = None;
+                selector.loading = false;
+                if selector.has_first_page() {
+                    self.chat_cache = Some(selector.clone());
+                }
                 let form_clone = form.clone();
                 self.state = if form.id.is_some() {
                     ScreenState::EditingAutomation(form_clone)
@@ -520,6 +1563,29 @@ impl NotificationScreen {
                 }
                 Ok(false)
             }
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Give the highlighted chat its own sound
+                let filtered = selector.filtered_chats();
+                if let Some((chat_id, _)) = filtered.get(selector.selected_index) {
+                    let current = form
+                        .chat_sound_overrides
+                        .get(chat_id)
+                        .cloned()
+                        .unwrap_or_default();
+                    selector.sound_input = Some((chat_id.clone(), current));
+                }
+                Ok(false)
+            }
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Force a reload from the first page
+                self.app_state.invalidate_chats();
+                let filter = std::mem::take(&mut selector.filter);
+                *selector = ChatSelector::new();
+                selector.filter = filter;
+                selector.loading = true;
+                self.chat_load_rx = Some(Self::spawn_chat_load(self.app_state.clone(), None));
+                Ok(false)
+            }
             KeyCode::Char(' ') | KeyCode::Char('d') | KeyCode::Char('D') => {
                 // Remove last added chat (Delete)
                 if !form.chat_ids.is_empty() {
@@ -546,31 +1612,17 @@ impl NotificationScreen {
                 }
 
                 // Check if we need to load more chats (outside the if to avoid borrow issues)
-                let should_load = selector.filter.is_empty() && // Only auto-load when not filtering
-                                  selector.has_more && 
-                                  !selector.loading && 
-                                  selector.selected_index >= selector.available_chats.len().saturating_sub(5);
+                let should_load = selector.has_more
+                    && !selector.loading
+                    && selector.selected_index >= filtered.len().saturating_sub(5)
+                    && (selector.filter.is_empty() || selector.filter_pages < MAX_FILTER_PAGES);
 
                 if should_load {
-                    let cursor = selector.cursor.clone();
-                    // Temporarily extract selector to avoid borrow issues
-                    let (form_temp, mut selector_temp) =
-                        match std::mem::replace(&mut self.state, ScreenState::List) {
-                            ScreenState::SelectingChats(f, s) => (f, s),
-                            other => {
-                                self.state = other;
-                                return Ok(false);
-                            }
-                        };
-
-                    selector_temp.loading = true;
-                    let (new_chats, new_cursor, has_more) = self.load_chats_sync(cursor);
-                    selector_temp.available_chats.extend(new_chats);
-                    selector_temp.cursor = new_cursor;
-                    selector_temp.has_more = has_more;
-                    selector_temp.loading = false;
-
-                    self.state = ScreenState::SelectingChats(form_temp, selector_temp);
+                    selector.loading = true;
+                    self.chat_load_rx = Some(Self::spawn_chat_load(
+                        self.app_state.clone(),
+                        selector.cursor.clone(),
+                    ));
                 }
 
                 Ok(false)
@@ -579,13 +1631,14 @@ impl NotificationScreen {
                 selector.filter.pop();
                 selector.selected_index = 0;
                 selector.scroll_offset = 0;
-                selector.scroll_offset = 0;
+                selector.filter_pages = 0;
                 Ok(false)
             }
             KeyCode::Char(c) => {
                 selector.filter.push(c);
                 selector.selected_index = 0;
                 selector.scroll_offset = 0;
+                selector.filter_pages = 0;
                 Ok(false)
             }
             _ => Ok(false),
@@ -598,6 +1651,11 @@ impl NotificationScreen {
             _ => return Ok(false),
         };
 
+        if form.dropdown.is_some() {
+            form.handle_dropdown_key(key.code);
+            return Ok(false);
+        }
+
         match key.code {
             KeyCode::Esc => {
                 // Return to main form
@@ -609,6 +1667,10 @@ impl NotificationScreen {
                 };
                 Ok(false)
             }
+            KeyCode::Enter if form.selected_field == 0 => {
+                form.open_dropdown(DropdownField::LoopUntil);
+                Ok(false)
+            }
             KeyCode::Enter => {
                 // Validate: if ForATime is selected, loop_time is required
                 if form.loop_until == crate::notifications::LoopUntil::ForATime
@@ -644,59 +1706,27 @@ impl NotificationScreen {
                 Ok(false)
             }
             KeyCode::Char(' ') => {
-                // Space to toggle loop_until
+                // Space also opens the loop condition list
                 if form.selected_field == 0 {
-                    form.loop_until = match form.loop_until {
-                        crate::notifications::LoopUntil::MessageSeen => {
-                            crate::notifications::LoopUntil::Answer
-                        }
-                        crate::notifications::LoopUntil::Answer => {
-                            crate::notifications::LoopUntil::ForATime
-                        }
-                        crate::notifications::LoopUntil::ForATime => {
-                            crate::notifications::LoopUntil::MessageSeen
-                        }
-                    };
+                    form.open_dropdown(DropdownField::LoopUntil);
                 }
                 Ok(false)
             }
             KeyCode::Backspace => {
                 // Handle backspace for text fields
-                let is_for_time = form.loop_until == crate::notifications::LoopUntil::ForATime;
-                match form.selected_field {
-                    1 if is_for_time => {
-                        form.loop_time.pop();
-                    }
-                    2 if is_for_time => {
-                        form.check_interval.pop();
-                    }
-                    1 if !is_for_time => {
-                        form.check_interval.pop();
-                    }
-                    _ => {}
+                let field = form.selected_field;
+                if let Some(input) = form.loop_text_field_mut(field) {
+                    input.pop();
                 }
                 Ok(false)
             }
             KeyCode::Char(c) => {
                 // Handle character input for text fields
-                let is_for_time = form.loop_until == crate::notifications::LoopUntil::ForATime;
-                match form.selected_field {
-                    1 if is_for_time => {
-                        if c.is_ascii_digit() {
-                            form.loop_time.push(c);
-                        }
-                    }
-                    2 if is_for_time => {
-                        if c.is_ascii_digit() {
-                            form.check_interval.push(c);
-                        }
-                    }
-                    1 if !is_for_time => {
-                        if c.is_ascii_digit() {
-                            form.check_interval.push(c);
-                        }
-                    }
-                    _ => {}
+                let field = form.selected_field;
+                if c.is_ascii_digit()
+                    && let Some(input) = form.loop_text_field_mut(field)
+                {
+                    input.push(c);
                 }
                 Ok(false)
             }
@@ -705,6 +1735,9 @@ impl NotificationScreen {
     }
 
     fn ui(&self, f: &mut Frame) {
+        if crate::tui::render_too_small(f, MIN_WIDTH, MIN_HEIGHT) {
+            return;
+        }
         let size = f.area();
 
         let chunks = Layout::default()
@@ -738,55 +1771,125 @@ impl NotificationScreen {
             }
             ScreenState::EditingAutomation(form) => {
                 self.render_form(f, size, form, "Edit Automation");
+                self.render_dropdown(f, size, form);
+                self.render_preview(f, size, form);
             }
             ScreenState::AddingAutomation(form) => {
                 self.render_form(f, size, form, "New Automation");
+                self.render_dropdown(f, size, form);
+                self.render_preview(f, size, form);
             }
             ScreenState::SelectingChats(form, selector) => {
                 self.render_chat_selector(f, size, form, selector);
             }
             ScreenState::ConfiguringLoop(form) => {
                 self.render_loop_config(f, size, form);
+                self.render_dropdown(f, size, form);
             }
             ScreenState::ConfiguringNtfy(form) => {
                 self.render_ntfy_config(f, size, form);
             }
+            ScreenState::ConfiguringSchedule(form) => {
+                self.render_schedule_config(f, size, form);
+            }
+            ScreenState::Snoozing(input) => {
+                self.render_automation_list(f, chunks[1]);
+                self.render_snooze_prompt(f, size, input);
+            }
         }
 
-        // Footer
+        // Footer, showing the selected automation's first lint warning in the list
+        let selected_warning = match &self.state {
+            ScreenState::List if self.message.is_empty() => {
+                self.automations.get(self.selected_index).and_then(|automation| {
+                    self.lint_warnings()
+                        .into_iter()
+                        .find(|w| w.automation_id == automation.id)
+                })
+            }
+            _ => None,
+        };
+
         let footer_text = if !self.message.is_empty() {
             self.message.clone()
+        } else if let Some(warning) = &selected_warning {
+            format!("⚠ {}", warning.message)
         } else {
                     match &self.state {
+                ScreenState::EditingAutomation(form)
+                | ScreenState::AddingAutomation(form)
+                | ScreenState::ConfiguringLoop(form)
+                    if form.dropdown.is_some() =>
+                {
+                    "↑↓: Choose | Enter: Select | Esc: Close".to_string()
+                }
+                ScreenState::EditingAutomation(form) | ScreenState::AddingAutomation(form)
+                    if form.previewing =>
+                {
+                    "Press any key to close the preview".to_string()
+                }
                 ScreenState::List => {
-                    "↑↓: Navigate | N: New | Enter: Edit | D: Delete | Q/Esc: Back".to_string()
+                    "↑↓: Navigate | N: New | Enter: Edit | D: Delete | U: Undo | T: Test | S: Snooze | M: Mute | R: Refresh | Q/Esc: Back"
+                        .to_string()
+                }
+                ScreenState::EditingAutomation(form) | ScreenState::AddingAutomation(form)
+                    if form.selected_field == 2 && form.automation_type.uses_loop_config() =>
+                {
+                    "Tab/↑↓: Navigate | +/-: Check Interval | Enter: Change/Configure | P: Preview | Esc: Cancel"
+                        .to_string()
                 }
                 ScreenState::EditingAutomation(_) => {
-                    "Tab/↑↓: Navigate | Space: Toggle | Enter: Save/Configure | Esc: Cancel"
+                    "Tab/↑↓: Navigate | Space: Toggle | Enter: Save/Configure | P: Preview | Esc: Cancel"
                         .to_string()
                 }
                 ScreenState::AddingAutomation(_) => {
-                    "Tab/↑↓: Navigate | Space: Toggle | Enter: Save/Configure | Esc: Cancel"
+                    "Tab/↑↓: Navigate | Space: Toggle | Enter: Save/Configure | P: Preview | Esc: Cancel"
                         .to_string()
                 }
+                ScreenState::SelectingChats(_, selector) if selector.sound_input.is_some() => {
+                    "Type a sound file path | Enter: Save | Esc: Cancel".to_string()
+                }
                 ScreenState::SelectingChats(_, _) => {
-                    "↑↓: Navigate | Enter: Add | D: Remove Last | Type to filter | Esc: Back"
+                    "↑↓: Navigate | Enter: Add | D: Remove Last | Ctrl+S: Chat Sound | Ctrl+R: Refresh | Type to filter | Esc: Back"
                         .to_string()
                 }
                 ScreenState::ConfiguringLoop(_) => {
-                    "Tab/↑↓: Navigate | Space: Toggle | Enter: Done | Esc: Cancel".to_string()
+                    "Tab/↑↓: Navigate | Enter: Choose/Done | Esc: Cancel".to_string()
                 }
                 ScreenState::ConfiguringNtfy(_) => {
                     "Tab/↑↓: Navigate | Enter: Done | Esc: Cancel".to_string()
                 }
+                ScreenState::ConfiguringSchedule(_) => {
+                    "Tab/↑↓: Navigate | Enter: Done | Esc: Cancel".to_string()
+                }
+                ScreenState::Snoozing(_) => {
+                    "Enter: Snooze | Esc: Cancel".to_string()
+                }
             }
         };
 
-        let footer = Paragraph::new(footer_text).style(Style::default().fg(Color::Gray));
+        let footer_color = if selected_warning.is_some() {
+            Color::Yellow
+        } else {
+            Color::Gray
+        };
+        let footer = Paragraph::new(footer_text).style(Style::default().fg(footer_color));
         f.render_widget(footer, chunks[2]);
     }
 
+    /// Lint warnings for the saved configuration
+    fn lint_warnings(&self) -> Vec<crate::config::LintWarning> {
+        self.app_state
+            .get_config()
+            .map(|config| config.lint())
+            .unwrap_or_default()
+    }
+
     fn render_automation_list(&self, f: &mut Frame, area: Rect) {
+        let warnings = self.lint_warnings();
+        // Inside the borders
+        let width = area.width.saturating_sub(2) as usize;
+
         let items: Vec<ListItem> = self
             .automations
             .iter()
@@ -794,24 +1897,48 @@ impl NotificationScreen {
             .map(|(idx, automation)| {
                 let is_selected = idx == self.selected_index;
                 let enabled_status = if automation.enabled { "✓" } else { "✗" };
+                let has_warnings = warnings.iter().any(|w| w.automation_id == automation.id);
                 let style = if is_selected {
                     Style::default()
                         .fg(Color::Black)
                         .bg(Color::Cyan)
                         .add_modifier(Modifier::BOLD)
+                } else if has_warnings {
+                    Style::default().fg(Color::Yellow)
                 } else {
                     Style::default().fg(Color::White)
                 };
 
-                let label = format!(
-                    "  [{}] {} ({} - {} chats)",
-                    enabled_status,
-                    automation.name,
+                let snooze = match automation.snoozed_until {
+                    Some(until) if automation.is_snoozed() => {
+                        format!(" · zzz until {}", format_snooze_end(until))
+                    }
+                    _ => String::new(),
+                };
+
+                let details = format!(
+                    " ({} - {}){}{}{}",
                     automation.automation_type,
-                    automation.chat_ids.len()
+                    self.chat_summary(automation),
+                    snooze,
+                    self.activity_summary(automation),
+                    if has_warnings { " ⚠" } else { "" }
+                );
+
+                // Shorten a long name first so the details stay in view, but
+                // keep some of it even when the details alone are too wide
+                let prefix = format!("  [{}] ", enabled_status);
+                let name_width = width
+                    .saturating_sub(Span::raw(&prefix).width() + Span::raw(&details).width())
+                    .max(MIN_NAME_WIDTH);
+                let label = format!(
+                    "{}{}{}",
+                    prefix,
+                    truncate_to_width(&automation.name, name_width),
+                    details
                 );
 
-                ListItem::new(Span::styled(label, style))
+                ListItem::new(Span::styled(truncate_to_width(&label, width), style))
             })
             .collect();
 
@@ -824,9 +1951,15 @@ impl NotificationScreen {
             List::new(items)
         };
 
+        let title = if self.activity_rx.is_some() {
+            format!("Automations {} loading status", spinner_frame(self.spinner_frame))
+        } else {
+            "Automations".to_string()
+        };
+
         let list = list.block(
             Block::default()
-                .title("Automations")
+                .title(title)
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Cyan)),
         );
@@ -839,7 +1972,7 @@ impl NotificationScreen {
 
         // Calculate modal size (centered, about 70% of screen width and height)
         let modal_width = std::cmp::min((area.width as usize * 70) / 100, 80);
-        let modal_height = std::cmp::min((area.height as usize * 80) / 100, 25);
+        let modal_height = std::cmp::min((area.height as usize * 80) / 100, 31);
 
         let modal_x = (area.width as usize - modal_width) / 2;
         let modal_y = (area.height as usize - modal_height) / 2;
@@ -867,7 +2000,7 @@ impl NotificationScreen {
             height: modal_area.height.saturating_sub(4),
         };
 
-        // All forms have the same 7 base fields
+        // All forms have the same 9 base fields
         let field_constraints = vec![
             Constraint::Length(3), // 0: Name
             Constraint::Length(3), // 1: Chat IDs
@@ -876,6 +2009,8 @@ impl NotificationScreen {
             Constraint::Length(3), // 4: Focus Chat
             Constraint::Length(3), // 5: Enabled
             Constraint::Length(3), // 6: Ntfy
+            Constraint::Length(3), // 7: Schedule
+            Constraint::Length(3), // 8: Profile
             Constraint::Min(1),    // Spacer
         ];
 
@@ -902,6 +2037,10 @@ impl NotificationScreen {
                 form.chat_ids.len()
             )
         };
+        let chat_display = truncate_to_width(
+            &chat_display,
+            form_chunks[1].width.saturating_sub(2) as usize,
+        );
         self.render_enum_field(
             f,
             form_chunks[1],
@@ -910,11 +2049,16 @@ impl NotificationScreen {
             form.selected_field == 1,
         );
 
-        // Field 2: Automation Type (with Loop config button)
-        let type_display = if form.automation_type == crate::notifications::AutomationType::Loop {
-            format!("{} (Press Enter to configure loop)", form.automation_type)
+        // Field 2: Automation Type, picked from a list; Loop and Combined lead on to
+        // the loop settings, and show their check interval alongside
+        let type_display = if form.automation_type.uses_loop_config() {
+            format!(
+                "{} · every {} (+/-: interval, Enter: change or configure loop)",
+                form.automation_type,
+                describe_ms(form.check_interval.parse().unwrap_or(3000))
+            )
         } else {
-            format!("{}", form.automation_type)
+            format!("{} (Press Enter to change)", form.automation_type)
         };
         self.render_enum_field(
             f,
@@ -928,7 +2072,7 @@ impl NotificationScreen {
         self.render_text_field(
             f,
             form_chunks[3],
-            "Sound (optional)",
+            "Sound (optional, Ctrl+B: built-in sounds)",
             &form.notification_sound,
             form.selected_field == 3,
         );
@@ -959,10 +2103,42 @@ impl NotificationScreen {
         };
         self.render_enum_field(
             f,
-            form_chunks[6],
-            "Ntfy Push Notification",
-            &ntfy_display,
-            form.selected_field == 6,
+            form_chunks[6],
+            "Ntfy Push Notification",
+            &ntfy_display,
+            form.selected_field == 6,
+        );
+
+        // Field 7: Schedule
+        let schedule_display = if !form.schedule_enabled {
+            "✗ Always active (Press Space to limit)".to_string()
+        } else if let Some(cron) = non_empty(&form.schedule_cron) {
+            format!("✓ cron {} (Press Enter to configure)", cron)
+        } else {
+            format!(
+                "✓ {} {} (Press Enter to configure)",
+                form.schedule_days, form.schedule_hours
+            )
+        };
+        self.render_enum_field(
+            f,
+            form_chunks[7],
+            "Schedule",
+            &schedule_display,
+            form.selected_field == 7,
+        );
+
+        // Field 8: Profile
+        let profile_display = match &form.profile {
+            Some(name) => format!("{} (Press Space to change)", name),
+            None => "None (Press Space to choose)".to_string(),
+        };
+        self.render_enum_field(
+            f,
+            form_chunks[8],
+            "Notification Profile",
+            &profile_display,
+            form.selected_field == 8,
         );
     }
 
@@ -996,6 +2172,54 @@ impl NotificationScreen {
         f.render_widget(paragraph, area);
     }
 
+    /// Popup list of an enum field's choices, marking the current value
+    fn render_dropdown(&self, f: &mut Frame, size: Rect, form: &AutomationForm) {
+        let Some(dropdown) = form.dropdown else {
+            return;
+        };
+
+        let current = match dropdown.field {
+            DropdownField::AutomationType => form.automation_type.to_string(),
+            DropdownField::LoopUntil => form.loop_until.to_string(),
+        };
+        let options = dropdown.field.options();
+
+        let modal_width = options.iter().map(|o| o.chars().count()).max().unwrap_or(0) as u16 + 10;
+        let modal_height = options.len() as u16 + 2;
+        let modal_area = Rect {
+            x: size.width.saturating_sub(modal_width) / 2,
+            y: size.height.saturating_sub(modal_height) / 2,
+            width: modal_width.min(size.width),
+            height: modal_height.min(size.height),
+        };
+
+        let items: Vec<ListItem> = options
+            .iter()
+            .enumerate()
+            .map(|(idx, option)| {
+                let style = if idx == dropdown.highlighted {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                let marker = if *option == current { "●" } else { " " };
+                ListItem::new(Span::styled(format!(" {} {} ", marker, option), style))
+            })
+            .collect();
+
+        f.render_widget(Clear, modal_area);
+        let list = List::new(items).block(
+            Block::default()
+                .title(dropdown.field.title())
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+        f.render_widget(list, modal_area);
+    }
+
     fn render_enum_field(
         &self,
         f: &mut Frame,
@@ -1103,14 +2327,19 @@ impl NotificationScreen {
             ])
             .split(inner_area);
 
-        // Filter input
-        let filter_display = if selector.filter.is_empty() {
-            "Type to filter...".to_string()
+        // Filter input, or the sound prompt while assigning one
+        let (filter_title, filter_display) = if let Some((_, input)) = &selector.sound_input {
+            (
+                "Chat Sound (empty uses the automation's sound)",
+                format!("{}_", input),
+            )
+        } else if selector.filter.is_empty() {
+            ("Filter", "Type to filter...".to_string())
         } else {
-            selector.filter.clone()
+            ("Filter", selector.filter.clone())
         };
         let filter_block = Block::default()
-            .title("Filter")
+            .title(filter_title)
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Yellow));
         let filter = Paragraph::new(filter_display)
@@ -1118,12 +2347,29 @@ impl NotificationScreen {
             .style(Style::default().fg(Color::Yellow));
         f.render_widget(filter, chunks[0]);
 
-        // Selected chats
+        // Selected chats, by name where the list has loaded them
         let selected_text = if form.chat_ids.is_empty() {
             "No chats selected yet".to_string()
         } else {
-            format!("Selected: {} chat(s)", form.chat_ids.len())
+            let names: Vec<&str> = form
+                .chat_ids
+                .iter()
+                .map(|id| {
+                    selector
+                        .available_chats
+                        .iter()
+                        .find(|(chat_id, _)| chat_id == id)
+                        .map_or(id.as_str(), |(_, name)| name.as_str())
+                })
+                .collect();
+            format!(
+                "Selected: {} chat(s): {}",
+                form.chat_ids.len(),
+                names.join(", ")
+            )
         };
+        let selected_text =
+            truncate_to_width(&selected_text, chunks[1].width.saturating_sub(2) as usize);
         let selected_block = Block::default()
             .title("Selected Chats")
             .borders(Borders::ALL)
@@ -1151,6 +2397,7 @@ impl NotificationScreen {
         let visible_end = std::cmp::min(scroll_offset + visible_height, filtered.len());
         let visible_items = &filtered[scroll_offset..visible_end];
 
+        let list_width = chunks[2].width.saturating_sub(2) as usize;
         let items: Vec<ListItem> = visible_items
             .iter()
             .enumerate()
@@ -1171,17 +2418,31 @@ impl NotificationScreen {
                     Style::default().fg(Color::White)
                 };
 
-                let label = format!("{}{}", prefix, name);
-                ListItem::new(Span::styled(label, style))
+                // Keep the sound in view by shortening the name first
+                let sound = form
+                    .chat_sound_overrides
+                    .get(id)
+                    .map(|sound| format!(" ♪ {}", sound))
+                    .unwrap_or_default();
+                let name_width = list_width
+                    .saturating_sub(Span::raw(prefix).width() + Span::raw(&sound).width())
+                    .max(MIN_NAME_WIDTH);
+                let label = format!("{}{}{}", prefix, truncate_to_width(name, name_width), sound);
+                ListItem::new(Span::styled(truncate_to_width(&label, list_width), style))
             })
             .collect();
 
         let list = if items.is_empty() {
             if selector.loading {
                 List::new(vec![ListItem::new(Span::styled(
-                    "Loading chats...",
+                    format!("{} Loading chats...", spinner_frame(self.spinner_frame)),
                     Style::default().fg(Color::Yellow),
                 ))])
+            } else if selector.load_error.is_some() {
+                List::new(vec![ListItem::new(Span::styled(
+                    "Couldn't load chats",
+                    Style::default().fg(Color::Red),
+                ))])
             } else {
                 List::new(vec![ListItem::new(Span::styled(
                     "No chats found",
@@ -1201,13 +2462,23 @@ impl NotificationScreen {
         } else {
             "Available Chats".to_string()
         };
+        let title = if selector.loading && !filtered.is_empty() {
+            format!("{} {} loading more...", title, spinner_frame(self.spinner_frame))
+        } else {
+            title
+        };
 
-        let list = list.block(
-            Block::default()
-                .title(title)
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan)),
-        );
+        let mut block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        if let Some(e) = &selector.load_error {
+            block = block.title_bottom(Line::styled(
+                format!(" ✗ {} (Ctrl+R to retry) ", e),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        let list = list.block(block);
 
         f.render_widget(list, chunks[2]);
     }
@@ -1242,14 +2513,14 @@ impl NotificationScreen {
             height: modal_area.height.saturating_sub(4),
         };
 
-        let is_for_time = form.loop_until == crate::notifications::LoopUntil::ForATime;
+        let extra_field = form.loop_extra_field();
 
         let mut field_constraints = vec![
             Constraint::Length(3), // 0: Loop Until
         ];
 
-        if is_for_time {
-            field_constraints.push(Constraint::Length(3)); // 1: Loop Time (only for ForATime)
+        if extra_field.is_some() {
+            field_constraints.push(Constraint::Length(3)); // 1: Loop Time or Min Unread
         }
 
         field_constraints.push(Constraint::Length(3)); // Check Interval
@@ -1265,26 +2536,26 @@ impl NotificationScreen {
             f,
             form_chunks[0],
             "Loop Until",
-            &format!("{}", form.loop_until),
+            &format!("{} (Press Enter to change)", form.loop_until),
             form.selected_field == 0,
         );
 
         let mut chunk_idx = 1;
 
-        // Field 1: Loop Time (only shown for ForATime)
-        if is_for_time {
+        // Field 1: Loop Time for ForATime, Min Unread for MessageSeen
+        if let Some((label, value)) = extra_field {
             self.render_text_field(
                 f,
                 form_chunks[chunk_idx],
-                "Loop Time (ms) *required*",
-                &form.loop_time,
+                label,
+                value,
                 form.selected_field == 1,
             );
             chunk_idx += 1;
         }
 
-        // Check Interval (field 1 or 2 depending on is_for_time)
-        let check_interval_field_idx = if is_for_time { 2 } else { 1 };
+        // Check Interval (field 1 or 2 depending on the extra field)
+        let check_interval_field_idx = chunk_idx;
         self.render_text_field(
             f,
             form_chunks[chunk_idx],
@@ -1329,23 +2600,32 @@ impl NotificationScreen {
                 Ok(false)
             }
             KeyCode::Tab | KeyCode::Down => {
-                // 3 fields: url (0), message (1), priority (2)
-                form.selected_field = (form.selected_field + 1) % 3;
+                // 8 fields: url (0), topic (1), token (2), title (3), message (4), tags (5),
+                // priority (6), fallback (7)
+                form.selected_field = (form.selected_field + 1) % 8;
                 Ok(false)
             }
             KeyCode::BackTab | KeyCode::Up => {
                 if form.selected_field > 0 {
                     form.selected_field -= 1;
                 } else {
-                    form.selected_field = 2;
+                    form.selected_field = 7;
                 }
                 Ok(false)
             }
+            KeyCode::Char(' ') if form.selected_field == 7 => {
+                form.ntfy_fallback = !form.ntfy_fallback;
+                Ok(false)
+            }
             KeyCode::Backspace => {
                 match form.selected_field {
                     0 => { form.ntfy_url.pop(); }
-                    1 => { form.ntfy_message.pop(); }
-                    2 => { form.ntfy_priority.pop(); }
+                    1 => { form.ntfy_topic.pop(); }
+                    2 => { form.ntfy_token.pop(); }
+                    3 => { form.ntfy_title.pop(); }
+                    4 => { form.ntfy_message.pop(); }
+                    5 => { form.ntfy_tags.pop(); }
+                    6 => { form.ntfy_priority.pop(); }
                     _ => {}
                 }
                 Ok(false)
@@ -1353,8 +2633,12 @@ impl NotificationScreen {
             KeyCode::Char(c) => {
                 match form.selected_field {
                     0 => form.ntfy_url.push(c),
-                    1 => form.ntfy_message.push(c),
-                    2 => if c.is_digit(10) { form.ntfy_priority.push(c) },
+                    1 => form.ntfy_topic.push(c),
+                    2 => form.ntfy_token.push(c),
+                    3 => form.ntfy_title.push(c),
+                    4 => form.ntfy_message.push(c),
+                    5 => form.ntfy_tags.push(c),
+                    6 if c.is_ascii_digit() => form.ntfy_priority.push(c),
                     _ => {}
                 }
                 Ok(false)
@@ -1363,10 +2647,215 @@ impl NotificationScreen {
         }
     }
 
+    fn handle_schedule_config_key(&mut self, key: KeyEvent) -> Result<bool> {
+        let form = match self.state {
+            ScreenState::ConfiguringSchedule(ref mut f) => f,
+            _ => return Ok(false),
+        };
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter => {
+                if key.code == KeyCode::Enter {
+                    // Validate before returning to the main form
+                    if let Err(e) = form.check_schedule() {
+                        self.message = format!("Invalid schedule: {}", e);
+                        return Ok(false);
+                    }
+                    self.message = "Schedule configured!".to_string();
+                }
+
+                let mut form_clone = form.clone();
+                form_clone.selected_field = 7;
+                self.state = if form.id.is_some() {
+                    ScreenState::EditingAutomation(form_clone)
+                } else {
+                    ScreenState::AddingAutomation(form_clone)
+                };
+                Ok(false)
+            }
+            KeyCode::Tab | KeyCode::Down | KeyCode::BackTab | KeyCode::Up => {
+                // 3 fields: days (0), hours (1), cron (2)
+                form.selected_field = match key.code {
+                    KeyCode::Tab | KeyCode::Down => (form.selected_field + 1) % 3,
+                    _ => (form.selected_field + 2) % 3,
+                };
+                Ok(false)
+            }
+            KeyCode::Backspace => {
+                match form.selected_field {
+                    0 => { form.schedule_days.pop(); }
+                    1 => { form.schedule_hours.pop(); }
+                    _ => { form.schedule_cron.pop(); }
+                }
+                self.message.clear();
+                Ok(false)
+            }
+            KeyCode::Char(c) => {
+                match form.selected_field {
+                    0 => form.schedule_days.push(c),
+                    1 => form.schedule_hours.push(c),
+                    _ => form.schedule_cron.push(c),
+                }
+                self.message.clear();
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Read-only modal with the form's plain-English summary
+    fn render_preview(&self, f: &mut Frame, size: Rect, form: &AutomationForm) {
+        if !form.previewing {
+            return;
+        }
+
+        let modal_width = (size.width * 7 / 10).max(40);
+        let modal_height = 10;
+        let modal_area = Rect {
+            x: size.width.saturating_sub(modal_width) / 2,
+            y: size.height.saturating_sub(modal_height) / 2,
+            width: modal_width.min(size.width),
+            height: modal_height.min(size.height),
+        };
+
+        let title = if form.name.is_empty() {
+            "Preview".to_string()
+        } else {
+            format!("Preview '{}'", form.name)
+        };
+
+        f.render_widget(Clear, modal_area);
+        let preview = Paragraph::new(form.summary())
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(Color::White))
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Magenta)),
+            );
+        f.render_widget(preview, modal_area);
+    }
+
+    fn render_snooze_prompt(&self, f: &mut Frame, size: Rect, input: &str) {
+        let modal_width = 50;
+        let modal_height = 9;
+        let modal_area = Rect {
+            x: size.width.saturating_sub(modal_width) / 2,
+            y: size.height.saturating_sub(modal_height) / 2,
+            width: modal_width.min(size.width),
+            height: modal_height.min(size.height),
+        };
+
+        f.render_widget(Clear, modal_area);
+        let name = self
+            .automations
+            .get(self.selected_index)
+            .map(|a| a.name.as_str())
+            .unwrap_or_default();
+        let modal_block = Block::default()
+            .title(format!("Snooze '{}'", name))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Magenta));
+        f.render_widget(modal_block, modal_area);
+
+        let inner_area = Rect {
+            x: modal_area.x + 2,
+            y: modal_area.y + 2,
+            width: modal_area.width.saturating_sub(4),
+            height: modal_area.height.saturating_sub(4),
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(1)])
+            .split(inner_area);
+
+        self.render_text_field(f, chunks[0], "Minutes", input, true);
+
+        let help_text = Paragraph::new("0 resumes a snoozed automation now")
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(help_text, chunks[1]);
+    }
+
+    fn render_schedule_config(&self, f: &mut Frame, size: Rect, form: &AutomationForm) {
+        // Calculate modal dimensions
+        let modal_width = (size.width as f32 * 0.6).max(50.0) as usize;
+        let modal_height = 17; // 3 fields + help text
+        let modal_x = (size.width as usize).saturating_sub(modal_width) / 2;
+        let modal_y = (size.height as usize).saturating_sub(modal_height) / 2;
+
+        let modal_area = Rect {
+            x: modal_x as u16,
+            y: modal_y as u16,
+            width: modal_width as u16,
+            height: modal_height as u16,
+        };
+
+        // Draw background overlay
+        f.render_widget(Clear, modal_area);
+        let modal_block = Block::default()
+            .title("Schedule Configuration")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Green));
+        f.render_widget(modal_block, modal_area);
+
+        // Create form content area
+        let inner_area = Rect {
+            x: modal_area.x + 2,
+            y: modal_area.y + 2,
+            width: modal_area.width.saturating_sub(4),
+            height: modal_area.height.saturating_sub(4),
+        };
+
+        let field_constraints = vec![
+            Constraint::Length(3), // 0: Days
+            Constraint::Length(3), // 1: Hours
+            Constraint::Length(3), // 2: Cron
+            Constraint::Min(1),    // Help text
+        ];
+
+        let form_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(field_constraints)
+            .split(inner_area);
+
+        // Field 0: Days
+        self.render_text_field(
+            f,
+            form_chunks[0],
+            "Days (e.g., mon-fri, sat,sun, daily)",
+            &form.schedule_days,
+            form.selected_field == 0,
+        );
+
+        // Field 1: Hours
+        self.render_text_field(
+            f,
+            form_chunks[1],
+            "Hours (e.g., 09:00-17:00, or 22:00-06:00 overnight)",
+            &form.schedule_hours,
+            form.selected_field == 1,
+        );
+
+        // Field 2: Cron
+        self.render_text_field(
+            f,
+            form_chunks[2],
+            "Cron (optional, overrides days and hours, e.g., * 9-17 * * mon-fri)",
+            &form.schedule_cron,
+            form.selected_field == 2,
+        );
+
+        // Help text
+        let help_text = Paragraph::new("Outside the schedule chats are still watched, but no alerts fire")
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(help_text, form_chunks[3]);
+    }
+
     fn render_ntfy_config(&self, f: &mut Frame, size: Rect, form: &AutomationForm) {
         // Calculate modal dimensions
         let modal_width = (size.width as f32 * 0.7).max(50.0) as usize;
-        let modal_height = 17; // Increased height for 3 fields + help text
+        let modal_height = std::cmp::min(30, size.height as usize); // 8 fields + help text
         let modal_x = (size.width as usize - modal_width) / 2;
         let modal_y = (size.height as usize - modal_height) / 2;
 
@@ -1395,8 +2884,13 @@ impl NotificationScreen {
 
         let field_constraints = vec![
             Constraint::Length(3), // 0: URL
-            Constraint::Length(3), // 1: Message
-            Constraint::Length(3), // 2: Priority
+            Constraint::Length(3), // 1: Topic
+            Constraint::Length(3), // 2: Token
+            Constraint::Length(3), // 3: Title
+            Constraint::Length(3), // 4: Message
+            Constraint::Length(3), // 5: Tags
+            Constraint::Length(3), // 6: Priority
+            Constraint::Length(3), // 7: Fallback
             Constraint::Min(1),    // Help text
         ];
 
@@ -1414,27 +2908,276 @@ impl NotificationScreen {
             form.selected_field == 0,
         );
 
-        // Field 1: Message
+        // Field 1: Topic
         self.render_text_field(
             f,
             form_chunks[1],
-            "Message Template (use {sender}, {chat_name}, {automation_name})",
-            &form.ntfy_message,
+            "Topic (optional, appended to the URL)",
+            &form.ntfy_topic,
             form.selected_field == 1,
         );
 
-        // Field 2: Priority
+        // Field 2: Token, masked
         self.render_text_field(
             f,
             form_chunks[2],
+            "Access Token (optional, for servers that require auth)",
+            &"*".repeat(form.ntfy_token.chars().count()),
+            form.selected_field == 2,
+        );
+
+        // Field 3: Title
+        self.render_text_field(
+            f,
+            form_chunks[3],
+            "Title Template (optional)",
+            &form.ntfy_title,
+            form.selected_field == 3,
+        );
+
+        // Field 4: Message
+        self.render_text_field(
+            f,
+            form_chunks[4],
+            "Message Template (use {sender}, {chat_name}, {message}, {unread_count}, {automation_name})",
+            &form.ntfy_message,
+            form.selected_field == 4,
+        );
+
+        // Field 5: Tags
+        self.render_text_field(
+            f,
+            form_chunks[5],
+            "Tags (optional, comma-separated, e.g. speech_balloon,work)",
+            &form.ntfy_tags,
+            form.selected_field == 5,
+        );
+
+        // Field 6: Priority
+        self.render_text_field(
+            f,
+            form_chunks[6],
             "Priority (1-5, 5 is max)",
             &form.ntfy_priority,
-            form.selected_field == 2,
+            form.selected_field == 6,
+        );
+
+        // Field 7: Fallback
+        self.render_bool_field(
+            f,
+            form_chunks[7],
+            "Fall Back Here And To Desktop When No Sound Can Play (Space to toggle)",
+            form.ntfy_fallback,
+            form.selected_field == 7,
         );
 
         // Help text
-        let help_text = Paragraph::new("Variables: {sender}, {chat_name}, {automation_name} | Priority: 5 (max), 1 (min)")
+        let help_text = Paragraph::new("Variables: {sender}, {chat_name}, {chat_id}, {message}, {unread_count}, {automation_name} | Priority: 5 (max), 1 (min)")
             .style(Style::default().fg(Color::DarkGray));
-        f.render_widget(help_text, form_chunks[3]);
+        f.render_widget(help_text, form_chunks[8]);
+    }
+}
+
+/// Local time a snooze ends, with the weekday when it isn't today
+fn format_snooze_end(until: std::time::SystemTime) -> String {
+    let until: chrono::DateTime<chrono::Local> = until.into();
+    if until.date_naive() == chrono::Local::now().date_naive() {
+        until.format("%H:%M").to_string()
+    } else {
+        until.format("%a %H:%M").to_string()
+    }
+}
+
+/// The bundled sound after `current`, wrapping around to no sound after the last
+fn next_builtin_sound(current: &str) -> String {
+    let settings: Vec<String> = crate::notifications::sounds::builtin_settings().collect();
+    match settings.iter().position(|setting| setting == current) {
+        Some(i) => settings.get(i + 1).cloned().unwrap_or_default(),
+        None => settings[0].clone(),
+    }
+}
+
+/// `None` for a blank input, otherwise the trimmed text
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Compact "how long ago", e.g. "just now", "5m ago", "3h ago", "2d ago"
+fn format_elapsed(elapsed: chrono::Duration) -> String {
+    if elapsed.num_minutes() < 1 {
+        "just now".to_string()
+    } else if elapsed.num_hours() < 1 {
+        format!("{}m ago", elapsed.num_minutes())
+    } else if elapsed.num_days() < 1 {
+        format!("{}h ago", elapsed.num_hours())
+    } else {
+        format!("{}d ago", elapsed.num_days())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_state::SharedAppState;
+    use crate::config::Config;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_down_on_empty_list() {
+        let mut screen = NotificationScreen::new(SharedAppState::new(Config::default()));
+        assert!(screen.automations.is_empty());
+
+        assert!(!screen.handle_list_key(key(KeyCode::Down)).unwrap());
+        assert_eq!(screen.selected_index, 0);
+    }
+
+    #[test]
+    fn test_delete_last_remaining_automation() {
+        let mut screen = NotificationScreen::new(SharedAppState::new(Config::default()));
+        screen.automations = vec![
+            NotificationAutomation::new("a".to_string(), "A".to_string(), vec![]),
+            NotificationAutomation::new("b".to_string(), "B".to_string(), vec![]),
+        ];
+        screen.selected_index = 1;
+
+        assert_eq!(screen.remove_selected().unwrap().id, "b");
+        assert_eq!(screen.selected_index, 0);
+        assert_eq!(screen.remove_selected().unwrap().id, "a");
+        assert_eq!(screen.selected_index, 0);
+        assert!(screen.remove_selected().is_none());
+
+        // Navigating the empty list leaves the selection alone and doesn't panic
+        for code in [KeyCode::Up, KeyCode::Down, KeyCode::Enter] {
+            assert!(!screen.handle_list_key(key(code)).unwrap());
+            assert_eq!(screen.selected_index, 0);
+            assert!(matches!(screen.state, ScreenState::List));
+        }
+    }
+
+    #[test]
+    fn test_undo_restores_deleted_automation_in_place() {
+        // Deleting and undoing save the config; keep that out of the real one
+        let dir = std::env::temp_dir().join(format!("beeper-undo-{}", uuid::Uuid::new_v4()));
+        Config::set_config_dir(dir.clone());
+
+        let mut screen = NotificationScreen::new(SharedAppState::new(Config::default()));
+        screen.automations = ["a", "b", "c"]
+            .iter()
+            .map(|id| NotificationAutomation::new(id.to_string(), id.to_uppercase(), vec![]))
+            .collect();
+        screen.selected_index = 1;
+        let ids = |screen: &NotificationScreen| -> Vec<String> {
+            screen.automations.iter().map(|a| a.id.clone()).collect()
+        };
+
+        screen.handle_list_key(key(KeyCode::Char('d'))).unwrap();
+        assert_eq!(ids(&screen), ["a", "c"]);
+        screen.handle_list_key(key(KeyCode::Down)).unwrap();
+        screen.handle_list_key(key(KeyCode::Char('u'))).unwrap();
+        assert_eq!(ids(&screen), ["a", "b", "c"]);
+        assert_eq!(screen.selected_index, 1);
+        assert_eq!(screen.message, "✓ Restored B");
+
+        // Only one level, and another action ends it
+        screen.handle_list_key(key(KeyCode::Char('u'))).unwrap();
+        assert_eq!(ids(&screen), ["a", "b", "c"]);
+        screen.handle_list_key(key(KeyCode::Char('d'))).unwrap();
+        screen.handle_list_key(key(KeyCode::Char('n'))).unwrap();
+        screen.state = ScreenState::List;
+        screen.handle_list_key(key(KeyCode::Char('u'))).unwrap();
+        assert_eq!(ids(&screen), ["a", "c"]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_plus_minus_steps_loop_check_interval() {
+        let mut screen = NotificationScreen::new(SharedAppState::new(Config::default()));
+        let mut form = AutomationForm::new(1000);
+        form.automation_type = crate::notifications::AutomationType::Loop;
+        form.selected_field = 2;
+        screen.state = ScreenState::AddingAutomation(form);
+
+        let interval = |screen: &NotificationScreen| match &screen.state {
+            ScreenState::AddingAutomation(form) => form.check_interval.clone(),
+            _ => panic!("left the form"),
+        };
+        screen.handle_form_key(key(KeyCode::Char('+'))).unwrap();
+        assert_eq!(interval(&screen), "1500");
+        for _ in 0..4 {
+            screen.handle_form_key(key(KeyCode::Char('-'))).unwrap();
+        }
+        assert_eq!(interval(&screen), "500", "never below one step");
+    }
+
+    #[test]
+    fn test_esc_asks_before_discarding_changes() {
+        let mut screen = NotificationScreen::new(SharedAppState::new(Config::default()));
+        screen.state = ScreenState::AddingAutomation(AutomationForm::new(3000));
+
+        // An untouched form closes straight away
+        screen.handle_form_key(key(KeyCode::Esc)).unwrap();
+        assert!(matches!(screen.state, ScreenState::List));
+
+        screen.state = ScreenState::AddingAutomation(AutomationForm::new(3000));
+        screen.handle_form_key(key(KeyCode::Char('W'))).unwrap();
+        screen.handle_form_key(key(KeyCode::Esc)).unwrap();
+        assert!(matches!(screen.state, ScreenState::AddingAutomation(_)));
+        screen.handle_form_key(key(KeyCode::Char('n'))).unwrap();
+        assert!(matches!(screen.state, ScreenState::AddingAutomation(_)));
+
+        screen.handle_form_key(key(KeyCode::Esc)).unwrap();
+        screen.handle_form_key(key(KeyCode::Char('y'))).unwrap();
+        assert!(matches!(screen.state, ScreenState::List));
+    }
+
+    #[test]
+    fn test_esc_before_first_chat_page_is_not_cached() {
+        let mut screen = NotificationScreen::new(SharedAppState::new(Config::default()));
+        let mut selector = ChatSelector::new();
+        selector.loading = true;
+        screen.state = ScreenState::SelectingChats(AutomationForm::new(3000), selector);
+
+        screen.handle_chat_selector_key(key(KeyCode::Esc)).unwrap();
+        assert!(matches!(screen.state, ScreenState::AddingAutomation(_)));
+        assert!(screen.chat_cache.is_none());
+
+        let mut selector = ChatSelector::new();
+        selector.available_chats = vec![("c1".to_string(), "Chat".to_string())];
+        selector.has_more = false;
+        screen.state = ScreenState::SelectingChats(AutomationForm::new(3000), selector);
+        screen.handle_chat_selector_key(key(KeyCode::Esc)).unwrap();
+        assert!(screen.chat_cache.is_some());
+    }
+
+    #[test]
+    fn test_failed_chat_load_is_shown_and_not_cached() {
+        let mut screen = NotificationScreen::new(SharedAppState::new(Config::default()));
+        let mut selector = ChatSelector::new();
+        selector.loading = true;
+        screen.state = ScreenState::SelectingChats(AutomationForm::new(3000), selector);
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send(Err("Beeper is not running".to_string())).unwrap();
+        screen.chat_load_rx = Some(rx);
+
+        screen.poll_chat_load();
+        match &screen.state {
+            ScreenState::SelectingChats(_, selector) => {
+                assert!(!selector.loading);
+                assert_eq!(
+                    selector.load_error.as_deref(),
+                    Some("Beeper is not running")
+                );
+            }
+            _ => panic!("left the selector"),
+        }
+
+        // Reopening tries again instead of showing an empty list
+        screen.handle_chat_selector_key(key(KeyCode::Esc)).unwrap();
+        assert!(screen.chat_cache.is_none());
     }
 }
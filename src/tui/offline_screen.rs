@@ -0,0 +1,114 @@
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    Frame, Terminal,
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+/// What the user chose on the offline screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OfflineAction {
+    Retry,
+    EditCredentials,
+    Quit,
+}
+
+/// Shown when nothing answers at the API URL, so users with correct
+/// credentials are told to start Beeper instead of being sent back to
+/// credential entry
+pub struct OfflineScreen {
+    url: String,
+    /// Whether a Beeper process was found, where that can be checked
+    process_running: Option<bool>,
+}
+
+impl OfflineScreen {
+    pub fn new(url: String, process_running: Option<bool>) -> Self {
+        Self {
+            url,
+            process_running,
+        }
+    }
+
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<OfflineAction> {
+        use crossterm::event::{self, Event};
+
+        loop {
+            terminal.draw(|f| self.ui(f))?;
+
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && let Some(action) = self.handle_key(key)
+            {
+                return Ok(action);
+            }
+        }
+    }
+
+    fn handle_key(&self, key: KeyEvent) -> Option<OfflineAction> {
+        match key.code {
+            KeyCode::Char('r') | KeyCode::Char('R') | KeyCode::Enter => Some(OfflineAction::Retry),
+            KeyCode::Char('e') | KeyCode::Char('E') => Some(OfflineAction::EditCredentials),
+            KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => Some(OfflineAction::Quit),
+            _ => None,
+        }
+    }
+
+    fn explanation(&self) -> String {
+        match self.process_running {
+            Some(true) => format!(
+                "Beeper Desktop is running, but nothing answers at {}. \
+                 Enable the Desktop API in Beeper's settings, then press R to retry.",
+                self.url
+            ),
+            _ => format!(
+                "Beeper Desktop doesn't appear to be running. \
+                 Start it and press R to retry. (Tried {})",
+                self.url
+            ),
+        }
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        let size = f.area();
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(30),
+                Constraint::Min(7),
+                Constraint::Percentage(30),
+            ])
+            .split(size);
+
+        let text = vec![
+            Line::from(Span::styled(
+                "Can't reach Beeper Desktop",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(self.explanation()),
+            Line::from(""),
+            Line::from(Span::styled(
+                "R: Retry | E: Edit URL/token | Q/Esc: Quit",
+                Style::default().fg(Color::Gray),
+            )),
+        ];
+
+        let body = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow)),
+            );
+        f.render_widget(body, chunks[1]);
+    }
+}
@@ -0,0 +1,378 @@
+use crate::app_state::SharedAppState;
+use crate::notifications::{CronSchedule, ScheduledFocus};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    Frame, Terminal,
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+};
+
+/// Smallest terminal that fits the header, the edit form and the footer
+const MIN_WIDTH: u16 = 50;
+const MIN_HEIGHT: u16 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Name,
+    ChatId,
+    Cron,
+}
+
+impl Field {
+    fn next(self) -> Self {
+        match self {
+            Field::Name => Field::ChatId,
+            Field::ChatId => Field::Cron,
+            Field::Cron => Field::Name,
+        }
+    }
+}
+
+/// An entry being added or edited
+struct Form {
+    /// Index of the entry being edited, `None` when adding
+    index: Option<usize>,
+    name: String,
+    chat_id: String,
+    cron: String,
+    field: Field,
+}
+
+impl Form {
+    fn input(&mut self) -> &mut String {
+        match self.field {
+            Field::Name => &mut self.name,
+            Field::ChatId => &mut self.chat_id,
+            Field::Cron => &mut self.cron,
+        }
+    }
+}
+
+pub struct ScheduledFocusScreen {
+    app_state: SharedAppState,
+    entries: Vec<ScheduledFocus>,
+    selected_index: usize,
+    form: Option<Form>,
+    message: String,
+}
+
+impl ScheduledFocusScreen {
+    pub fn new(app_state: SharedAppState) -> Self {
+        let entries = app_state
+            .with_config(|config| config.notifications.scheduled_focus.clone())
+            .unwrap_or_default();
+
+        Self {
+            app_state,
+            entries,
+            selected_index: 0,
+            form: None,
+            message: String::new(),
+        }
+    }
+
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        use crossterm::event::{self, Event};
+
+        loop {
+            terminal.draw(|f| self.ui(f))?;
+
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press && self.handle_key(key) => {
+                    return Ok(());
+                }
+                Event::Paste(text) => {
+                    if let Some(form) = &mut self.form {
+                        form.input().push_str(text.trim());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Handle a key press; returns true to leave the screen
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if self.form.is_some() {
+            self.handle_form_key(key);
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return true,
+            KeyCode::Up => {
+                self.selected_index = self.selected_index.saturating_sub(1);
+            }
+            KeyCode::Down if self.selected_index + 1 < self.entries.len() => {
+                self.selected_index += 1;
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                self.form = Some(Form {
+                    index: None,
+                    name: String::new(),
+                    chat_id: String::new(),
+                    cron: String::new(),
+                    field: Field::Name,
+                });
+                self.message.clear();
+            }
+            KeyCode::Enter | KeyCode::Char('e') | KeyCode::Char('E') => {
+                if let Some(entry) = self.entries.get(self.selected_index) {
+                    self.form = Some(Form {
+                        index: Some(self.selected_index),
+                        name: entry.name.clone(),
+                        chat_id: entry.chat_id.clone(),
+                        cron: entry.cron.clone(),
+                        field: Field::Name,
+                    });
+                    self.message.clear();
+                }
+            }
+            KeyCode::Char(' ') => {
+                if let Some(entry) = self.entries.get_mut(self.selected_index) {
+                    entry.enabled = !entry.enabled;
+                    let state = if entry.enabled { "enabled" } else { "disabled" };
+                    let name = entry.name.clone();
+                    self.save(format!("✓ '{}' {}", name, state));
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete
+                if self.selected_index < self.entries.len() =>
+            {
+                let removed = self.entries.remove(self.selected_index);
+                self.selected_index = self
+                    .selected_index
+                    .min(self.entries.len().saturating_sub(1));
+                self.save(format!("✓ Deleted '{}'", removed.name));
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn handle_form_key(&mut self, key: KeyEvent) {
+        let Some(form) = &mut self.form else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc => {
+                self.form = None;
+                self.message.clear();
+            }
+            KeyCode::Tab | KeyCode::Down => form.field = form.field.next(),
+            KeyCode::BackTab | KeyCode::Up => form.field = form.field.next().next(),
+            KeyCode::Backspace => {
+                form.input().pop();
+            }
+            KeyCode::Char(c) => form.input().push(c),
+            KeyCode::Enter => self.submit_form(),
+            _ => {}
+        }
+    }
+
+    /// Check the form and store it as a new or updated entry
+    fn submit_form(&mut self) {
+        let Some(form) = &self.form else {
+            return;
+        };
+
+        let name = form.name.trim().to_string();
+        let chat_id = form.chat_id.trim().to_string();
+        let cron = form.cron.split_whitespace().collect::<Vec<_>>().join(" ");
+        if name.is_empty() {
+            self.message = "✗ Give the entry a name".to_string();
+            return;
+        }
+        if chat_id.is_empty() {
+            self.message = "✗ Enter the ID of the chat to open".to_string();
+            return;
+        }
+        if let Err(e) = cron.parse::<CronSchedule>() {
+            self.message = format!("✗ Invalid cron expression: {}", e);
+            return;
+        }
+
+        match form.index {
+            Some(index) => {
+                let entry = &mut self.entries[index];
+                entry.name = name.clone();
+                entry.chat_id = chat_id;
+                entry.cron = cron;
+            }
+            None => {
+                self.entries
+                    .push(ScheduledFocus::new(name.clone(), chat_id, cron));
+                self.selected_index = self.entries.len() - 1;
+            }
+        }
+        self.form = None;
+        self.save(format!("✓ Saved '{}'", name));
+    }
+
+    /// Write the entries to the config and save it, reporting `success` or the error
+    fn save(&mut self, success: String) {
+        let entries = self.entries.clone();
+        let result = self
+            .app_state
+            .with_config_mut(|config| config.notifications.scheduled_focus = entries)
+            .and_then(|()| self.app_state.save_config());
+
+        self.message = match result {
+            Ok(()) => success,
+            Err(e) => format!("✗ Error saving configuration: {}", e),
+        };
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        if super::render_too_small(f, MIN_WIDTH, MIN_HEIGHT) {
+            return;
+        }
+        let size = f.area();
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(3),
+                    Constraint::Min(6),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
+            .split(size);
+
+        // Header
+        let header = Paragraph::new(vec![
+            Line::from(vec![Span::styled(
+                "Scheduled Focus",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )]),
+            Line::from(Span::styled(
+                "Open a chat in Beeper at set times, whether or not anything new arrived",
+                Style::default().fg(Color::Gray),
+            )),
+        ]);
+        f.render_widget(header, chunks[0]);
+
+        match &self.form {
+            Some(form) => self.render_form(f, form, chunks[1]),
+            None => self.render_list(f, chunks[1]),
+        }
+
+        // Footer with help text
+        let (footer_text, footer_style) = if !self.message.is_empty() {
+            let color = if self.message.starts_with('✗') {
+                Color::Red
+            } else {
+                Color::Green
+            };
+            (self.message.clone(), Style::default().fg(color))
+        } else if self.form.is_some() {
+            (
+                "Tab/↑↓: Switch Field | Enter: Save | Esc: Cancel".to_string(),
+                Style::default().fg(Color::Gray),
+            )
+        } else {
+            (
+                "↑↓: Navigate | A: Add | Enter: Edit | Space: Enable/Disable | D: Delete | Esc: Back"
+                    .to_string(),
+                Style::default().fg(Color::Gray),
+            )
+        };
+        f.render_widget(Paragraph::new(footer_text).style(footer_style), chunks[2]);
+    }
+
+    fn render_list(&self, f: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| {
+                let style = if idx == self.selected_index {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else if entry.enabled {
+                    Style::default().fg(Color::White)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+                let label = format!(
+                    "  [{}] {}  {}  → {}",
+                    if entry.enabled { "✓" } else { "✗" },
+                    entry.cron,
+                    entry.name,
+                    entry.chat_id
+                );
+                ListItem::new(Span::styled(label, style))
+            })
+            .collect();
+
+        let list = if items.is_empty() {
+            List::new(vec![ListItem::new(Span::styled(
+                "No scheduled focus yet. Press A to add one.",
+                Style::default().fg(Color::DarkGray),
+            ))])
+        } else {
+            List::new(items)
+        };
+
+        let list = list.block(
+            Block::default()
+                .title(format!("Entries ({})", self.entries.len()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+        f.render_widget(list, area);
+    }
+
+    fn render_form(&self, f: &mut Frame, form: &Form, area: Rect) {
+        let field_line = |label: &str, value: &str, field: Field| {
+            let active = form.field == field;
+            let style = if active {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            Line::from(vec![
+                Span::styled(format!("{:<10}", label), style),
+                Span::styled(format!("{}{}", value, if active { "_" } else { "" }), style),
+            ])
+        };
+
+        let lines = vec![
+            field_line("Name", &form.name, Field::Name),
+            Line::from(""),
+            field_line("Chat ID", &form.chat_id, Field::ChatId),
+            Line::from(""),
+            field_line("Cron", &form.cron, Field::Cron),
+            Line::from(Span::styled(
+                "          minute hour day month weekday, e.g. 55 9 * * mon-fri",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
+
+        let title = if form.index.is_some() {
+            "Edit Scheduled Focus"
+        } else {
+            "Add Scheduled Focus"
+        };
+        let form_widget = Paragraph::new(lines).block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+        f.render_widget(form_widget, area);
+    }
+}
@@ -0,0 +1,379 @@
+use crate::config::{Config, ImportMode};
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    Frame, Terminal,
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use std::path::PathBuf;
+
+/// Whether the screen writes the configuration out or reads one in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransferKind {
+    Export,
+    Import,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InputField {
+    Path,
+    Option,
+}
+
+pub struct TransferScreen {
+    config: Config,
+    kind: TransferKind,
+    active_field: InputField,
+    path_input: String,
+    include_token: bool,
+    import_mode: ImportMode,
+    /// Parsed import waiting on a decision about automations with clashing names
+    pending_import: Option<(Config, Vec<String>)>,
+    done: bool,
+    message: String,
+}
+
+impl TransferScreen {
+    pub fn new(config: Config, kind: TransferKind) -> Self {
+        let path_input = dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("beeper-automations-export.toml")
+            .to_string_lossy()
+            .to_string();
+
+        Self {
+            config,
+            kind,
+            active_field: InputField::Path,
+            path_input,
+            include_token: false,
+            import_mode: ImportMode::Merge,
+            pending_import: None,
+            done: false,
+            message: String::new(),
+        }
+    }
+
+    /// Returns the updated configuration after a successful import
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<Option<Config>> {
+        use crossterm::event::{self, Event};
+
+        loop {
+            terminal.draw(|f| self.ui(f))?;
+
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && self.handle_key(key)
+            {
+                break;
+            }
+        }
+
+        if self.done && self.kind == TransferKind::Import {
+            Ok(Some(self.config.clone()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        // Any key leaves once the transfer has finished
+        if self.done {
+            return true;
+        }
+
+        if let Some((imported, _)) = &self.pending_import {
+            match key.code {
+                KeyCode::Char('o') | KeyCode::Char('O') => {
+                    let imported = imported.clone();
+                    self.pending_import = None;
+                    self.apply_import(imported, true);
+                }
+                KeyCode::Char('s') | KeyCode::Char('S') => {
+                    let imported = imported.clone();
+                    self.pending_import = None;
+                    self.apply_import(imported, false);
+                }
+                KeyCode::Esc => {
+                    self.pending_import = None;
+                    self.message = "Import cancelled".to_string();
+                }
+                _ => {}
+            }
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Tab | KeyCode::Up | KeyCode::Down => {
+                self.active_field = match self.active_field {
+                    InputField::Path => InputField::Option,
+                    InputField::Option => InputField::Path,
+                };
+                false
+            }
+            KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right
+                if self.active_field == InputField::Option =>
+            {
+                match self.kind {
+                    TransferKind::Export => self.include_token = !self.include_token,
+                    TransferKind::Import => {
+                        self.import_mode = match self.import_mode {
+                            ImportMode::Merge => ImportMode::Replace,
+                            ImportMode::Replace => ImportMode::Merge,
+                        }
+                    }
+                }
+                self.message.clear();
+                false
+            }
+            KeyCode::Backspace if self.active_field == InputField::Path => {
+                self.path_input.pop();
+                self.message.clear();
+                false
+            }
+            KeyCode::Char(c) if self.active_field == InputField::Path => {
+                self.path_input.push(c);
+                self.message.clear();
+                false
+            }
+            KeyCode::Enter => {
+                if self.path_input.trim().is_empty() {
+                    self.message = "Please enter a file path".to_string();
+                } else {
+                    match self.kind {
+                        TransferKind::Export => self.export(),
+                        TransferKind::Import => self.import(),
+                    }
+                }
+                false
+            }
+            KeyCode::Esc => true,
+            _ => false,
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        PathBuf::from(self.path_input.trim())
+    }
+
+    fn export(&mut self) {
+        match self.config.export_to(&self.path(), self.include_token) {
+            Ok(()) => {
+                self.message = format!(
+                    "✓ Exported {} automation(s) to {}. Press any key to return.",
+                    self.config.notifications.automations.len(),
+                    self.path().display()
+                );
+                self.done = true;
+            }
+            Err(e) => self.message = format!("✗ Export failed: {}", e),
+        }
+    }
+
+    fn import(&mut self) {
+        let imported = match Config::import_from(&self.path()) {
+            Ok(imported) => imported,
+            Err(e) => {
+                self.message = format!("✗ Could not import {}: {}", self.path().display(), e);
+                return;
+            }
+        };
+
+        let conflicts = self.config.conflicting_automations(&imported);
+        if self.import_mode == ImportMode::Merge && !conflicts.is_empty() {
+            self.pending_import = Some((imported, conflicts));
+        } else {
+            self.apply_import(imported, false);
+        }
+    }
+
+    fn apply_import(&mut self, imported: Config, overwrite_conflicts: bool) {
+        let count = imported.notifications.automations.len();
+        let mut updated = self.config.clone();
+        updated.import_automations(imported, self.import_mode, overwrite_conflicts);
+
+        match updated.save() {
+            Ok(()) => {
+                self.config = updated;
+                self.message = format!(
+                    "✓ Imported {} automation(s). Press any key to return.",
+                    count
+                );
+                self.done = true;
+            }
+            Err(e) => self.message = format!("✗ Error saving configuration: {}", e),
+        }
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        let size = f.area();
+
+        // Main vertical layout
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(3),
+                    Constraint::Min(10),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
+            .split(size);
+
+        // Header
+        let title = match self.kind {
+            TransferKind::Export => "Export Configuration",
+            TransferKind::Import => "Import Configuration",
+        };
+        let header = Paragraph::new(vec![
+            Line::from(vec![Span::styled(
+                title,
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )]),
+            Line::from(""),
+        ]);
+        f.render_widget(header, chunks[0]);
+
+        // Form area
+        let form_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Min(0),
+                ]
+                .as_ref(),
+            )
+            .split(chunks[1]);
+
+        self.render_input_field(
+            f,
+            form_chunks[0],
+            "File Path",
+            &self.path_input,
+            self.active_field == InputField::Path,
+        );
+
+        let (option_label, option_value) = match self.kind {
+            TransferKind::Export => (
+                "Include API Token",
+                if self.include_token {
+                    "[✓] Yes - keep this file private"
+                } else {
+                    "[ ] No - safe to share"
+                },
+            ),
+            TransferKind::Import => (
+                "Import Mode",
+                match self.import_mode {
+                    ImportMode::Merge => "Merge - keep existing automations",
+                    ImportMode::Replace => "Replace - discard existing automations",
+                },
+            ),
+        };
+        self.render_input_field(
+            f,
+            form_chunks[1],
+            option_label,
+            option_value,
+            self.active_field == InputField::Option,
+        );
+
+        if let Some((_, conflicts)) = &self.pending_import {
+            self.render_conflict_prompt(f, form_chunks[2], conflicts);
+        }
+
+        // Message or help text area
+        let message_text = if !self.message.is_empty() {
+            self.message.clone()
+        } else if self.pending_import.is_some() {
+            "O: Overwrite existing | S: Skip conflicting | Esc: Cancel".to_string()
+        } else {
+            "Tab: Switch field | Space: Toggle option | Enter: Confirm | Esc: Back".to_string()
+        };
+
+        let message_style = if self.message.starts_with('✓') {
+            Style::default().fg(Color::Green)
+        } else if self.message.starts_with('✗') {
+            Style::default().fg(Color::Red)
+        } else if !self.message.is_empty() {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+
+        let help = Paragraph::new(message_text).style(message_style);
+        f.render_widget(help, chunks[2]);
+    }
+
+    fn render_conflict_prompt(&self, f: &mut Frame, area: Rect, conflicts: &[String]) {
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!(
+                    "{} automation(s) already exist with the same name:",
+                    conflicts.len()
+                ),
+                Style::default().fg(Color::Yellow),
+            )),
+            Line::from(""),
+        ];
+        lines.extend(conflicts.iter().map(|name| Line::from(format!("  • {}", name))));
+        lines.push(Line::from(""));
+        lines.push(Line::from("Overwrite them with the imported versions, or skip them?"));
+
+        let prompt = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title("Conflicts")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow)),
+            )
+            .wrap(Wrap { trim: false });
+
+        f.render_widget(prompt, area);
+    }
+
+    fn render_input_field(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        label: &str,
+        value: &str,
+        active: bool,
+    ) {
+        let border_color = if active { Color::Cyan } else { Color::White };
+        let style = if active {
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+
+        let block = Block::default()
+            .title(label)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color));
+
+        let display_value = if active && value.is_empty() {
+            "_".to_string()
+        } else {
+            value.to_string()
+        };
+
+        let content = Paragraph::new(display_value)
+            .block(block)
+            .style(style)
+            .alignment(Alignment::Left);
+
+        f.render_widget(content, area);
+    }
+}